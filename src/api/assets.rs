@@ -1,20 +1,43 @@
-use crate::api::ApiClient;
-use crate::error::Result;
+use crate::api::datasets::DownloadUrlResponse;
+use crate::api::jobs::JobStatus;
+use crate::api::pagination::{Page, PageRequest};
+use crate::api::{ApiClient, ProgressCallback};
+use crate::config::OperationClass;
+use crate::error::{CliError, Result};
+use crate::time::parse_rfc3339;
+use crate::validate::MeshStats;
+use clap::ValueEnum;
 use reqwest::multipart::{Form, Part};
+use reqwest::{Body, Method};
+use futures::TryStreamExt;
+use globset::{GlobBuilder, GlobMatcher};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
 use std::path::Path;
 use tokio::fs::File;
 use tokio::io::AsyncReadExt;
+use tokio_util::io::ReaderStream;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Asset {
     pub id: i32,
     pub asset_id: String,
     pub name: String,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
     pub file_type: String,
     pub size_bytes: i64,
     pub thumbnail_url: Option<String>,
     pub created_at: String,
+    /// SHA-256 of the uploaded file, if the server recorded one
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Geometry metrics recorded at upload, for meshes uploaded with them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mesh: Option<MeshStats>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -23,11 +46,205 @@ pub struct AssetListResponse {
     pub total: i32,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AssetUploadMetadata {
     pub name: String,
     pub category: Option<String>,
     pub tags: Vec<String>,
+    /// SHA-256 of the file, so the server can find duplicates later
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// Geometry metrics from `zere assets inspect`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mesh: Option<MeshStats>,
+}
+
+/// Changes to an asset's metadata; fields left as `None` are not touched
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AssetUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// The full new tag list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+}
+
+impl AssetUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.category.is_none() && self.tags.is_none()
+    }
+}
+
+/// A job whose config references an asset
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AssetJobRef {
+    pub job_id: String,
+    pub name: String,
+    pub status: JobStatus,
+}
+
+/// A saved scene config that references an asset
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AssetConfigRef {
+    pub config_id: String,
+    pub name: String,
+}
+
+/// Everything on the server that references an asset
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct AssetUsage {
+    #[serde(default)]
+    pub jobs: Vec<AssetJobRef>,
+    #[serde(default)]
+    pub configs: Vec<AssetConfigRef>,
+}
+
+impl AssetUsage {
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty() && self.configs.is_empty()
+    }
+
+    /// Jobs that are queued, running or paused, and may still need the asset
+    pub fn active_jobs(&self) -> impl Iterator<Item = &AssetJobRef> {
+        self.jobs.iter().filter(|job| !job.status.is_terminal())
+    }
+}
+
+/// What `assets list` can sort by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum AssetSort {
+    Name,
+    Size,
+    Created,
+}
+
+/// Filters and ordering for listing assets.
+///
+/// Everything except the name glob is sent to the server as query parameters. Servers
+/// that predate a parameter ignore it, so results should still be checked with
+/// [`AssetQuery::matches`] and [`AssetQuery::is_sorted`].
+#[derive(Debug, Clone, Default)]
+pub struct AssetQuery {
+    pub file_type: Option<String>,
+    pub category: Option<String>,
+    pub tag: Option<String>,
+    /// Case-insensitive glob on the asset name; the API cannot apply this one
+    pub name: Option<GlobMatcher>,
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    /// Created at or after this `YYYY-MM-DD` date or RFC 3339 timestamp
+    pub created_after: Option<String>,
+    /// Created before this `YYYY-MM-DD` date or RFC 3339 timestamp
+    pub created_before: Option<String>,
+    pub sort: Option<AssetSort>,
+    pub descending: bool,
+}
+
+impl AssetQuery {
+    /// Set the name filter from a glob such as `chair*`
+    pub fn name_glob(mut self, pattern: &str) -> Result<Self> {
+        let glob = GlobBuilder::new(pattern)
+            .case_insensitive(true)
+            .build()
+            .map_err(|e| CliError::Other(format!("Invalid name pattern '{}': {}", pattern, e.kind())))?;
+        self.name = Some(glob.compile_matcher());
+        Ok(self)
+    }
+
+    /// Whether the query has any filter or ordering at all
+    pub fn is_empty(&self) -> bool {
+        self.params().is_empty() && self.name.is_none()
+    }
+
+    /// Whether some filter can only be applied client-side
+    pub fn needs_client_filtering(&self) -> bool {
+        self.name.is_some()
+    }
+
+    /// Query parameters for the filters the API understands
+    pub fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        let mut push = |key, value: Option<String>| {
+            if let Some(value) = value {
+                params.push((key, value));
+            }
+        };
+        push("type", self.file_type.clone());
+        push("category", self.category.clone());
+        push("tag", self.tag.clone());
+        push("min_size", self.min_size.map(|size| size.to_string()));
+        push("max_size", self.max_size.map(|size| size.to_string()));
+        push("created_after", self.created_after.clone());
+        push("created_before", self.created_before.clone());
+        push("sort", self.sort.and_then(|sort| sort.to_possible_value()).map(|value| value.get_name().to_string()));
+        if self.sort.is_some() && self.descending {
+            push("order", Some("desc".to_string()));
+        }
+        params
+    }
+
+    /// Whether an asset passes every filter
+    pub fn matches(&self, asset: &Asset) -> bool {
+        let size = asset.size_bytes.max(0) as u64;
+        self.file_type.as_ref().is_none_or(|t| asset.file_type.eq_ignore_ascii_case(t))
+            && (self.category.is_none() || asset.category == self.category)
+            && self.tag.as_ref().is_none_or(|tag| asset.tags.contains(tag))
+            && self.name.as_ref().is_none_or(|glob| glob.is_match(&asset.name))
+            && self.min_size.is_none_or(|min| size >= min)
+            && self.max_size.is_none_or(|max| size <= max)
+            && self.created_after.as_ref().is_none_or(|after| compare_times(&asset.created_at, after).is_ge())
+            && self.created_before.as_ref().is_none_or(|before| compare_times(&asset.created_at, before).is_lt())
+    }
+
+    /// Order of two assets under the requested sort; everything is equal without one
+    pub fn compare(&self, a: &Asset, b: &Asset) -> Ordering {
+        let ordering = match self.sort {
+            None => return Ordering::Equal,
+            Some(AssetSort::Name) => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            Some(AssetSort::Size) => a.size_bytes.cmp(&b.size_bytes),
+            Some(AssetSort::Created) => compare_times(&a.created_at, &b.created_at),
+        };
+        if self.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+
+    pub fn sort(&self, assets: &mut [Asset]) {
+        assets.sort_by(|a, b| self.compare(a, b));
+    }
+
+    pub fn is_sorted(&self, assets: &[Asset]) -> bool {
+        assets.windows(2).all(|pair| self.compare(&pair[0], &pair[1]) != Ordering::Greater)
+    }
+}
+
+/// Order of two timestamps by the instant they denote, whatever their offset or precision.
+///
+/// Values that are not RFC 3339 fall back to comparing as strings.
+fn compare_times(a: &str, b: &str) -> Ordering {
+    match (parse_rfc3339(a), parse_rfc3339(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+/// Hex SHA-256 of a file's contents, read in chunks
+pub async fn file_sha256(path: &Path) -> Result<String> {
+    let mut file = File::open(path).await?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
 }
 
 impl ApiClient {
@@ -38,24 +255,58 @@ impl ApiClient {
         Ok(response.assets)
     }
 
+    /// List a single page of assets
+    pub async fn list_assets_page(&self, page: PageRequest) -> Result<Page<Asset>> {
+        let req = self.get("/api/v1/assets").query(&page.query());
+        let response: AssetListResponse = self.send_json(req).await?;
+        Ok(Page {
+            items: response.assets,
+            total: response.total,
+            offset: page.offset,
+        })
+    }
+
+    /// List a single page of assets matching `query`
+    pub async fn search_assets_page(&self, query: &AssetQuery, page: PageRequest) -> Result<Page<Asset>> {
+        let req = self.get("/api/v1/assets").query(&page.query()).query(&query.params());
+        let response: AssetListResponse = self.send_json(req).await?;
+        Ok(Page {
+            items: response.assets,
+            total: response.total,
+            offset: page.offset,
+        })
+    }
+
+    /// Assets whose file has the given SHA-256
+    pub async fn find_assets_by_hash(&self, sha256: &str) -> Result<Vec<Asset>> {
+        let req = self.get("/api/v1/assets").query(&[("sha256", sha256)]);
+        let response: AssetListResponse = self.send_json(req).await?;
+        // Guard against a server that ignores the filter and returns everything
+        Ok(response
+            .assets
+            .into_iter()
+            .filter(|asset| asset.sha256.as_deref() == Some(sha256))
+            .collect())
+    }
+
     /// Get asset details
     pub async fn get_asset(&self, asset_id: &str) -> Result<Asset> {
         let req = self.get(&format!("/api/v1/assets/{}", asset_id));
         self.send_json(req).await
     }
 
-    /// Upload asset
+    /// Upload asset.
+    ///
+    /// The file is streamed from disk rather than read into memory, and `progress`
+    /// is called with the number of bytes sent as the body goes out.
     pub async fn upload_asset(
         &self,
         file_path: &Path,
-        name: String,
-        category: Option<String>,
-        tags: Vec<String>,
+        metadata: AssetUploadMetadata,
+        progress: Option<ProgressCallback>,
     ) -> Result<Asset> {
-        // Read file
-        let mut file = File::open(file_path).await?;
-        let mut buffer = Vec::new();
-        file.read_to_end(&mut buffer).await?;
+        let file = File::open(file_path).await?;
+        let length = file.metadata().await?.len();
 
         let file_name = file_path
             .file_name()
@@ -63,20 +314,79 @@ impl ApiClient {
             .unwrap_or("upload")
             .to_string();
 
+        let mut sent = 0u64;
+        let body = ReaderStream::new(file).inspect_ok(move |chunk| {
+            sent += chunk.len() as u64;
+            if let Some(progress) = &progress {
+                progress(sent);
+            }
+        });
+
         // Create multipart form
-        let file_part = Part::bytes(buffer).file_name(file_name);
-        let form = Form::new()
+        let file_part = Part::stream_with_length(Body::wrap_stream(body), length).file_name(file_name);
+        let mut form = Form::new()
             .part("file", file_part)
-            .text("name", name)
-            .text("tags", serde_json::to_string(&tags)?);
+            .text("name", metadata.name)
+            .text("tags", serde_json::to_string(&metadata.tags)?);
+
+        if let Some(cat) = metadata.category {
+            form = form.text("category", cat);
+        }
+        if let Some(sha256) = metadata.sha256 {
+            form = form.text("sha256", sha256);
+        }
+        if let Some(mesh) = &metadata.mesh {
+            form = form.text("mesh", serde_json::to_string(mesh)?);
+        }
+
+        let req = self
+            .request(OperationClass::Upload, Method::POST, "/api/v1/assets/upload")
+            .multipart(form);
+        self.send_upload_json(req).await
+    }
 
-        let form = if let Some(cat) = category {
-            form.text("category", cat)
+    /// Fetch an asset's thumbnail image.
+    ///
+    /// Absolute URLs are fetched as they are (they are usually presigned); relative ones
+    /// are resolved against the API endpoint.
+    pub async fn get_thumbnail(&self, thumbnail_url: &str) -> Result<Vec<u8>> {
+        let req = if thumbnail_url.starts_with('/') {
+            self.get(thumbnail_url)
         } else {
-            form
+            self.get_url(thumbnail_url)
         };
+        let response = self.send_raw(req).await?;
+        Ok(response.bytes().await?.to_vec())
+    }
 
-        let req = self.post("/api/v1/assets/upload").multipart(form);
+    /// Get a download URL for an asset's original file
+    pub async fn get_asset_download_url(&self, asset_id: &str) -> Result<DownloadUrlResponse> {
+        let req = self.get(&format!("/api/v1/assets/{}/download", asset_id));
+        self.send_json(req).await
+    }
+
+    /// Download an asset's original file, streaming it to `output_path`.
+    ///
+    /// `progress` is called with the number of bytes written. Returns the file's size.
+    pub async fn download_asset(
+        &self,
+        asset_id: &str,
+        output_path: &Path,
+        progress: Option<ProgressCallback>,
+    ) -> Result<u64> {
+        let download = self.get_asset_download_url(asset_id).await?;
+        self.download_to_file(&download.download_url, output_path, progress).await
+    }
+
+    /// Change an asset's name, category or tags
+    pub async fn update_asset(&self, asset_id: &str, update: &AssetUpdate) -> Result<Asset> {
+        let req = self.patch(&format!("/api/v1/assets/{}", asset_id)).json(update);
+        self.send_json(req).await
+    }
+
+    /// List the jobs and configs that reference an asset
+    pub async fn asset_usage(&self, asset_id: &str) -> Result<AssetUsage> {
+        let req = self.get(&format!("/api/v1/assets/{}/usage", asset_id));
         self.send_json(req).await
     }
 
@@ -86,3 +396,82 @@ impl ApiClient {
         self.send(req).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::fake;
+
+    #[test]
+    fn test_query_filters_and_sorts_like_the_server() {
+        let asset = |id: &str, name: &str, size_bytes: i64, created_at: &str| Asset {
+            size_bytes,
+            created_at: created_at.to_string(),
+            tags: vec!["oak".to_string()],
+            ..fake::asset(id, name)
+        };
+        let mut assets = vec![
+            asset("a1", "Oak Chair", 2_000_000, "2024-03-01T10:00:00Z"),
+            asset("a2", "oak table", 9_000_000, "2024-05-01T10:00:00Z"),
+            asset("a3", "Pine Chair", 500, "2024-05-02T10:00:00Z"),
+        ];
+
+        let query = AssetQuery {
+            tag: Some("oak".to_string()),
+            min_size: Some(1_000_000),
+            created_after: Some("2024-03-01".to_string()),
+            created_before: Some("2024-05-01T10:00:00Z".to_string()),
+            sort: Some(AssetSort::Size),
+            descending: true,
+            ..AssetQuery::default()
+        };
+        assert_eq!(
+            query.params(),
+            [
+                ("tag", "oak".to_string()),
+                ("min_size", "1000000".to_string()),
+                ("created_after", "2024-03-01".to_string()),
+                ("created_before", "2024-05-01T10:00:00Z".to_string()),
+                ("sort", "size".to_string()),
+                ("order", "desc".to_string()),
+            ]
+        );
+        let matching: Vec<&str> = assets.iter().filter(|a| query.matches(a)).map(|a| a.name.as_str()).collect();
+        assert_eq!(matching, ["Oak Chair"]);
+
+        let by_name = AssetQuery {
+            sort: Some(AssetSort::Name),
+            ..AssetQuery::default()
+        }
+        .name_glob("oak*")
+        .unwrap();
+        assert!(by_name.needs_client_filtering());
+        assert!(!by_name.is_sorted(&[assets[2].clone(), assets[0].clone()]));
+        by_name.sort(&mut assets);
+        let names: Vec<&str> = assets.iter().filter(|a| by_name.matches(a)).map(|a| a.name.as_str()).collect();
+        assert_eq!(names, ["Oak Chair", "oak table"]);
+    }
+
+    #[test]
+    fn test_created_filters_compare_instants_not_strings() {
+        let created = |id: &str, created_at: &str| Asset {
+            created_at: created_at.to_string(),
+            ..fake::asset(id, id)
+        };
+        // 09:30Z with an offset, and half a second after the lower bound
+        let assets = [
+            created("offset", "2024-05-01T11:30:00+02:00"),
+            created("fraction", "2024-05-01T08:00:00.5Z"),
+            created("early", "2024-05-01T07:59:59.999Z"),
+        ];
+        let query = AssetQuery {
+            created_after: Some("2024-05-01T08:00:00Z".to_string()),
+            created_before: Some("2024-05-01T10:00:00Z".to_string()),
+            sort: Some(AssetSort::Created),
+            ..AssetQuery::default()
+        };
+        let matching: Vec<&str> = assets.iter().filter(|a| query.matches(a)).map(|a| a.name.as_str()).collect();
+        assert_eq!(matching, ["offset", "fraction"]);
+        assert!(query.is_sorted(&[assets[2].clone(), assets[1].clone(), assets[0].clone()]));
+    }
+}
@@ -1,5 +1,7 @@
 use crate::api::ApiClient;
+use crate::config::AuthConfig;
 use crate::error::Result;
+use crate::time::unix_now;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize)]
@@ -11,9 +13,30 @@ pub struct LoginRequest {
 #[derive(Debug, Deserialize)]
 pub struct LoginResponse {
     pub access_token: String,
+    pub refresh_token: Option<String>,
+    /// Access token lifetime in seconds
+    pub expires_in: Option<i64>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+impl LoginResponse {
+    /// Convert into the session stored in the config file.
+    ///
+    /// A refresh response may omit the refresh token, in which case the previous one is kept.
+    pub fn into_session(self, previous_refresh_token: Option<String>) -> AuthConfig {
+        AuthConfig {
+            api_key: Some(self.access_token),
+            refresh_token: self.refresh_token.or(previous_refresh_token),
+            expires_at: self.expires_in.map(|secs| unix_now() + secs),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct UserInfo {
     pub id: i32,
     pub email: String,
@@ -24,12 +47,12 @@ pub struct UserInfo {
 
 impl ApiClient {
     /// Login with email and password
-    pub async fn login(&self, email: String, password: String) -> Result<String> {
+    pub async fn login(&self, email: String, password: String) -> Result<AuthConfig> {
         let req = self.post("/api/v1/auth/login")
             .json(&LoginRequest { email, password });
 
         let response: LoginResponse = self.send_json(req).await?;
-        Ok(response.access_token)
+        Ok(response.into_session(None))
     }
 
     /// Get current user info
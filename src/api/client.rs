@@ -1,128 +1,852 @@
-use crate::config::Config;
-use crate::error::{CliError, Result};
-use reqwest::{Client, RequestBuilder, Response};
+use crate::api::auth::{LoginResponse, RefreshRequest};
+use crate::api::idle::IdleTimer;
+use crate::api::redact::{redact_body, redact_url};
+use crate::api::retry::{self, RetryPolicy};
+use crate::config::{AuthConfig, Config, OperationClass, Overrides};
+use crate::error::{ApiError, CliError, Result};
+use crate::api::{ProgressCallback, HTTP_TARGET};
+use reqwest::header::{HeaderValue, AUTHORIZATION};
+use reqwest::{
+    Certificate, Client, Identity, Method, NoProxy, Proxy, Request, RequestBuilder, Response,
+    StatusCode,
+};
+use futures::future::BoxFuture;
+use futures::StreamExt;
 use serde::de::DeserializeOwned;
-use serde::Serialize;
-use std::time::Duration;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+use tracing::Level;
+
+/// Refresh the access token proactively when it expires within this many seconds
+const REFRESH_MARGIN_SECS: i64 = 60;
+
+/// Produces a new session once the current one can no longer be refreshed, e.g. by logging in again
+type Reauthenticate = Arc<dyn Fn() -> BoxFuture<'static, Result<AuthConfig>> + Send + Sync>;
 
 #[derive(Clone)]
 pub struct ApiClient {
-    client: Client,
+    clients: HttpClients,
     base_url: String,
-    api_key: Option<String>,
+    /// Current session, shared between clones so a refresh benefits all of them
+    credentials: Arc<RwLock<AuthConfig>>,
+    /// Serializes token refreshes so concurrent 401s trigger a single refresh
+    refresh_lock: Arc<tokio::sync::Mutex<()>>,
+    /// Config file that refreshed tokens are written back to
+    credentials_file: Option<PathBuf>,
+    reauthenticate: Option<Reauthenticate>,
+    retry: RetryPolicy,
+    /// Fail an upload once it makes no progress for this long (see [`IdleTimer`])
+    upload_idle: Option<Duration>,
 }
 
-impl ApiClient {
-    /// Create a new API client from config
-    pub fn from_config(config: &Config) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(30))
-            .build()
-            .map_err(|e| CliError::Network(e))?;
+/// One HTTP client per operation class, since connect and read-idle timeouts are per client
+#[derive(Clone)]
+struct HttpClients {
+    metadata: Client,
+    upload: Client,
+    download: Client,
+    poll: Client,
+}
+
+impl HttpClients {
+    fn new(config: &Config, overrides: &Overrides) -> Result<Self> {
+        Ok(HttpClients {
+            metadata: http_client(config, OperationClass::Metadata, overrides)?,
+            upload: http_client(config, OperationClass::Upload, overrides)?,
+            download: http_client(config, OperationClass::Download, overrides)?,
+            poll: http_client(config, OperationClass::Poll, overrides)?,
+        })
+    }
+
+    fn for_class(&self, class: OperationClass) -> &Client {
+        match class {
+            OperationClass::Metadata => &self.metadata,
+            OperationClass::Upload => &self.upload,
+            OperationClass::Download => &self.download,
+            OperationClass::Poll => &self.poll,
+        }
+    }
+}
+
+/// Builds an [`ApiClient`] from config, with settings the config file does not hold
+pub struct ApiClientBuilder {
+    config: Config,
+    overrides: Overrides,
+    authenticated: bool,
+    credentials_file: Option<PathBuf>,
+    reauthenticate: Option<Reauthenticate>,
+}
+
+impl ApiClientBuilder {
+    /// Layer per-invocation settings, such as command-line flags, over the config
+    pub fn overrides(mut self, overrides: Overrides) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
+    /// Send no credentials, e.g. to log in
+    pub fn unauthenticated(mut self) -> Self {
+        self.authenticated = false;
+        self
+    }
+
+    /// Write refreshed sessions back to the user's config file, as the CLI does.
+    ///
+    /// Off by default: refreshed tokens are then kept in memory only.
+    pub fn persist_credentials(mut self, persist: bool) -> Self {
+        // Without a config dir there is nowhere to persist to, so tokens stay in memory
+        self.credentials_file = persist.then(|| Config::config_path().ok()).flatten();
+        self
+    }
+
+    /// Write refreshed sessions to `path` instead of the user's config file
+    #[cfg(test)]
+    fn persist_credentials_to(mut self, path: PathBuf) -> Self {
+        self.credentials_file = Some(path);
+        self
+    }
+
+    /// Ask `reauthenticate` for a new session when the current one cannot be refreshed.
+    ///
+    /// The request that found the session expired is then sent again, so callers
+    /// see it succeed instead of getting `CliError::SessionExpired`.
+    pub fn on_session_expired<F, Fut>(mut self, reauthenticate: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<AuthConfig>> + Send + 'static,
+    {
+        self.reauthenticate = Some(Arc::new(move || Box::pin(reauthenticate())));
+        self
+    }
 
+    pub fn build(self) -> Result<ApiClient> {
+        let config = &self.config;
+        let credentials = if self.authenticated {
+            config.auth.clone()
+        } else {
+            AuthConfig::default()
+        };
         Ok(ApiClient {
-            client,
+            clients: HttpClients::new(config, &self.overrides)?,
             base_url: config.api.endpoint.clone(),
-            api_key: config.auth.api_key.clone(),
+            credentials: Arc::new(RwLock::new(credentials)),
+            refresh_lock: Arc::new(tokio::sync::Mutex::new(())),
+            credentials_file: self.credentials_file,
+            reauthenticate: self.reauthenticate,
+            retry: RetryPolicy::from(&config.effective_retry(&self.overrides)),
+            upload_idle: config.timeouts.resolve(OperationClass::Upload, &self.overrides).read_idle,
         })
     }
+}
+
+impl ApiClient {
+    /// Start building a client from config; see [`ApiClientBuilder`]
+    pub fn builder(config: &Config) -> ApiClientBuilder {
+        ApiClientBuilder {
+            config: config.clone(),
+            overrides: Overrides::default(),
+            authenticated: true,
+            credentials_file: None,
+            reauthenticate: None,
+        }
+    }
+
+    /// Create a new API client from config.
+    ///
+    /// Refreshed tokens are kept in memory only; see [`ApiClientBuilder::persist_credentials`].
+    pub fn from_config(config: &Config) -> Result<Self> {
+        ApiClient::builder(config).build()
+    }
+
+    /// Create a client that uses the config's network settings but sends no credentials
+    pub fn unauthenticated(config: &Config) -> Result<Self> {
+        ApiClient::builder(config).unauthenticated().build()
+    }
 
     /// Create a new API client with custom base URL
     pub fn new(base_url: String, api_key: Option<String>) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(30))
-            .build()
-            .map_err(|e| CliError::Network(e))?;
+        let mut config = Config::default();
+        config.set_endpoint(base_url);
+        config.set_api_key(api_key);
+        ApiClient::from_config(&config)
+    }
 
-        Ok(ApiClient {
-            client,
-            base_url,
-            api_key,
-        })
+    /// Current access token, if any
+    fn access_token(&self) -> Option<String> {
+        self.credentials.read().ok()?.api_key.clone()
+    }
+
+    fn authorize(&self, req: RequestBuilder) -> RequestBuilder {
+        match self.access_token() {
+            Some(key) => req.bearer_auth(key),
+            None => req,
+        }
+    }
+
+    /// Build a request for an API path using the client of the given operation class
+    pub fn request(&self, class: OperationClass, method: Method, path: &str) -> RequestBuilder {
+        let url = format!("{}{}", self.base_url, path);
+        self.authorize(self.clients.for_class(class).request(method, &url))
     }
 
     /// Build a GET request
     pub fn get(&self, path: &str) -> RequestBuilder {
-        let url = format!("{}{}", self.base_url, path);
-        let mut req = self.client.get(&url);
-        if let Some(key) = &self.api_key {
-            req = req.bearer_auth(key);
-        }
-        req
+        self.request(OperationClass::Metadata, Method::GET, path)
     }
 
     /// Build a POST request
     pub fn post(&self, path: &str) -> RequestBuilder {
-        let url = format!("{}{}", self.base_url, path);
-        let mut req = self.client.post(&url);
-        if let Some(key) = &self.api_key {
-            req = req.bearer_auth(key);
-        }
-        req
+        self.request(OperationClass::Metadata, Method::POST, path)
     }
 
     /// Build a PUT request
     pub fn put(&self, path: &str) -> RequestBuilder {
-        let url = format!("{}{}", self.base_url, path);
-        let mut req = self.client.put(&url);
-        if let Some(key) = &self.api_key {
-            req = req.bearer_auth(key);
-        }
-        req
+        self.request(OperationClass::Metadata, Method::PUT, path)
+    }
+
+    /// Build a PATCH request
+    pub fn patch(&self, path: &str) -> RequestBuilder {
+        self.request(OperationClass::Metadata, Method::PATCH, path)
     }
 
     /// Build a DELETE request
     pub fn delete(&self, path: &str) -> RequestBuilder {
-        let url = format!("{}{}", self.base_url, path);
-        let mut req = self.client.delete(&url);
-        if let Some(key) = &self.api_key {
-            req = req.bearer_auth(key);
+        self.request(OperationClass::Metadata, Method::DELETE, path)
+    }
+
+    /// Build an unauthenticated GET for an absolute URL outside the API, e.g. a presigned download
+    pub fn get_url(&self, url: &str) -> RequestBuilder {
+        self.clients.download.get(url)
+    }
+
+    /// Stream a presigned download URL to `output_path`.
+    ///
+    /// The data goes to a `.part` file that replaces `output_path` only once complete,
+    /// so an interrupted download never leaves a truncated file behind. `progress` is
+    /// called with the number of bytes written. Returns the file's size.
+    pub async fn download_to_file(
+        &self,
+        url: &str,
+        output_path: &Path,
+        progress: Option<ProgressCallback>,
+    ) -> Result<u64> {
+        // Presigned URLs carry their own signature; never send our bearer token along
+        let response = self.send_raw(self.get_url(url)).await?;
+
+        let mut partial = output_path.as_os_str().to_owned();
+        partial.push(".part");
+        let partial = PathBuf::from(partial);
+
+        let result = async {
+            let mut file = File::create(&partial).await?;
+            let mut written = 0u64;
+            let mut body = response.bytes_stream();
+            while let Some(chunk) = body.next().await {
+                let chunk = chunk?;
+                file.write_all(&chunk).await?;
+                written += chunk.len() as u64;
+                if let Some(progress) = &progress {
+                    progress(written);
+                }
+            }
+            file.flush().await?;
+            Ok(written)
+        }
+        .await;
+
+        match result {
+            Ok(written) => {
+                tokio::fs::rename(&partial, output_path).await?;
+                Ok(written)
+            }
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&partial).await;
+                Err(e)
+            }
         }
-        req
     }
 
     /// Send request and parse JSON response
     pub async fn send_json<T: DeserializeOwned>(&self, req: RequestBuilder) -> Result<T> {
-        let response = req.send().await?;
+        let response = self.execute(req, false, None).await?;
         self.handle_response(response).await
     }
 
+    /// Send an upload and parse the JSON response, failing if the transfer stalls
+    pub(crate) async fn send_upload_json<T: DeserializeOwned>(&self, req: RequestBuilder) -> Result<T> {
+        let response = self.execute(req, false, self.upload_idle).await?;
+        self.handle_response(response).await
+    }
+
+    /// Execute a request, refreshing the session and retrying transient failures.
+    ///
+    /// A 401 on an authenticated request renews the session once, by refreshing it or
+    /// through the `on_session_expired` hook, and replays the request.
+    /// With `idle` set, each attempt fails once its body makes no progress for that long.
+    async fn execute(&self, req: RequestBuilder, force_retry: bool, idle: Option<Duration>) -> Result<Response> {
+        // Keep the client the request was built with, so its operation class timeouts apply
+        let (client, request) = req.build_split();
+        let mut request = request?;
+        let authenticated = request.headers().contains_key(AUTHORIZATION);
+
+        if authenticated {
+            let expiring = self
+                .credentials
+                .read()
+                .map(|auth| auth.can_refresh() && auth.expires_within(REFRESH_MARGIN_SECS))
+                .unwrap_or(false);
+            if expiring {
+                // Best effort: if this fails the server's 401 takes the path below
+                let _ = self.refresh_session(self.access_token()).await;
+            }
+            self.apply_token(&mut request);
+        }
+
+        // Without a refresh token the session can still be renewed by logging in again
+        let renewable = authenticated
+            && (self.reauthenticate.is_some()
+                || self.credentials.read().map(|auth| auth.can_refresh()).unwrap_or(false));
+        let replay = if renewable { request.try_clone() } else { None };
+        let used_token = self.access_token();
+
+        let response = self.execute_with_retries(&client, request, force_retry, idle).await?;
+
+        match replay {
+            Some(mut replay) if response.status() == StatusCode::UNAUTHORIZED => {
+                self.renew_session(used_token).await?;
+                self.apply_token(&mut replay);
+                self.execute_with_retries(&client, replay, force_retry, idle).await
+            }
+            _ => Ok(response),
+        }
+    }
+
+    /// Send a request, retrying transient failures according to the retry policy.
+    ///
+    /// Non-idempotent methods are sent once unless `force_retry` is set or the
+    /// request carries an idempotency key. Requests with streaming bodies cannot
+    /// be replayed and are always sent once.
+    async fn execute_with_retries(
+        &self,
+        client: &Client,
+        request: Request,
+        force_retry: bool,
+        idle: Option<Duration>,
+    ) -> Result<Response> {
+        let retryable = force_retry || RetryPolicy::is_replay_safe(request.method(), request.headers());
+
+        let mut attempt = 1;
+        loop {
+            let attempt_request = if retryable && attempt < self.retry.max_attempts {
+                request.try_clone()
+            } else {
+                None
+            };
+
+            // Last (or only) attempt: hand the result straight back to the caller
+            let Some(attempt_request) = attempt_request else {
+                return self.send_once(client, request, attempt, idle).await;
+            };
+
+            let delay = match self.send_once(client, attempt_request, attempt, idle).await {
+                Ok(response) if RetryPolicy::is_retryable_status(response.status()) => {
+                    match self.retry.delay(attempt, response.status(), response.headers()) {
+                        Some(delay) => delay,
+                        // Surfaces as `RateLimited`, carrying the server's hint
+                        None => return Ok(response),
+                    }
+                }
+                Ok(response) => return Ok(response),
+                Err(CliError::Network(e)) if RetryPolicy::is_retryable_error(&e) => self.retry.backoff(attempt),
+                // A stalled upload
+                Err(CliError::Io(e)) if e.kind() == std::io::ErrorKind::TimedOut => self.retry.backoff(attempt),
+                Err(e) => return Err(e),
+            };
+
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Send a single HTTP request, logging it with secrets redacted
+    async fn send_once(
+        &self,
+        client: &Client,
+        mut request: Request,
+        attempt: u32,
+        idle: Option<Duration>,
+    ) -> Result<Response> {
+        let method = request.method().clone();
+        let url = redact_url(request.url().as_str());
+
+        if tracing::enabled!(target: HTTP_TARGET, Level::TRACE) {
+            if let Some(body) = request.body().and_then(|b| b.as_bytes()) {
+                tracing::trace!(target: HTTP_TARGET, "{} {} request body: {}", method, url, redact_body(body));
+            }
+        }
+
+        let started = Instant::now();
+        let result = match idle {
+            Some(idle) => {
+                let timer = IdleTimer::new(idle);
+                timer.watch(&mut request);
+                timer.run(client.execute(request)).await
+            }
+            None => client.execute(request).await.map_err(CliError::from),
+        };
+        let elapsed = started.elapsed();
+
+        match &result {
+            Ok(response) => tracing::debug!(
+                target: HTTP_TARGET,
+                "{} {} -> {} in {:.0?} (attempt {})",
+                method,
+                url,
+                response.status().as_u16(),
+                elapsed,
+                attempt
+            ),
+            Err(e) => tracing::debug!(
+                target: HTTP_TARGET,
+                "{} {} -> error after {:.0?} (attempt {}): {}",
+                method,
+                url,
+                elapsed,
+                attempt,
+                e
+            ),
+        }
+
+        result
+    }
+
+    /// Replace the bearer token of an already built request with the current one
+    fn apply_token(&self, request: &mut Request) {
+        let header = self
+            .access_token()
+            .and_then(|token| HeaderValue::from_str(&format!("Bearer {}", token)).ok());
+        if let Some(mut header) = header {
+            header.set_sensitive(true);
+            request.headers_mut().insert(AUTHORIZATION, header);
+        }
+    }
+
+    /// Exchange the refresh token for a new access token.
+    ///
+    /// `stale_token` is the token that was rejected; if another request already
+    /// replaced it while we waited for the lock, no second refresh is made.
+    async fn refresh_session(&self, stale_token: Option<String>) -> Result<()> {
+        let _guard = self.refresh_lock.lock().await;
+        if self.access_token() != stale_token {
+            return Ok(());
+        }
+
+        let refresh_token = self
+            .credentials
+            .read()
+            .ok()
+            .and_then(|auth| auth.refresh_token.clone())
+            .ok_or(CliError::SessionExpired)?;
+
+        let url = format!("{}/api/v1/auth/refresh", self.base_url);
+        let request = self
+            .clients
+            .metadata
+            .post(&url)
+            .json(&RefreshRequest {
+                refresh_token: refresh_token.clone(),
+            })
+            .build()?;
+        // Sent once: servers that rotate refresh tokens revoke the session if one is reused
+        let response = self.execute_with_retries(&self.clients.metadata, request, false, None).await?;
+        if !response.status().is_success() {
+            return Err(CliError::SessionExpired);
+        }
+
+        let tokens: LoginResponse = response
+            .json()
+            .await
+            .map_err(|_| CliError::InvalidResponse)?;
+        self.set_session(tokens.into_session(Some(refresh_token)));
+        Ok(())
+    }
+
+    /// Refresh the session, or failing that, get a new one from the `on_session_expired` hook
+    async fn renew_session(&self, stale_token: Option<String>) -> Result<()> {
+        let reauthenticate = match (self.refresh_session(stale_token.clone()).await, &self.reauthenticate) {
+            (Err(CliError::SessionExpired), Some(reauthenticate)) => reauthenticate.clone(),
+            (result, _) => return result,
+        };
+
+        // Concurrent requests wait here, and only the first asks for a new session
+        let _guard = self.refresh_lock.lock().await;
+        if self.access_token() != stale_token {
+            return Ok(());
+        }
+        self.set_session(reauthenticate().await?);
+        Ok(())
+    }
+
+    fn set_session(&self, session: AuthConfig) {
+        if let Ok(mut credentials) = self.credentials.write() {
+            *credentials = session.clone();
+        }
+
+        if let Some(path) = &self.credentials_file {
+            // Keep the in-memory session even if the config file can't be written
+            if let Ok(mut config) = Config::load_from(path) {
+                config.set_session(session);
+                let _ = config.save_to(path);
+            }
+        }
+    }
+
     /// Handle response and parse JSON
     async fn handle_response<T: DeserializeOwned>(&self, response: Response) -> Result<T> {
+        if response.status().is_success() {
+            let body = response.bytes().await?;
+            tracing::trace!(target: HTTP_TARGET, "response body: {}", redact_body(&body));
+            serde_json::from_slice::<T>(&body).map_err(|_| CliError::InvalidResponse)
+        } else {
+            Err(Self::error_from_response(response).await)
+        }
+    }
+
+    /// Decode a non-2xx response into the matching `CliError` variant
+    async fn error_from_response(response: Response) -> CliError {
         let status = response.status();
+        let request_id = response
+            .headers()
+            .get("x-request-id")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let retry_after_secs = retry::retry_after(response.headers()).map(|d| d.as_secs());
+        let path = response.url().path().to_string();
 
-        if status.is_success() {
-            response.json::<T>().await.map_err(|e| {
-                CliError::InvalidResponse
-            })
-        } else if status == 401 {
-            Err(CliError::Auth("Unauthorized. Please login again.".to_string()))
-        } else if status == 403 {
-            Err(CliError::Auth("Forbidden. You don't have permission to access this resource.".to_string()))
-        } else if status == 404 {
-            Err(CliError::Api("Resource not found".to_string()))
+        let body = response.text().await.unwrap_or_default();
+        tracing::trace!(target: HTTP_TARGET, "error body: {}", redact_body(body.as_bytes()));
+        let error = ApiError::from_body(status.as_u16(), &body, request_id);
+
+        match status {
+            StatusCode::UNAUTHORIZED => CliError::Auth {
+                message: format!("Unauthorized: {}. Please login again.", error.message),
+                error: Some(Box::new(error)),
+            },
+            StatusCode::FORBIDDEN => CliError::Auth {
+                message: format!("Forbidden: {}", error.message),
+                error: Some(Box::new(error)),
+            },
+            StatusCode::NOT_FOUND => match resource_from_path(&path) {
+                Some((resource, id)) => CliError::NotFound {
+                    resource,
+                    id,
+                    request_id: error.request_id,
+                },
+                None => CliError::Server(error),
+            },
+            StatusCode::CONFLICT => CliError::Conflict(error),
+            StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => CliError::Validation(error),
+            StatusCode::TOO_MANY_REQUESTS => CliError::RateLimited {
+                error,
+                retry_after_secs,
+            },
+            _ => CliError::Server(error),
+        }
+    }
+
+    /// Send request and return the raw response (e.g. for streaming), failing on non-2xx statuses
+    pub async fn send_raw(&self, req: RequestBuilder) -> Result<Response> {
+        let response = self.execute(req, false, None).await?;
+        if response.status().is_success() {
+            Ok(response)
         } else {
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            Err(CliError::Api(format!("API error ({}): {}", status, error_text)))
+            Err(Self::error_from_response(response).await)
         }
     }
 
     /// Send request with no expected response
     pub async fn send(&self, req: RequestBuilder) -> Result<()> {
-        let response = req.send().await?;
-        let status = response.status();
+        let response = self.execute(req, false, None).await?;
+        Self::check_status(response).await
+    }
 
-        if status.is_success() {
+    /// Send request with no expected response, retrying even if the method is not idempotent.
+    ///
+    /// Only use this for endpoints that are safe to replay (e.g. cancelling a job).
+    pub async fn send_retrying(&self, req: RequestBuilder) -> Result<()> {
+        let response = self.execute(req, true, None).await?;
+        Self::check_status(response).await
+    }
+
+    /// Map a response with no expected body to success or an error
+    async fn check_status(response: Response) -> Result<()> {
+        if response.status().is_success() {
             Ok(())
-        } else if status == 401 {
-            Err(CliError::Auth("Unauthorized. Please login again.".to_string()))
-        } else if status == 403 {
-            Err(CliError::Auth("Forbidden. You don't have permission to access this resource.".to_string()))
-        } else if status == 404 {
-            Err(CliError::Api("Resource not found".to_string()))
         } else {
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            Err(CliError::Api(format!("API error ({}): {}", status, error_text)))
+            Err(Self::error_from_response(response).await)
+        }
+    }
+}
+
+/// Build the HTTP client for an operation class with the network settings from config
+fn http_client(config: &Config, class: OperationClass, overrides: &Overrides) -> Result<Client> {
+    let mut builder = Client::builder();
+
+    let timeouts = config.timeouts.resolve(class, overrides);
+    if let Some(connect) = timeouts.connect {
+        builder = builder.connect_timeout(connect);
+    }
+    // Uploads watch their body instead, since the server is silent while it is sent
+    if let Some(read_idle) = timeouts.read_idle.filter(|_| class != OperationClass::Upload) {
+        builder = builder.read_timeout(read_idle);
+    }
+    if let Some(total) = timeouts.total {
+        builder = builder.timeout(total);
+    }
+
+    let proxy = &config.proxy;
+    if proxy.is_configured() {
+        let no_proxy = NoProxy::from_string(&proxy.no_proxy.join(","));
+        if let Some(url) = &proxy.http {
+            builder = builder.proxy(Proxy::http(url)?.no_proxy(no_proxy.clone()));
+        }
+        if let Some(url) = &proxy.https {
+            builder = builder.proxy(Proxy::https(url)?.no_proxy(no_proxy));
+        }
+    }
+
+    let tls = &config.tls;
+    for path in &tls.ca_certs {
+        for cert in load_certificates(path)? {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    if let Some(path) = &tls.client_cert {
+        let identity = load_identity(
+            path,
+            tls.client_key.as_deref(),
+            tls.client_cert_password.as_deref(),
+        )?;
+        builder = builder.identity(identity);
+    }
+
+    if tls.insecure_skip_verify {
+        tracing::warn!("TLS certificate verification is disabled (tls.insecure_skip_verify)");
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+
+    builder.build().map_err(CliError::Network)
+}
+
+fn read_tls_file(path: &Path) -> Result<Vec<u8>> {
+    std::fs::read(path)
+        .map_err(|e| CliError::Config(format!("Failed to read {}: {}", path.display(), e)))
+}
+
+/// Load a PEM bundle, falling back to a single DER certificate
+fn load_certificates(path: &Path) -> Result<Vec<Certificate>> {
+    let bytes = read_tls_file(path)?;
+    let certs = match Certificate::from_pem_bundle(&bytes) {
+        Ok(certs) if !certs.is_empty() => certs,
+        _ => vec![Certificate::from_der(&bytes).map_err(|e| {
+            CliError::Config(format!("Invalid CA certificate {}: {}", path.display(), e))
+        })?],
+    };
+    Ok(certs)
+}
+
+/// Load a PKCS#12 archive or a PEM certificate chain with a PKCS#8 key
+fn load_identity(cert: &Path, key: Option<&Path>, password: Option<&str>) -> Result<Identity> {
+    let cert_bytes = read_tls_file(cert)?;
+    let is_pkcs12 = cert
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("p12") || e.eq_ignore_ascii_case("pfx"));
+
+    let identity = if is_pkcs12 {
+        Identity::from_pkcs12_der(&cert_bytes, password.unwrap_or(""))
+    } else {
+        // The key may live in the same PEM file as the certificate chain
+        let key_bytes = match key {
+            Some(key) => read_tls_file(key)?,
+            None => cert_bytes.clone(),
+        };
+        Identity::from_pkcs8_pem(&cert_bytes, &key_bytes)
+    };
+
+    identity.map_err(|e| CliError::Config(format!("Invalid client identity {}: {}", cert.display(), e)))
+}
+
+/// Derive a resource name and ID from an API path, e.g. `/api/v1/jobs/abc/progress` -> ("Job", "abc")
+fn resource_from_path(path: &str) -> Option<(String, String)> {
+    let mut segments = path
+        .trim_start_matches('/')
+        .split('/')
+        .skip_while(|s| *s == "api" || s.starts_with('v') && s[1..].chars().all(|c| c.is_ascii_digit()));
+
+    let collection = segments.next()?;
+    let id = segments.next()?;
+
+    let singular = collection.strip_suffix('s').unwrap_or(collection);
+    let mut resource = singular.to_string();
+    if let Some(first) = resource.get_mut(0..1) {
+        first.make_ascii_uppercase();
+    }
+
+    Some((resource, id.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::State;
+    use axum::http::HeaderMap;
+    use axum::routing::{get, post};
+    use axum::{Json, Router};
+    use serde_json::{json, Value};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    /// Auth backend that accepts one access token at a time and hands out a new one per refresh
+    #[derive(Default)]
+    struct AuthServer {
+        valid_token: Mutex<String>,
+        /// Whether the refresh token is still accepted
+        refreshable: Mutex<bool>,
+        refreshes: AtomicUsize,
+    }
+
+    async fn me(State(server): State<Arc<AuthServer>>, headers: HeaderMap) -> (StatusCode, Json<Value>) {
+        let expected = format!("Bearer {}", server.valid_token.lock().unwrap());
+        match headers.get(AUTHORIZATION).and_then(|v| v.to_str().ok()) {
+            Some(auth) if auth == expected => (StatusCode::OK, Json(json!({"id": 1, "email": "a@b.c"}))),
+            _ => (StatusCode::UNAUTHORIZED, Json(json!({"detail": "Token expired"}))),
+        }
+    }
+
+    async fn refresh(State(server): State<Arc<AuthServer>>) -> (StatusCode, Json<Value>) {
+        let n = server.refreshes.fetch_add(1, Ordering::SeqCst) + 1;
+        if !*server.refreshable.lock().unwrap() {
+            return (StatusCode::UNAUTHORIZED, Json(json!({"detail": "Refresh token revoked"})));
         }
+        let token = format!("access-{}", n);
+        *server.valid_token.lock().unwrap() = token.clone();
+        (StatusCode::OK, Json(json!({"access_token": token, "refresh_token": format!("refresh-{}", n)})))
+    }
+
+    async fn serve(server: Arc<AuthServer>) -> String {
+        let app = Router::new()
+            .route("/api/v1/auth/me", get(me))
+            .route("/api/v1/auth/refresh", post(refresh))
+            .with_state(server);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_expired_session_is_refreshed_persisted_and_replayed_then_relogged() {
+        let server = Arc::new(AuthServer::default());
+        *server.valid_token.lock().unwrap() = "current".to_string();
+        *server.refreshable.lock().unwrap() = true;
+
+        let dir = tempfile::tempdir().unwrap();
+        let config_file = dir.path().join("config.toml");
+        let mut config = Config::default();
+        config.set_endpoint(serve(server.clone()).await);
+        config.set_session(AuthConfig {
+            api_key: Some("stale".to_string()),
+            refresh_token: Some("refresh-0".to_string()),
+            expires_at: None,
+        });
+        config.save_to(&config_file).unwrap();
+
+        let relogins = Arc::new(AtomicUsize::new(0));
+        let counter = relogins.clone();
+        let relogin_server = server.clone();
+        let client = ApiClient::builder(&config)
+            .persist_credentials_to(config_file.clone())
+            .on_session_expired(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                *relogin_server.valid_token.lock().unwrap() = "relogged".to_string();
+                async {
+                    Ok(AuthConfig {
+                        api_key: Some("relogged".to_string()),
+                        refresh_token: None,
+                        expires_at: None,
+                    })
+                }
+            })
+            .build()
+            .unwrap();
+
+        // The 401 triggers one refresh, whose tokens are saved, and the request is replayed
+        assert_eq!(client.whoami().await.unwrap().email, "a@b.c");
+        assert_eq!(server.refreshes.load(Ordering::SeqCst), 1);
+        let saved = Config::load_from(&config_file).unwrap().auth;
+        assert_eq!(saved.api_key.as_deref(), Some("access-1"));
+        assert_eq!(saved.refresh_token.as_deref(), Some("refresh-1"));
+
+        // Once the refresh token is revoked the hook logs in again and the request still succeeds
+        *server.valid_token.lock().unwrap() = "rotated-elsewhere".to_string();
+        *server.refreshable.lock().unwrap() = false;
+        assert_eq!(client.whoami().await.unwrap().email, "a@b.c");
+        assert_eq!(server.refreshes.load(Ordering::SeqCst), 2);
+        assert_eq!(relogins.load(Ordering::SeqCst), 1);
+        let saved = Config::load_from(&config_file).unwrap().auth;
+        assert_eq!(saved.api_key.as_deref(), Some("relogged"));
+    }
+
+    #[tokio::test]
+    async fn test_session_without_refresh_token_is_relogged_and_replayed() {
+        let server = Arc::new(AuthServer::default());
+        *server.valid_token.lock().unwrap() = "current".to_string();
+
+        let mut config = Config::default();
+        config.set_endpoint(serve(server.clone()).await);
+        config.set_session(AuthConfig {
+            api_key: Some("stale".to_string()),
+            refresh_token: None,
+            expires_at: None,
+        });
+
+        let relogins = Arc::new(AtomicUsize::new(0));
+        let counter = relogins.clone();
+        let client = ApiClient::builder(&config)
+            .on_session_expired(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                async {
+                    Ok(AuthConfig {
+                        api_key: Some("current".to_string()),
+                        refresh_token: None,
+                        expires_at: None,
+                    })
+                }
+            })
+            .build()
+            .unwrap();
+
+        assert_eq!(client.whoami().await.unwrap().email, "a@b.c");
+        assert_eq!(relogins.load(Ordering::SeqCst), 1);
+        assert_eq!(server.refreshes.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_resource_from_path() {
+        assert_eq!(
+            resource_from_path("/api/v1/jobs/job_123/progress"),
+            Some(("Job".to_string(), "job_123".to_string()))
+        );
+        assert_eq!(
+            resource_from_path("/api/v1/assets/a1"),
+            Some(("Asset".to_string(), "a1".to_string()))
+        );
+        assert_eq!(resource_from_path("/api/v1/datasets"), None);
     }
 }
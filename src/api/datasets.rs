@@ -1,4 +1,5 @@
-use crate::api::ApiClient;
+use crate::api::pagination::{Page, PageRequest};
+use crate::api::{ApiClient, ProgressCallback};
 use crate::error::Result;
 use serde::{Deserialize, Serialize};
 
@@ -18,7 +19,7 @@ pub struct DatasetListResponse {
     pub total: i32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct DownloadUrlResponse {
     pub download_url: String,
     pub expires_at: String,
@@ -34,6 +35,17 @@ impl ApiClient {
         Ok(response.datasets)
     }
 
+    /// List a single page of datasets
+    pub async fn list_datasets_page(&self, page: PageRequest) -> Result<Page<Dataset>> {
+        let req = self.get("/api/v1/datasets").query(&page.query());
+        let response: DatasetListResponse = self.send_json(req).await?;
+        Ok(Page {
+            items: response.datasets,
+            total: response.total,
+            offset: page.offset,
+        })
+    }
+
     /// Get dataset details
     pub async fn get_dataset(&self, dataset_id: &str) -> Result<Dataset> {
         let req = self.get(&format!("/api/v1/datasets/{}", dataset_id));
@@ -46,11 +58,15 @@ impl ApiClient {
         self.send_json(req).await
     }
 
-    /// Download dataset to file
-    pub async fn download_dataset(&self, download_url: &str, output_path: &std::path::Path) -> Result<()> {
-        let response = reqwest::get(download_url).await?;
-        let bytes = response.bytes().await?;
-        tokio::fs::write(output_path, bytes).await?;
-        Ok(())
+    /// Download dataset to file, streaming it like [`ApiClient::download_to_file`].
+    ///
+    /// Returns the file's size.
+    pub async fn download_dataset(
+        &self,
+        download_url: &str,
+        output_path: &std::path::Path,
+        progress: Option<ProgressCallback>,
+    ) -> Result<u64> {
+        self.download_to_file(download_url, output_path, progress).await
     }
 }
@@ -0,0 +1,657 @@
+use crate::api::assets::{Asset, AssetQuery, AssetUpdate, AssetUploadMetadata, AssetUsage};
+use crate::api::auth::UserInfo;
+use crate::api::datasets::{Dataset, DownloadUrlResponse};
+use crate::api::jobs::{Job, JobProgress, JobStatus};
+use crate::api::pagination::{Page, PageRequest};
+use crate::api::uploads::{part_checksum, InitiateUploadRequest, UploadSession, UploadedPart};
+use crate::api::{ProgressCallback, ZereApi};
+use crate::config::AuthConfig;
+use crate::error::{ApiError, CliError, Result};
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+/// Timestamp used for everything the fake creates
+const CREATED_AT: &str = "2026-01-01T00:00:00Z";
+
+/// In-memory [`ZereApi`] for tests.
+///
+/// Holds jobs, assets and datasets in memory, records every call and can be
+/// scripted to walk a job through a sequence of progress updates or to fail
+/// the next call with a given error.
+#[derive(Default)]
+pub struct FakeApi {
+    state: Mutex<FakeState>,
+}
+
+#[derive(Default)]
+struct FakeState {
+    jobs: Vec<Job>,
+    assets: Vec<Asset>,
+    datasets: Vec<Dataset>,
+    /// Progress updates returned by successive `get_job_progress` calls; the last one repeats
+    progress: HashMap<String, VecDeque<JobProgress>>,
+    /// Outcome of upcoming calls; `None` lets the call through
+    failures: VecDeque<Option<CliError>>,
+    uploads: HashMap<String, FakeUpload>,
+    /// Thumbnail URL -> image bytes
+    thumbnails: HashMap<String, Vec<u8>>,
+    /// Asset ID -> what references it; other assets are unused
+    usage: HashMap<String, AssetUsage>,
+    /// Idempotency key -> job it created
+    idempotency_keys: HashMap<String, String>,
+    calls: Vec<String>,
+    next_id: i32,
+    /// Answer uploads of known content with the existing asset, like a deduplicating server
+    dedup: bool,
+}
+
+struct FakeUpload {
+    request: InitiateUploadRequest,
+    parts: BTreeMap<u32, UploadedPart>,
+}
+
+impl FakeApi {
+    pub fn new() -> Self {
+        FakeApi::default()
+    }
+
+    pub fn with_jobs(self, jobs: Vec<Job>) -> Self {
+        self.lock().jobs = jobs;
+        self
+    }
+
+    pub fn with_assets(self, assets: Vec<Asset>) -> Self {
+        self.lock().assets = assets;
+        self
+    }
+
+    pub fn with_thumbnail(self, thumbnail_url: &str, bytes: Vec<u8>) -> Self {
+        self.lock().thumbnails.insert(thumbnail_url.to_string(), bytes);
+        self
+    }
+
+    pub fn with_usage(self, asset_id: &str, usage: AssetUsage) -> Self {
+        self.lock().usage.insert(asset_id.to_string(), usage);
+        self
+    }
+
+    /// Make `upload_asset` return the existing asset when one has the same SHA-256
+    pub fn with_dedup(self) -> Self {
+        self.lock().dedup = true;
+        self
+    }
+
+    pub fn with_datasets(self, datasets: Vec<Dataset>) -> Self {
+        self.lock().datasets = datasets;
+        self
+    }
+
+    /// Make successive `get_job_progress` calls for `job_id` return `steps` in order
+    pub fn script_progress(&self, job_id: &str, steps: impl IntoIterator<Item = JobProgress>) {
+        self.lock()
+            .progress
+            .insert(job_id.to_string(), steps.into_iter().collect());
+    }
+
+    /// Make the next call fail with `error`
+    pub fn fail_next(&self, error: CliError) {
+        self.lock().failures.push_back(Some(error));
+    }
+
+    /// Make the `n`th call from now (counting from 1) fail with `error`
+    pub fn fail_nth(&self, n: usize, error: CliError) {
+        let mut state = self.lock();
+        if state.failures.len() < n {
+            state.failures.resize_with(n, || None);
+        }
+        state.failures[n - 1] = Some(error);
+    }
+
+    /// Calls made so far, e.g. `"cancel_job job-1"`
+    pub fn calls(&self) -> Vec<String> {
+        self.lock().calls.clone()
+    }
+
+    pub fn jobs(&self) -> Vec<Job> {
+        self.lock().jobs.clone()
+    }
+
+    pub fn assets(&self) -> Vec<Asset> {
+        self.lock().assets.clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, FakeState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Record a call, failing if a failure is scripted
+    fn record(&self, call: String) -> Result<()> {
+        self.begin(call).map(drop)
+    }
+
+    /// Record a call and return the state, or the scripted failure
+    fn begin(&self, call: String) -> Result<MutexGuard<'_, FakeState>> {
+        let mut state = self.lock();
+        state.calls.push(call);
+        match state.failures.pop_front().flatten() {
+            Some(error) => Err(error),
+            None => Ok(state),
+        }
+    }
+}
+
+/// A job as the backend returns it
+pub fn job(job_id: &str, status: JobStatus) -> Job {
+    Job {
+        id: 0,
+        job_id: job_id.to_string(),
+        name: format!("Job {}", job_id),
+        status,
+        num_scenes: 100,
+        progress: None,
+        created_at: CREATED_AT.to_string(),
+        completed_at: None,
+    }
+}
+
+/// An asset as the backend returns it
+pub fn asset(asset_id: &str, name: &str) -> Asset {
+    Asset {
+        id: 0,
+        asset_id: asset_id.to_string(),
+        name: name.to_string(),
+        category: None,
+        tags: Vec::new(),
+        file_type: "glb".to_string(),
+        size_bytes: 1_048_576,
+        thumbnail_url: None,
+        created_at: CREATED_AT.to_string(),
+        sha256: None,
+        mesh: None,
+    }
+}
+
+/// A dataset as the backend returns it
+pub fn dataset(job_id: &str) -> Dataset {
+    Dataset {
+        id: 0,
+        job_id: job_id.to_string(),
+        name: format!("Dataset {}", job_id),
+        size_bytes: 1_048_576,
+        format: "coco".to_string(),
+        created_at: CREATED_AT.to_string(),
+    }
+}
+
+/// A progress update for a job of 100 scenes
+pub fn progress(status: JobStatus, percent: f32) -> JobProgress {
+    JobProgress {
+        status,
+        progress: 100,
+        progress_percent: percent,
+        scenes_generated: percent as i32,
+        eta_seconds: None,
+    }
+}
+
+fn not_found(resource: &str, id: &str) -> CliError {
+    CliError::NotFound {
+        resource: resource.to_string(),
+        id: id.to_string(),
+        request_id: None,
+    }
+}
+
+fn page_of<T: Clone>(items: &[T], page: PageRequest) -> Page<T> {
+    Page {
+        items: items
+            .iter()
+            .skip(page.offset as usize)
+            .take(page.limit as usize)
+            .cloned()
+            .collect(),
+        total: items.len() as i32,
+        offset: page.offset,
+    }
+}
+
+#[async_trait]
+impl ZereApi for FakeApi {
+    async fn login(&self, email: String, _password: String) -> Result<AuthConfig> {
+        self.record(format!("login {}", email))?;
+        Ok(AuthConfig {
+            api_key: Some("fake-access-token".to_string()),
+            refresh_token: Some("fake-refresh-token".to_string()),
+            expires_at: None,
+        })
+    }
+
+    async fn whoami(&self) -> Result<UserInfo> {
+        self.record("whoami".to_string())?;
+        Ok(UserInfo {
+            id: 1,
+            email: "dev@example.com".to_string(),
+            organization_id: None,
+            organization_name: None,
+            role: None,
+        })
+    }
+
+    async fn logout(&self) -> Result<()> {
+        self.record("logout".to_string())?;
+        Ok(())
+    }
+
+    async fn list_jobs_page(&self, page: PageRequest) -> Result<Page<Job>> {
+        let state = self.begin(format!("list_jobs_page {}", page.offset))?;
+        Ok(page_of(&state.jobs, page))
+    }
+
+    async fn get_job(&self, job_id: &str) -> Result<Job> {
+        let state = self.begin(format!("get_job {}", job_id))?;
+        state
+            .jobs
+            .iter()
+            .find(|job| job.job_id == job_id)
+            .cloned()
+            .ok_or_else(|| not_found("Job", job_id))
+    }
+
+    async fn create_job(
+        &self,
+        name: String,
+        num_scenes: i32,
+        _config_yaml: Option<String>,
+        idempotency_key: Option<String>,
+    ) -> Result<Job> {
+        let mut state = self.begin(format!("create_job {}", name))?;
+        let replayed = idempotency_key
+            .as_ref()
+            .and_then(|key| state.idempotency_keys.get(key))
+            .and_then(|job_id| state.jobs.iter().find(|job| &job.job_id == job_id));
+        if let Some(job) = replayed {
+            return Ok(job.clone());
+        }
+
+        state.next_id += 1;
+        let job = Job {
+            id: state.next_id,
+            name,
+            num_scenes,
+            ..job(&format!("job-{}", state.next_id), JobStatus::Queued)
+        };
+        if let Some(key) = idempotency_key {
+            state.idempotency_keys.insert(key, job.job_id.clone());
+        }
+        state.jobs.push(job.clone());
+        Ok(job)
+    }
+
+    async fn get_job_progress(&self, job_id: &str) -> Result<JobProgress> {
+        let mut state = self.begin(format!("get_job_progress {}", job_id))?;
+        let scripted = match state.progress.get_mut(job_id) {
+            Some(steps) if steps.len() > 1 => steps.pop_front(),
+            Some(steps) => steps.front().cloned(),
+            None => None,
+        };
+
+        let job = state
+            .jobs
+            .iter_mut()
+            .find(|job| job.job_id == job_id)
+            .ok_or_else(|| not_found("Job", job_id))?;
+
+        let update = scripted.unwrap_or_else(|| {
+            let percent = job.progress.unwrap_or(0) as f32 * 100.0 / job.num_scenes.max(1) as f32;
+            progress(job.status.clone(), percent)
+        });
+        job.status = update.status.clone();
+        job.progress = Some(update.scenes_generated);
+        Ok(update)
+    }
+
+    async fn cancel_job(&self, job_id: &str) -> Result<()> {
+        let mut state = self.begin(format!("cancel_job {}", job_id))?;
+        let job = state
+            .jobs
+            .iter_mut()
+            .find(|job| job.job_id == job_id)
+            .ok_or_else(|| not_found("Job", job_id))?;
+
+        if !job.status.can_transition_to(&JobStatus::Cancelled) {
+            let message = format!("Job is already {}", job.status);
+            return Err(CliError::Conflict(ApiError::from_body(409, &message, None)));
+        }
+        job.status = JobStatus::Cancelled;
+        state.progress.remove(job_id);
+        Ok(())
+    }
+
+    async fn list_assets_page(&self, page: PageRequest) -> Result<Page<Asset>> {
+        let state = self.begin(format!("list_assets_page {}", page.offset))?;
+        Ok(page_of(&state.assets, page))
+    }
+
+    async fn search_assets_page(&self, query: &AssetQuery, page: PageRequest) -> Result<Page<Asset>> {
+        let state = self.begin(format!("search_assets_page {}", page.offset))?;
+        // Like the real API, apply everything but the name glob
+        let server_side = AssetQuery {
+            name: None,
+            ..query.clone()
+        };
+        let mut assets: Vec<Asset> = state.assets.iter().filter(|a| server_side.matches(a)).cloned().collect();
+        server_side.sort(&mut assets);
+        Ok(page_of(&assets, page))
+    }
+
+    async fn get_asset(&self, asset_id: &str) -> Result<Asset> {
+        let state = self.begin(format!("get_asset {}", asset_id))?;
+        state
+            .assets
+            .iter()
+            .find(|asset| asset.asset_id == asset_id)
+            .cloned()
+            .ok_or_else(|| not_found("Asset", asset_id))
+    }
+
+    async fn find_assets_by_hash(&self, sha256: &str) -> Result<Vec<Asset>> {
+        let state = self.begin(format!("find_assets_by_hash {}", sha256))?;
+        Ok(state
+            .assets
+            .iter()
+            .filter(|asset| asset.sha256.as_deref() == Some(sha256))
+            .cloned()
+            .collect())
+    }
+
+    async fn upload_asset(
+        &self,
+        file_path: &Path,
+        metadata: AssetUploadMetadata,
+        progress: Option<ProgressCallback>,
+    ) -> Result<Asset> {
+        self.record(format!("upload_asset {}", metadata.name))?;
+        let size_bytes = tokio::fs::metadata(file_path).await?.len();
+        if let Some(progress) = progress {
+            progress(size_bytes);
+        }
+
+        let mut state = self.lock();
+        if state.dedup && metadata.sha256.is_some() {
+            if let Some(existing) = state.assets.iter().find(|asset| asset.sha256 == metadata.sha256) {
+                return Ok(existing.clone());
+            }
+        }
+        state.next_id += 1;
+        let asset = Asset {
+            id: state.next_id,
+            file_type: file_path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or_default()
+                .to_string(),
+            size_bytes: size_bytes as i64,
+            category: metadata.category,
+            tags: metadata.tags,
+            sha256: metadata.sha256,
+            mesh: metadata.mesh,
+            ..asset(&format!("asset-{}", state.next_id), &metadata.name)
+        };
+        state.assets.push(asset.clone());
+        Ok(asset)
+    }
+
+    async fn initiate_upload(&self, request: &InitiateUploadRequest) -> Result<UploadSession> {
+        let mut state = self.begin(format!("initiate_upload {}", request.metadata.name))?;
+        state.next_id += 1;
+        let upload_id = format!("upload-{}", state.next_id);
+        state.uploads.insert(
+            upload_id.clone(),
+            FakeUpload {
+                request: request.clone(),
+                parts: BTreeMap::new(),
+            },
+        );
+        Ok(UploadSession {
+            upload_id,
+            part_size: request.part_size,
+            parts: Vec::new(),
+        })
+    }
+
+    async fn get_upload(&self, upload_id: &str) -> Result<UploadSession> {
+        let state = self.begin(format!("get_upload {}", upload_id))?;
+        let upload = state
+            .uploads
+            .get(upload_id)
+            .ok_or_else(|| not_found("Upload", upload_id))?;
+        Ok(UploadSession {
+            upload_id: upload_id.to_string(),
+            part_size: upload.request.part_size,
+            parts: upload.parts.values().cloned().collect(),
+        })
+    }
+
+    async fn upload_part(
+        &self,
+        upload_id: &str,
+        part_number: u32,
+        data: Vec<u8>,
+        sha256: &str,
+    ) -> Result<UploadedPart> {
+        let mut state = self.begin(format!("upload_part {} {}", upload_id, part_number))?;
+        let upload = state
+            .uploads
+            .get_mut(upload_id)
+            .ok_or_else(|| not_found("Upload", upload_id))?;
+        if part_checksum(&data) != sha256 {
+            let message = format!("Checksum mismatch for part {}", part_number);
+            return Err(CliError::Validation(ApiError::from_body(422, &message, None)));
+        }
+
+        let part = UploadedPart {
+            part_number,
+            sha256: sha256.to_string(),
+            size_bytes: data.len() as u64,
+        };
+        upload.parts.insert(part_number, part.clone());
+        Ok(part)
+    }
+
+    async fn complete_upload(&self, upload_id: &str, parts: &[UploadedPart]) -> Result<Asset> {
+        let mut state = self.begin(format!("complete_upload {}", upload_id))?;
+        let upload = state
+            .uploads
+            .get(upload_id)
+            .ok_or_else(|| not_found("Upload", upload_id))?;
+        if !parts.iter().eq(upload.parts.values()) {
+            let message = "Parts do not match what was uploaded";
+            return Err(CliError::Validation(ApiError::from_body(422, message, None)));
+        }
+        let file_name = upload.request.file_name.clone();
+        let metadata = upload.request.metadata.clone();
+        state.uploads.remove(upload_id);
+
+        state.next_id += 1;
+        let asset = Asset {
+            id: state.next_id,
+            file_type: Path::new(&file_name)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or_default()
+                .to_string(),
+            size_bytes: parts.iter().map(|part| part.size_bytes).sum::<u64>() as i64,
+            category: metadata.category,
+            tags: metadata.tags,
+            sha256: metadata.sha256,
+            mesh: metadata.mesh,
+            ..asset(&format!("asset-{}", state.next_id), &metadata.name)
+        };
+        state.assets.push(asset.clone());
+        Ok(asset)
+    }
+
+    async fn get_thumbnail(&self, thumbnail_url: &str) -> Result<Vec<u8>> {
+        let state = self.begin(format!("get_thumbnail {}", thumbnail_url))?;
+        state
+            .thumbnails
+            .get(thumbnail_url)
+            .cloned()
+            .ok_or_else(|| not_found("Thumbnail", thumbnail_url))
+    }
+
+    async fn download_asset(
+        &self,
+        asset_id: &str,
+        output_path: &Path,
+        progress: Option<ProgressCallback>,
+    ) -> Result<u64> {
+        {
+            let state = self.begin(format!("download_asset {}", asset_id))?;
+            if !state.assets.iter().any(|asset| asset.asset_id == asset_id) {
+                return Err(not_found("Asset", asset_id));
+            }
+        }
+        tokio::fs::write(output_path, asset_id.as_bytes()).await?;
+        let size_bytes = asset_id.len() as u64;
+        if let Some(progress) = progress {
+            progress(size_bytes);
+        }
+        Ok(size_bytes)
+    }
+
+    async fn update_asset(&self, asset_id: &str, update: &AssetUpdate) -> Result<Asset> {
+        let mut state = self.begin(format!("update_asset {}", asset_id))?;
+        let asset = state
+            .assets
+            .iter_mut()
+            .find(|asset| asset.asset_id == asset_id)
+            .ok_or_else(|| not_found("Asset", asset_id))?;
+        if let Some(name) = &update.name {
+            asset.name = name.clone();
+        }
+        if let Some(category) = &update.category {
+            asset.category = Some(category.clone());
+        }
+        if let Some(tags) = &update.tags {
+            asset.tags = tags.clone();
+        }
+        Ok(asset.clone())
+    }
+
+    async fn asset_usage(&self, asset_id: &str) -> Result<AssetUsage> {
+        let state = self.begin(format!("asset_usage {}", asset_id))?;
+        if !state.assets.iter().any(|asset| asset.asset_id == asset_id) {
+            return Err(not_found("Asset", asset_id));
+        }
+        Ok(state.usage.get(asset_id).cloned().unwrap_or_default())
+    }
+
+    async fn delete_asset(&self, asset_id: &str) -> Result<()> {
+        let mut state = self.begin(format!("delete_asset {}", asset_id))?;
+        let before = state.assets.len();
+        state.assets.retain(|asset| asset.asset_id != asset_id);
+        if state.assets.len() == before {
+            return Err(not_found("Asset", asset_id));
+        }
+        Ok(())
+    }
+
+    async fn list_datasets_page(&self, page: PageRequest) -> Result<Page<Dataset>> {
+        let state = self.begin(format!("list_datasets_page {}", page.offset))?;
+        Ok(page_of(&state.datasets, page))
+    }
+
+    async fn get_dataset(&self, dataset_id: &str) -> Result<Dataset> {
+        let state = self.begin(format!("get_dataset {}", dataset_id))?;
+        state
+            .datasets
+            .iter()
+            .find(|dataset| dataset.job_id == dataset_id)
+            .cloned()
+            .ok_or_else(|| not_found("Dataset", dataset_id))
+    }
+
+    async fn get_download_url(&self, job_id: &str) -> Result<DownloadUrlResponse> {
+        let state = self.begin(format!("get_download_url {}", job_id))?;
+        let dataset = state
+            .datasets
+            .iter()
+            .find(|dataset| dataset.job_id == job_id)
+            .ok_or_else(|| not_found("Dataset", job_id))?;
+        Ok(DownloadUrlResponse {
+            download_url: format!("https://storage.invalid/datasets/{}.zip", job_id),
+            expires_at: CREATED_AT.to_string(),
+            size_bytes: dataset.size_bytes,
+            format: dataset.format.clone(),
+        })
+    }
+
+    async fn download_dataset(
+        &self,
+        download_url: &str,
+        output_path: &Path,
+        progress: Option<ProgressCallback>,
+    ) -> Result<u64> {
+        self.record(format!("download_dataset {}", download_url))?;
+        tokio::fs::write(output_path, download_url.as_bytes()).await?;
+        let written = download_url.len() as u64;
+        if let Some(progress) = progress {
+            progress(written);
+        }
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_scripted_progress_advances_job() {
+        let api = FakeApi::new().with_jobs(vec![job("job-1", JobStatus::Queued)]);
+        api.script_progress(
+            "job-1",
+            [
+                progress(JobStatus::Running, 50.0),
+                progress(JobStatus::Completed, 100.0),
+            ],
+        );
+
+        assert_eq!(api.get_job_progress("job-1").await.unwrap().status, JobStatus::Running);
+        assert_eq!(api.get_job_progress("job-1").await.unwrap().status, JobStatus::Completed);
+        // The last step repeats
+        assert_eq!(api.get_job_progress("job-1").await.unwrap().status, JobStatus::Completed);
+        assert_eq!(api.get_job("job-1").await.unwrap().status, JobStatus::Completed);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_finished_job_conflicts() {
+        let api = FakeApi::new().with_jobs(vec![job("job-1", JobStatus::Completed)]);
+        let err = api.cancel_job("job-1").await.unwrap_err();
+        assert!(matches!(err, CliError::Conflict(_)));
+    }
+
+    #[tokio::test]
+    async fn test_create_job_replays_idempotency_key() {
+        let api = FakeApi::new();
+        let key = Some("key-1".to_string());
+
+        let first = api.create_job("a".to_string(), 10, None, key.clone()).await.unwrap();
+        let replay = api.create_job("a".to_string(), 10, None, key).await.unwrap();
+
+        assert_eq!(first.job_id, replay.job_id);
+        assert_eq!(api.jobs().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_fail_next() {
+        let api = FakeApi::new();
+        api.fail_next(CliError::SessionExpired);
+
+        assert!(matches!(api.whoami().await, Err(CliError::SessionExpired)));
+        assert!(api.whoami().await.is_ok());
+    }
+}
@@ -0,0 +1,150 @@
+use crate::config::Config;
+use crate::error::{CliError, Result};
+use crate::time::unix_now;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Header the backend uses to deduplicate replayed requests
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// How long the backend remembers a key; older pending keys are dropped
+const KEY_TTL_SECS: i64 = 24 * 60 * 60;
+
+/// Generate a fresh random idempotency key
+pub fn generate_key() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Stable fingerprint of a request's parameters, used to find its pending key
+pub fn fingerprint(parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.len().to_le_bytes());
+        hasher.update(part.as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PendingKey {
+    key: String,
+    created_at: i64,
+}
+
+/// Whether a request may have taken effect despite failing with `err`.
+///
+/// Only then is its key worth keeping for a rerun; after a definitive rejection (4xx)
+/// the next attempt should get a fresh key, or the server would replay the rejection.
+pub fn outcome_unknown(err: &CliError) -> bool {
+    match err {
+        // Lost connections and timeouts, or a success response we could not read
+        CliError::Network(_) | CliError::Io(_) | CliError::InvalidResponse => true,
+        _ => err.api_error().is_some_and(|error| error.status >= 500),
+    }
+}
+
+/// Idempotency keys of requests that have not yet succeeded, persisted so that
+/// rerunning the same command after a dropped connection reuses the same key
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PendingKeys {
+    #[serde(default)]
+    keys: HashMap<String, PendingKey>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl PendingKeys {
+    /// Load pending keys from the config dir, dropping expired ones
+    pub fn load() -> Result<Self> {
+        let path = Config::config_dir()?.join("pending_requests.json");
+        let mut pending: PendingKeys = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_default(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => PendingKeys::default(),
+            Err(e) => return Err(e.into()),
+        };
+        pending.path = path;
+
+        let now = unix_now();
+        pending.keys.retain(|_, pending| now - pending.created_at < KEY_TTL_SECS);
+        Ok(pending)
+    }
+
+    /// Key for a request, reusing the pending one if an earlier attempt did not finish.
+    ///
+    /// Returns the key and whether it was reused. New keys are saved right away so a
+    /// crash or dropped connection mid-request still leaves them on disk.
+    pub fn key_for(&mut self, fingerprint: &str) -> Result<(String, bool)> {
+        if let Some(pending) = self.keys.get(fingerprint) {
+            return Ok((pending.key.clone(), true));
+        }
+
+        let key = generate_key();
+        self.keys.insert(
+            fingerprint.to_string(),
+            PendingKey {
+                key: key.clone(),
+                created_at: unix_now(),
+            },
+        );
+        self.save()?;
+        Ok((key, false))
+    }
+
+    /// Forget the key of a request that succeeded
+    pub fn complete(&mut self, fingerprint: &str) -> Result<()> {
+        if self.keys.remove(fingerprint).is_some() {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ApiError;
+
+    #[test]
+    fn test_fingerprint_separates_parts() {
+        assert_eq!(fingerprint(&["a", "bc"]), fingerprint(&["a", "bc"]));
+        assert_ne!(fingerprint(&["a", "bc"]), fingerprint(&["ab", "c"]));
+    }
+
+    #[test]
+    fn test_only_unknown_outcomes_keep_the_key() {
+        let server = CliError::Server(ApiError::from_body(503, "", None));
+        let rejected = CliError::Validation(ApiError::from_body(422, "", None));
+        let timeout = CliError::Io(std::io::Error::from(std::io::ErrorKind::TimedOut));
+
+        assert!(outcome_unknown(&server));
+        assert!(outcome_unknown(&timeout));
+        assert!(!outcome_unknown(&rejected));
+        assert!(!outcome_unknown(&CliError::SessionExpired));
+    }
+
+    #[test]
+    fn test_pending_key_reused_until_complete() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut pending = PendingKeys {
+            path: dir.path().join("pending_requests.json"),
+            ..PendingKeys::default()
+        };
+
+        let (key, reused) = pending.key_for("job").unwrap();
+        assert!(!reused);
+        assert_eq!(pending.key_for("job").unwrap(), (key.clone(), true));
+
+        pending.complete("job").unwrap();
+        assert_ne!(pending.key_for("job").unwrap().0, key);
+    }
+}
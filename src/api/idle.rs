@@ -0,0 +1,245 @@
+//! Idle timeout for uploads.
+//!
+//! Upload clients have no read timeout: the server sends nothing while a large body
+//! goes out, so a read timeout would fail healthy uploads that simply take long.
+//! Instead the body itself is watched, and the request fails once no chunk was sent
+//! for the idle timeout. Once the whole body is out, the same timeout bounds the wait
+//! for the server's answer, so a server that never responds cannot hang an upload.
+
+use crate::error::{CliError, Result};
+use http_body::{Body as HttpBody, Frame, SizeHint};
+use reqwest::header::{HeaderValue, CONTENT_LENGTH};
+use reqwest::{Body, Request, Response};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Large data frames are sent in chunks of this size, so their progress can be observed
+const CHUNK_SIZE: usize = 64 * 1024;
+
+type Chunk = <Body as HttpBody>::Data;
+
+/// Timer that restarts whenever the watched request body makes progress
+#[derive(Clone)]
+pub(crate) struct IdleTimer {
+    idle: Duration,
+    last_activity: Arc<Mutex<Instant>>,
+    /// Set once the whole body was sent
+    finished: Arc<AtomicBool>,
+}
+
+impl IdleTimer {
+    pub(crate) fn new(idle: Duration) -> Self {
+        IdleTimer {
+            idle,
+            last_activity: Arc::new(Mutex::new(Instant::now())),
+            finished: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    fn touch(&self) {
+        *self.last_activity.lock().unwrap_or_else(PoisonError::into_inner) = Instant::now();
+    }
+
+    fn finish(&self) {
+        self.touch();
+        self.finished.store(true, Ordering::SeqCst);
+    }
+
+    fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::SeqCst)
+    }
+
+    fn deadline(&self) -> Instant {
+        *self.last_activity.lock().unwrap_or_else(PoisonError::into_inner) + self.idle
+    }
+
+    /// Restart the timer every time a chunk of the request's body is sent
+    pub(crate) fn watch(&self, request: &mut Request) {
+        let Some(body) = request.body_mut().take() else {
+            return;
+        };
+        if let Some(bytes) = body.as_bytes() {
+            request
+                .headers_mut()
+                .entry(CONTENT_LENGTH)
+                .or_insert_with(|| HeaderValue::from(bytes.len()));
+        }
+        *request.body_mut() = Some(Body::wrap(WatchedBody {
+            inner: body,
+            pending: Chunk::new(),
+            timer: self.clone(),
+        }));
+    }
+
+    /// Wait for `send`, failing once the timer runs out while sending the body or
+    /// waiting for the answer after it
+    pub(crate) async fn run(&self, send: impl Future<Output = reqwest::Result<Response>>) -> Result<Response> {
+        tokio::pin!(send);
+        self.touch();
+        loop {
+            let deadline = self.deadline();
+            tokio::select! {
+                result = &mut send => return Ok(result?),
+                _ = tokio::time::sleep_until(deadline) => {
+                    // The body may have moved on while we slept
+                    if self.deadline() <= Instant::now() {
+                        let message = if self.is_finished() {
+                            format!("upload sent, but no response for {}s", self.idle.as_secs())
+                        } else {
+                            format!("upload stalled: no progress for {}s", self.idle.as_secs())
+                        };
+                        return Err(CliError::Io(std::io::Error::new(std::io::ErrorKind::TimedOut, message)));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Request body that restarts an [`IdleTimer`] as its chunks are sent
+struct WatchedBody {
+    inner: Body,
+    /// Rest of a large data frame, handed out in slices of [`CHUNK_SIZE`]
+    pending: Chunk,
+    timer: IdleTimer,
+}
+
+impl WatchedBody {
+    /// The next slice of the pending frame, without copying it
+    fn next_chunk(&mut self) -> Frame<Chunk> {
+        let len = self.pending.len().min(CHUNK_SIZE);
+        let chunk = self.pending.split_to(len);
+        self.timer.touch();
+        if self.is_end_stream() {
+            self.timer.finish();
+        }
+        Frame::data(chunk)
+    }
+}
+
+impl HttpBody for WatchedBody {
+    type Data = <Body as HttpBody>::Data;
+    type Error = <Body as HttpBody>::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<std::result::Result<Frame<Self::Data>, Self::Error>>> {
+        if !self.pending.is_empty() {
+            return Poll::Ready(Some(Ok(self.next_chunk())));
+        }
+        match Pin::new(&mut self.inner).poll_frame(cx) {
+            Poll::Ready(None) => {
+                self.timer.finish();
+                Poll::Ready(None)
+            }
+            Poll::Ready(Some(Ok(frame))) => match frame.into_data() {
+                Ok(data) => {
+                    self.pending = data;
+                    Poll::Ready(Some(Ok(self.next_chunk())))
+                }
+                Err(frame) => {
+                    self.timer.touch();
+                    Poll::Ready(Some(Ok(frame)))
+                }
+            },
+            poll => poll,
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.pending.is_empty() && self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        let inner = self.inner.size_hint();
+        let pending = self.pending.len() as u64;
+        let mut hint = SizeHint::new();
+        hint.set_lower(inner.lower() + pending);
+        if let Some(upper) = inner.upper() {
+            hint.set_upper(upper + pending);
+        }
+        hint
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stall_fails_only_after_idle_period_without_progress() {
+        let timer = IdleTimer::new(Duration::from_secs(10));
+
+        // Progress every 5s keeps a transfer alive well past the idle timeout
+        let progressing = timer.clone();
+        let send = async move {
+            for _ in 0..6 {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                progressing.touch();
+            }
+            std::future::pending::<reqwest::Result<Response>>().await
+        };
+
+        let started = Instant::now();
+        let err = timer.run(send).await.unwrap_err();
+        assert!(matches!(err, CliError::Io(ref e) if e.kind() == std::io::ErrorKind::TimedOut));
+        assert_eq!(started.elapsed(), Duration::from_secs(40));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_server_gets_idle_period_to_answer_once_body_was_sent() {
+        let timer = IdleTimer::new(Duration::from_secs(10));
+
+        // The server processes the upload for a while after the last byte, then answers
+        let sending = timer.clone();
+        let send = async move {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            sending.finish();
+            tokio::time::sleep(Duration::from_secs(8)).await;
+            Ok(Response::from(axum::http::Response::new("done")))
+        };
+        let response = timer.run(send).await.unwrap();
+        assert_eq!(response.text().await.unwrap(), "done");
+
+        // A server that never answers fails the upload instead of hanging it
+        let timer = IdleTimer::new(Duration::from_secs(10));
+        let sending = timer.clone();
+        let send = async move {
+            sending.finish();
+            std::future::pending::<reqwest::Result<Response>>().await
+        };
+        let started = Instant::now();
+        let err = timer.run(send).await.unwrap_err();
+        assert!(err.to_string().contains("no response for 10s"), "{}", err);
+        assert_eq!(started.elapsed(), Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn test_large_frames_are_sent_in_chunks() {
+        let timer = IdleTimer::new(Duration::from_secs(10));
+        let data = vec![7u8; CHUNK_SIZE * 2 + 10];
+        let mut body = WatchedBody {
+            inner: Body::from(data.clone()),
+            pending: Chunk::new(),
+            timer: timer.clone(),
+        };
+        assert_eq!(body.size_hint().exact(), Some(data.len() as u64));
+
+        let mut sizes = Vec::new();
+        let mut sent = Vec::new();
+        while let Some(frame) = std::future::poll_fn(|cx| Pin::new(&mut body).poll_frame(cx)).await {
+            let chunk = frame.unwrap().into_data().unwrap();
+            sizes.push(chunk.len());
+            sent.extend_from_slice(&chunk);
+        }
+        assert_eq!(sizes, [CHUNK_SIZE, CHUNK_SIZE, 10]);
+        assert_eq!(sent, data);
+        assert!(timer.is_finished());
+    }
+}
@@ -1,19 +1,112 @@
+use crate::api::idempotency::{generate_key, IDEMPOTENCY_KEY_HEADER};
+use crate::api::pagination::{Page, PageRequest};
 use crate::api::ApiClient;
+use crate::config::OperationClass;
 use crate::error::Result;
+use reqwest::Method;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Job {
     pub id: i32,
     pub job_id: String,
     pub name: String,
-    pub status: String,
+    pub status: JobStatus,
     pub num_scenes: i32,
     pub progress: Option<i32>,
     pub created_at: String,
     pub completed_at: Option<String>,
 }
 
+/// Lifecycle state of a generation job.
+///
+/// Parsing accepts aliases some backends send (`pending`, `canceled`), but the status
+/// is always written back under its canonical name from [`JobStatus::as_str`]. The
+/// mapping is one-way on purpose: the CLI only reads job statuses, never sends them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Paused,
+    Completed,
+    Failed,
+    Cancelled,
+    /// A status this version of the CLI does not know about yet
+    Unknown(String),
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &str {
+        match self {
+            JobStatus::Queued => "queued",
+            JobStatus::Running => "running",
+            JobStatus::Paused => "paused",
+            JobStatus::Completed => "completed",
+            JobStatus::Failed => "failed",
+            JobStatus::Cancelled => "cancelled",
+            JobStatus::Unknown(status) => status,
+        }
+    }
+
+    /// The job will not change state again
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled)
+    }
+
+    /// The job is waiting for or holding workers
+    pub fn is_active(&self) -> bool {
+        matches!(self, JobStatus::Queued | JobStatus::Running | JobStatus::Paused)
+    }
+
+    /// Whether the backend may move a job from this state to `next`
+    pub fn can_transition_to(&self, next: &JobStatus) -> bool {
+        use JobStatus::*;
+        match (self, next) {
+            (Queued, Running | Cancelled | Failed) => true,
+            (Running, Paused | Completed | Failed | Cancelled) => true,
+            (Paused, Running | Cancelled | Failed) => true,
+            // We cannot reason about states we don't know
+            (Unknown(_), _) | (_, Unknown(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+impl From<&str> for JobStatus {
+    fn from(status: &str) -> Self {
+        match status.to_ascii_lowercase().as_str() {
+            "queued" | "pending" => JobStatus::Queued,
+            "running" => JobStatus::Running,
+            "paused" => JobStatus::Paused,
+            "completed" => JobStatus::Completed,
+            "failed" => JobStatus::Failed,
+            "cancelled" | "canceled" => JobStatus::Cancelled,
+            _ => JobStatus::Unknown(status.to_string()),
+        }
+    }
+}
+
+impl From<String> for JobStatus {
+    fn from(status: String) -> Self {
+        JobStatus::from(status.as_str())
+    }
+}
+
+impl From<JobStatus> for String {
+    fn from(status: JobStatus) -> Self {
+        status.as_str().to_string()
+    }
+}
+
+impl fmt::Display for JobStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Pad via `f.pad` so `{:12}` aligns columns
+        f.pad(self.as_str())
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct JobListResponse {
     pub jobs: Vec<Job>,
@@ -27,9 +120,9 @@ pub struct CreateJobRequest {
     pub config_yaml: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct JobProgress {
-    pub status: String,
+    pub status: JobStatus,
     pub progress: i32,
     pub progress_percent: f32,
     pub scenes_generated: i32,
@@ -44,36 +137,86 @@ impl ApiClient {
         Ok(response.jobs)
     }
 
+    /// List a single page of jobs
+    pub async fn list_jobs_page(&self, page: PageRequest) -> Result<Page<Job>> {
+        let req = self.get("/api/v1/jobs").query(&page.query());
+        let response: JobListResponse = self.send_json(req).await?;
+        Ok(Page {
+            items: response.jobs,
+            total: response.total,
+            offset: page.offset,
+        })
+    }
+
     /// Get job details
     pub async fn get_job(&self, job_id: &str) -> Result<Job> {
         let req = self.get(&format!("/api/v1/jobs/{}", job_id));
         self.send_json(req).await
     }
 
-    /// Create a new job
+    /// Create a new job.
+    ///
+    /// The request carries an idempotency key (a fresh one unless given), so it is
+    /// retried on transient failures and replaying a key returns the original job.
     pub async fn create_job(
         &self,
         name: String,
         num_scenes: i32,
         config_yaml: Option<String>,
+        idempotency_key: Option<String>,
     ) -> Result<Job> {
-        let req = self.post("/api/v1/jobs").json(&CreateJobRequest {
-            name,
-            num_scenes,
-            config_yaml,
-        });
+        let key = idempotency_key.unwrap_or_else(generate_key);
+        let req = self
+            .post("/api/v1/jobs")
+            .header(IDEMPOTENCY_KEY_HEADER, key)
+            .json(&CreateJobRequest {
+                name,
+                num_scenes,
+                config_yaml,
+            });
         self.send_json(req).await
     }
 
     /// Get job progress
     pub async fn get_job_progress(&self, job_id: &str) -> Result<JobProgress> {
-        let req = self.get(&format!("/api/v1/jobs/{}/progress", job_id));
+        let path = format!("/api/v1/jobs/{}/progress", job_id);
+        let req = self.request(OperationClass::Poll, Method::GET, &path);
         self.send_json(req).await
     }
 
     /// Cancel a job
     pub async fn cancel_job(&self, job_id: &str) -> Result<()> {
+        // Cancelling is safe to replay, so retry it like an idempotent call
         let req = self.post(&format!("/api/v1/jobs/{}/cancel", job_id));
-        self.send(req).await
+        self.send_retrying(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_round_trip() {
+        let status: JobStatus = serde_json::from_str(r#""canceled""#).unwrap();
+        assert_eq!(status, JobStatus::Cancelled);
+        assert_eq!(serde_json::to_string(&status).unwrap(), r#""cancelled""#);
+
+        let pending: JobStatus = serde_json::from_str(r#""pending""#).unwrap();
+        assert_eq!(pending, JobStatus::Queued);
+        assert_eq!(serde_json::to_string(&pending).unwrap(), r#""queued""#);
+
+        let unknown: JobStatus = serde_json::from_str(r#""archived""#).unwrap();
+        assert_eq!(unknown, JobStatus::Unknown("archived".to_string()));
+        assert_eq!(unknown.to_string(), "archived");
+    }
+
+    #[test]
+    fn test_status_transitions() {
+        assert!(JobStatus::Queued.can_transition_to(&JobStatus::Running));
+        assert!(JobStatus::Running.can_transition_to(&JobStatus::Paused));
+        assert!(!JobStatus::Completed.can_transition_to(&JobStatus::Running));
+        assert!(JobStatus::Cancelled.is_terminal());
+        assert!(JobStatus::Paused.is_active());
     }
 }
@@ -2,6 +2,21 @@ pub mod auth;
 pub mod assets;
 pub mod client;
 pub mod datasets;
+pub mod fake;
+mod idle;
+pub mod idempotency;
 pub mod jobs;
+pub mod pagination;
+pub mod redact;
+pub mod retry;
+pub mod traits;
+pub mod uploads;
 
-pub use client::ApiClient;
+pub use client::{ApiClient, ApiClientBuilder};
+pub use traits::ZereApi;
+
+/// Called with the number of bytes transferred so far
+pub type ProgressCallback = std::sync::Arc<dyn Fn(u64) + Send + Sync>;
+
+/// Tracing target used for HTTP request/response logs
+pub const HTTP_TARGET: &str = "zere::http";
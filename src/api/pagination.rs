@@ -0,0 +1,121 @@
+use crate::error::{CliError, Result};
+use futures::stream::{self, Stream};
+use futures::TryStreamExt;
+use std::future::Future;
+
+/// Default number of items requested per page
+pub const DEFAULT_PAGE_SIZE: u32 = 50;
+
+/// A window into a server-side list, sent as `limit`/`offset` query params
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageRequest {
+    pub limit: u32,
+    pub offset: u32,
+}
+
+impl PageRequest {
+    /// Request the given 1-based page with `limit` items per page
+    pub fn page(limit: u32, page: u32) -> Self {
+        PageRequest {
+            limit,
+            offset: limit.saturating_mul(page.saturating_sub(1)),
+        }
+    }
+
+    pub fn query(&self) -> [(&'static str, u32); 2] {
+        [("limit", self.limit), ("offset", self.offset)]
+    }
+}
+
+impl Default for PageRequest {
+    fn default() -> Self {
+        PageRequest::page(DEFAULT_PAGE_SIZE, 1)
+    }
+}
+
+/// One page of a list response together with the server's total count
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: i32,
+    pub offset: u32,
+}
+
+impl<T> Page<T> {
+    /// Offset of the next page, or `None` if this was the last one
+    pub fn next_offset(&self) -> Option<u32> {
+        let next = self.offset + self.items.len() as u32;
+        // An empty page means the server has nothing more, whatever `total` says
+        if self.items.is_empty() || i64::from(next) >= i64::from(self.total) {
+            None
+        } else {
+            Some(next)
+        }
+    }
+}
+
+/// Turn a page fetcher into a stream of items that transparently walks every page
+pub fn paginate<T, F, Fut>(page_size: u32, fetch: F) -> impl Stream<Item = Result<T>>
+where
+    F: Fn(PageRequest) -> Fut,
+    Fut: Future<Output = Result<Page<T>>>,
+{
+    let pages = stream::try_unfold((fetch, Some(0)), move |(fetch, offset)| async move {
+        let Some(offset) = offset else {
+            return Ok::<_, CliError>(None);
+        };
+        let page = fetch(PageRequest {
+            limit: page_size,
+            offset,
+        })
+        .await?;
+        let next = page.next_offset();
+        Ok(Some((page.items, (fetch, next))))
+    });
+
+    pages
+        .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+        .try_flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_request_offsets() {
+        assert_eq!(PageRequest::page(25, 1).offset, 0);
+        assert_eq!(PageRequest::page(25, 3).offset, 50);
+        assert_eq!(PageRequest::page(25, 0).offset, 0);
+    }
+
+    #[test]
+    fn test_next_offset() {
+        let page = Page { items: vec![1, 2], total: 5, offset: 2 };
+        assert_eq!(page.next_offset(), Some(4));
+
+        let last = Page { items: vec![5], total: 5, offset: 4 };
+        assert_eq!(last.next_offset(), None);
+
+        let empty: Page<i32> = Page { items: vec![], total: 10, offset: 4 };
+        assert_eq!(empty.next_offset(), None);
+    }
+
+    #[tokio::test]
+    async fn test_paginate_walks_every_page() {
+        let all: Vec<i32> = (0..7).collect();
+        let items: Vec<i32> = paginate(3, |req| {
+            let all = all.clone();
+            async move {
+                let start = req.offset as usize;
+                let end = (start + req.limit as usize).min(all.len());
+                Ok(Page { items: all[start..end].to_vec(), total: all.len() as i32, offset: req.offset })
+            }
+        })
+        .try_collect()
+        .await
+        .unwrap();
+
+        assert_eq!(items, all);
+    }
+}
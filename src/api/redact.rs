@@ -0,0 +1,139 @@
+use serde_json::Value;
+
+const REDACTED: &str = "***";
+
+/// JSON keys whose values are never logged
+const SECRET_KEYS: &[&str] = &[
+    "password",
+    "access_token",
+    "refresh_token",
+    "api_key",
+    "token",
+    "secret",
+];
+
+/// Query parameters that carry credentials or presigned-URL signatures
+const SECRET_QUERY_PARAMS: &[&str] = &[
+    "signature",
+    "credential",
+    "security-token",
+    "token",
+    "sig",
+    "key",
+    "policy",
+];
+
+/// Redact credentials in the query string of a URL, e.g. `X-Amz-Signature`
+pub fn redact_url(url: &str) -> String {
+    let Some((base, query)) = url.split_once('?') else {
+        return url.to_string();
+    };
+
+    let (query, fragment) = match query.split_once('#') {
+        Some((query, fragment)) => (query, Some(fragment)),
+        None => (query, None),
+    };
+
+    let params: Vec<String> = query
+        .split('&')
+        .map(|param| match param.split_once('=') {
+            Some((name, _)) if is_secret_param(name) => format!("{}={}", name, REDACTED),
+            _ => param.to_string(),
+        })
+        .collect();
+
+    let mut redacted = format!("{}?{}", base, params.join("&"));
+    if let Some(fragment) = fragment {
+        redacted.push('#');
+        redacted.push_str(fragment);
+    }
+    redacted
+}
+
+fn is_secret_param(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SECRET_QUERY_PARAMS.iter().any(|secret| {
+        name == *secret
+            || name.ends_with(&format!("-{}", secret))
+            || name.ends_with(&format!("_{}", secret))
+    })
+}
+
+/// Redact a request or response body for logging.
+///
+/// JSON bodies have secret fields masked and embedded URLs scrubbed; other
+/// bodies are summarized by size only, since they may be binary uploads.
+pub fn redact_body(body: &[u8]) -> String {
+    match serde_json::from_slice::<Value>(body) {
+        Ok(mut value) => {
+            redact_json(&mut value);
+            value.to_string()
+        }
+        Err(_) => format!("<{} bytes>", body.len()),
+    }
+}
+
+fn redact_json(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if SECRET_KEYS.contains(&key.to_ascii_lowercase().as_str()) {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_json(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_json),
+        Value::String(s) if s.starts_with("http://") || s.starts_with("https://") => {
+            *s = redact_url(s);
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_presigned_url() {
+        let url = "https://bucket.s3.amazonaws.com/ds.zip?X-Amz-Algorithm=AWS4-HMAC-SHA256&X-Amz-Credential=AKIA%2F20240101&X-Amz-Signature=abcdef&X-Amz-Expires=3600";
+        let redacted = redact_url(url);
+
+        assert!(redacted.contains("X-Amz-Algorithm=AWS4-HMAC-SHA256"));
+        assert!(redacted.contains("X-Amz-Credential=***"));
+        assert!(redacted.contains("X-Amz-Signature=***"));
+        assert!(redacted.contains("X-Amz-Expires=3600"));
+        assert!(!redacted.contains("abcdef"));
+    }
+
+    #[test]
+    fn test_redact_url_without_query() {
+        assert_eq!(redact_url("https://api.zeredata.com/api/v1/jobs"), "https://api.zeredata.com/api/v1/jobs");
+    }
+
+    #[test]
+    fn test_redact_login_body() {
+        let body = br#"{"email": "me@example.com", "password": "hunter2"}"#;
+        let redacted = redact_body(body);
+
+        assert!(redacted.contains("me@example.com"));
+        assert!(!redacted.contains("hunter2"));
+    }
+
+    #[test]
+    fn test_redact_nested_tokens_and_urls() {
+        let body = br#"{"access_token": "a", "data": {"refresh_token": "b", "download_url": "https://x/y?sig=zzz"}}"#;
+        let redacted = redact_body(body);
+
+        assert!(!redacted.contains("\"a\""));
+        assert!(!redacted.contains("\"b\""));
+        assert!(!redacted.contains("zzz"));
+    }
+
+    #[test]
+    fn test_binary_body_is_summarized() {
+        assert_eq!(redact_body(&[0xff, 0x00, 0x12]), "<3 bytes>");
+    }
+}
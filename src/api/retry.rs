@@ -0,0 +1,186 @@
+use crate::api::idempotency::IDEMPOTENCY_KEY_HEADER;
+use crate::config::RetryConfig;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Method, StatusCode};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// Retry policy applied by `ApiClient` to transient failures
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::from(&RetryConfig::default())
+    }
+}
+
+impl From<&RetryConfig> for RetryPolicy {
+    fn from(config: &RetryConfig) -> Self {
+        RetryPolicy {
+            max_attempts: config.max_attempts.max(1),
+            initial_backoff: Duration::from_millis(config.initial_backoff_ms),
+            max_backoff: Duration::from_millis(config.max_backoff_ms),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Exponential backoff with jitter for the given (1-based) failed attempt.
+    ///
+    /// The delay is drawn uniformly from the upper half of the capped exponential
+    /// window, so concurrent clients don't retry in lockstep.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exp = attempt.saturating_sub(1).min(16);
+        let window = self
+            .initial_backoff
+            .saturating_mul(1 << exp)
+            .min(self.max_backoff);
+        let half = window / 2;
+        let jitter_ms = match half.as_millis() as u64 {
+            0 => 0,
+            ms => random_u64() % (ms + 1),
+        };
+        half + Duration::from_millis(jitter_ms)
+    }
+
+    /// Delay before the next attempt, preferring the server's `Retry-After` hint.
+    ///
+    /// `None` when the hint asks for a longer wait than `max_backoff`: the request
+    /// should fail with the hint rather than block for that long.
+    pub fn delay(&self, attempt: u32, status: StatusCode, headers: &HeaderMap) -> Option<Duration> {
+        let honors_hint = status == StatusCode::TOO_MANY_REQUESTS
+            || status == StatusCode::SERVICE_UNAVAILABLE;
+
+        match retry_after(headers) {
+            Some(hint) if honors_hint && hint > self.max_backoff => None,
+            Some(hint) if honors_hint => Some(hint),
+            _ => Some(self.backoff(attempt)),
+        }
+    }
+
+    /// Whether a response status is worth retrying
+    pub fn is_retryable_status(status: StatusCode) -> bool {
+        matches!(
+            status,
+            StatusCode::TOO_MANY_REQUESTS
+                | StatusCode::BAD_GATEWAY
+                | StatusCode::SERVICE_UNAVAILABLE
+                | StatusCode::GATEWAY_TIMEOUT
+        )
+    }
+
+    /// Whether a transport error is worth retrying: failed connections and timeouts.
+    ///
+    /// Other request errors (building the request, reading its body, redirect loops)
+    /// would fail the same way again.
+    pub fn is_retryable_error(err: &reqwest::Error) -> bool {
+        err.is_connect() || err.is_timeout()
+    }
+
+    /// Whether a method can be safely replayed without the caller opting in
+    pub fn is_idempotent(method: &Method) -> bool {
+        matches!(
+            *method,
+            Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS | Method::TRACE
+        )
+    }
+
+    /// Whether a request can be replayed: idempotent methods, or any request carrying an
+    /// `Idempotency-Key` the server uses to deduplicate it
+    pub fn is_replay_safe(method: &Method, headers: &HeaderMap) -> bool {
+        Self::is_idempotent(method) || headers.contains_key(IDEMPOTENCY_KEY_HEADER)
+    }
+}
+
+/// Parse a `Retry-After` header given in delta-seconds.
+///
+/// HTTP-date values are ignored; the caller falls back to computed backoff.
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+fn random_u64() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(400),
+            max_backoff: Duration::from_millis(2_000),
+        }
+    }
+
+    #[test]
+    fn test_backoff_grows_and_is_capped() {
+        let policy = policy();
+        for _ in 0..50 {
+            let first = policy.backoff(1);
+            assert!(first >= Duration::from_millis(200) && first <= Duration::from_millis(400));
+
+            let third = policy.backoff(3);
+            assert!(third >= Duration::from_millis(800) && third <= Duration::from_millis(1_600));
+
+            let tenth = policy.backoff(10);
+            assert!(tenth >= Duration::from_millis(1_000) && tenth <= Duration::from_millis(2_000));
+        }
+    }
+
+    #[test]
+    fn test_retry_after_is_honored_for_429_and_503_up_to_max_backoff() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("7"));
+
+        let policy = policy();
+        assert_eq!(policy.delay(1, StatusCode::TOO_MANY_REQUESTS, &headers), None);
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("2"));
+        assert_eq!(
+            policy.delay(1, StatusCode::TOO_MANY_REQUESTS, &headers),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(
+            policy.delay(1, StatusCode::SERVICE_UNAVAILABLE, &headers),
+            Some(Duration::from_secs(2))
+        );
+        assert!(policy.delay(1, StatusCode::BAD_GATEWAY, &headers).unwrap() <= Duration::from_millis(400));
+    }
+
+    #[test]
+    fn test_retry_after_ignores_http_dates() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"));
+        assert_eq!(retry_after(&headers), None);
+    }
+
+    #[test]
+    fn test_only_idempotent_methods_retry_by_default() {
+        assert!(RetryPolicy::is_idempotent(&Method::GET));
+        assert!(RetryPolicy::is_idempotent(&Method::DELETE));
+        assert!(!RetryPolicy::is_idempotent(&Method::POST));
+        assert!(!RetryPolicy::is_idempotent(&Method::PATCH));
+
+        let mut headers = HeaderMap::new();
+        assert!(!RetryPolicy::is_replay_safe(&Method::POST, &headers));
+        headers.insert(IDEMPOTENCY_KEY_HEADER, "abc".parse().unwrap());
+        assert!(RetryPolicy::is_replay_safe(&Method::POST, &headers));
+    }
+}
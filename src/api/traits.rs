@@ -0,0 +1,295 @@
+use crate::api::assets::{Asset, AssetQuery, AssetUpdate, AssetUploadMetadata, AssetUsage};
+use crate::api::auth::UserInfo;
+use crate::api::datasets::{Dataset, DownloadUrlResponse};
+use crate::api::jobs::{Job, JobProgress};
+use crate::api::pagination::{paginate, Page, PageRequest};
+use crate::api::uploads::{InitiateUploadRequest, UploadSession, UploadedPart};
+use crate::api::{ApiClient, ProgressCallback};
+use crate::config::AuthConfig;
+use crate::error::Result;
+use async_trait::async_trait;
+use futures::{future, Stream, TryStreamExt};
+use std::path::Path;
+
+/// Operations the CLI and TUI need from the ZereData backend.
+///
+/// Implemented by [`ApiClient`] for the real API and by
+/// [`FakeApi`](crate::api::fake::FakeApi) for tests.
+#[async_trait]
+pub trait ZereApi: Send + Sync {
+    /// Login with email and password
+    async fn login(&self, email: String, password: String) -> Result<AuthConfig>;
+
+    /// Get current user info
+    async fn whoami(&self) -> Result<UserInfo>;
+
+    /// Logout (server-side)
+    async fn logout(&self) -> Result<()>;
+
+    /// List a single page of jobs
+    async fn list_jobs_page(&self, page: PageRequest) -> Result<Page<Job>>;
+
+    /// Get job details
+    async fn get_job(&self, job_id: &str) -> Result<Job>;
+
+    /// Create a new job, deduplicated by `idempotency_key` if given
+    async fn create_job(
+        &self,
+        name: String,
+        num_scenes: i32,
+        config_yaml: Option<String>,
+        idempotency_key: Option<String>,
+    ) -> Result<Job>;
+
+    /// Get job progress
+    async fn get_job_progress(&self, job_id: &str) -> Result<JobProgress>;
+
+    /// Cancel a job
+    async fn cancel_job(&self, job_id: &str) -> Result<()>;
+
+    /// List a single page of assets
+    async fn list_assets_page(&self, page: PageRequest) -> Result<Page<Asset>>;
+
+    /// List a single page of assets matching `query`; the server may ignore some filters
+    async fn search_assets_page(&self, query: &AssetQuery, page: PageRequest) -> Result<Page<Asset>>;
+
+    /// Get asset details
+    async fn get_asset(&self, asset_id: &str) -> Result<Asset>;
+
+    /// Assets whose file has the given SHA-256
+    async fn find_assets_by_hash(&self, sha256: &str) -> Result<Vec<Asset>>;
+
+    /// Upload asset
+    async fn upload_asset(
+        &self,
+        file_path: &Path,
+        metadata: AssetUploadMetadata,
+        progress: Option<ProgressCallback>,
+    ) -> Result<Asset>;
+
+    /// Start a chunked upload
+    async fn initiate_upload(&self, request: &InitiateUploadRequest) -> Result<UploadSession>;
+
+    /// Get a chunked upload and the parts received so far
+    async fn get_upload(&self, upload_id: &str) -> Result<UploadSession>;
+
+    /// Upload one part of a chunked upload
+    async fn upload_part(
+        &self,
+        upload_id: &str,
+        part_number: u32,
+        data: Vec<u8>,
+        sha256: &str,
+    ) -> Result<UploadedPart>;
+
+    /// Assemble the uploaded parts into an asset
+    async fn complete_upload(&self, upload_id: &str, parts: &[UploadedPart]) -> Result<Asset>;
+
+    /// Fetch an asset's thumbnail image
+    async fn get_thumbnail(&self, thumbnail_url: &str) -> Result<Vec<u8>>;
+
+    /// Download an asset's original file; returns its size
+    async fn download_asset(
+        &self,
+        asset_id: &str,
+        output_path: &Path,
+        progress: Option<ProgressCallback>,
+    ) -> Result<u64>;
+
+    /// Change an asset's name, category or tags
+    async fn update_asset(&self, asset_id: &str, update: &AssetUpdate) -> Result<Asset>;
+
+    /// List the jobs and configs that reference an asset
+    async fn asset_usage(&self, asset_id: &str) -> Result<AssetUsage>;
+
+    /// Delete asset
+    async fn delete_asset(&self, asset_id: &str) -> Result<()>;
+
+    /// List a single page of datasets
+    async fn list_datasets_page(&self, page: PageRequest) -> Result<Page<Dataset>>;
+
+    /// Get dataset details
+    async fn get_dataset(&self, dataset_id: &str) -> Result<Dataset>;
+
+    /// Get download URL for a dataset (via job_id)
+    async fn get_download_url(&self, job_id: &str) -> Result<DownloadUrlResponse>;
+
+    /// Download dataset to file
+    async fn download_dataset(
+        &self,
+        download_url: &str,
+        output_path: &Path,
+        progress: Option<ProgressCallback>,
+    ) -> Result<u64>;
+
+    /// Stream every job, fetching pages of `page_size` as they are consumed
+    fn jobs_stream(&self, page_size: u32) -> impl Stream<Item = Result<Job>> + '_
+    where
+        Self: Sized,
+    {
+        paginate(page_size, move |page| self.list_jobs_page(page))
+    }
+
+    /// Stream every asset, fetching pages of `page_size` as they are consumed
+    fn assets_stream(&self, page_size: u32) -> impl Stream<Item = Result<Asset>> + '_
+    where
+        Self: Sized,
+    {
+        paginate(page_size, move |page| self.list_assets_page(page))
+    }
+
+    /// Stream every asset matching `query`, applying any filter the server ignored
+    fn search_assets_stream<'a>(
+        &'a self,
+        query: &'a AssetQuery,
+        page_size: u32,
+    ) -> impl Stream<Item = Result<Asset>> + 'a
+    where
+        Self: Sized,
+    {
+        paginate(page_size, move |page| self.search_assets_page(query, page))
+            .try_filter(move |asset| future::ready(query.matches(asset)))
+    }
+
+    /// Stream every dataset, fetching pages of `page_size` as they are consumed
+    fn datasets_stream(&self, page_size: u32) -> impl Stream<Item = Result<Dataset>> + '_
+    where
+        Self: Sized,
+    {
+        paginate(page_size, move |page| self.list_datasets_page(page))
+    }
+}
+
+#[async_trait]
+impl ZereApi for ApiClient {
+    async fn login(&self, email: String, password: String) -> Result<AuthConfig> {
+        ApiClient::login(self, email, password).await
+    }
+
+    async fn whoami(&self) -> Result<UserInfo> {
+        ApiClient::whoami(self).await
+    }
+
+    async fn logout(&self) -> Result<()> {
+        ApiClient::logout(self).await
+    }
+
+    async fn list_jobs_page(&self, page: PageRequest) -> Result<Page<Job>> {
+        ApiClient::list_jobs_page(self, page).await
+    }
+
+    async fn get_job(&self, job_id: &str) -> Result<Job> {
+        ApiClient::get_job(self, job_id).await
+    }
+
+    async fn create_job(
+        &self,
+        name: String,
+        num_scenes: i32,
+        config_yaml: Option<String>,
+        idempotency_key: Option<String>,
+    ) -> Result<Job> {
+        ApiClient::create_job(self, name, num_scenes, config_yaml, idempotency_key).await
+    }
+
+    async fn get_job_progress(&self, job_id: &str) -> Result<JobProgress> {
+        ApiClient::get_job_progress(self, job_id).await
+    }
+
+    async fn cancel_job(&self, job_id: &str) -> Result<()> {
+        ApiClient::cancel_job(self, job_id).await
+    }
+
+    async fn list_assets_page(&self, page: PageRequest) -> Result<Page<Asset>> {
+        ApiClient::list_assets_page(self, page).await
+    }
+
+    async fn search_assets_page(&self, query: &AssetQuery, page: PageRequest) -> Result<Page<Asset>> {
+        ApiClient::search_assets_page(self, query, page).await
+    }
+
+    async fn get_asset(&self, asset_id: &str) -> Result<Asset> {
+        ApiClient::get_asset(self, asset_id).await
+    }
+
+    async fn find_assets_by_hash(&self, sha256: &str) -> Result<Vec<Asset>> {
+        ApiClient::find_assets_by_hash(self, sha256).await
+    }
+
+    async fn upload_asset(
+        &self,
+        file_path: &Path,
+        metadata: AssetUploadMetadata,
+        progress: Option<ProgressCallback>,
+    ) -> Result<Asset> {
+        ApiClient::upload_asset(self, file_path, metadata, progress).await
+    }
+
+    async fn initiate_upload(&self, request: &InitiateUploadRequest) -> Result<UploadSession> {
+        ApiClient::initiate_upload(self, request).await
+    }
+
+    async fn get_upload(&self, upload_id: &str) -> Result<UploadSession> {
+        ApiClient::get_upload(self, upload_id).await
+    }
+
+    async fn upload_part(
+        &self,
+        upload_id: &str,
+        part_number: u32,
+        data: Vec<u8>,
+        sha256: &str,
+    ) -> Result<UploadedPart> {
+        ApiClient::upload_part(self, upload_id, part_number, data, sha256).await
+    }
+
+    async fn complete_upload(&self, upload_id: &str, parts: &[UploadedPart]) -> Result<Asset> {
+        ApiClient::complete_upload(self, upload_id, parts).await
+    }
+
+    async fn get_thumbnail(&self, thumbnail_url: &str) -> Result<Vec<u8>> {
+        ApiClient::get_thumbnail(self, thumbnail_url).await
+    }
+
+    async fn download_asset(
+        &self,
+        asset_id: &str,
+        output_path: &Path,
+        progress: Option<ProgressCallback>,
+    ) -> Result<u64> {
+        ApiClient::download_asset(self, asset_id, output_path, progress).await
+    }
+
+    async fn update_asset(&self, asset_id: &str, update: &AssetUpdate) -> Result<Asset> {
+        ApiClient::update_asset(self, asset_id, update).await
+    }
+
+    async fn asset_usage(&self, asset_id: &str) -> Result<AssetUsage> {
+        ApiClient::asset_usage(self, asset_id).await
+    }
+
+    async fn delete_asset(&self, asset_id: &str) -> Result<()> {
+        ApiClient::delete_asset(self, asset_id).await
+    }
+
+    async fn list_datasets_page(&self, page: PageRequest) -> Result<Page<Dataset>> {
+        ApiClient::list_datasets_page(self, page).await
+    }
+
+    async fn get_dataset(&self, dataset_id: &str) -> Result<Dataset> {
+        ApiClient::get_dataset(self, dataset_id).await
+    }
+
+    async fn get_download_url(&self, job_id: &str) -> Result<DownloadUrlResponse> {
+        ApiClient::get_download_url(self, job_id).await
+    }
+
+    async fn download_dataset(
+        &self,
+        download_url: &str,
+        output_path: &Path,
+        progress: Option<ProgressCallback>,
+    ) -> Result<u64> {
+        ApiClient::download_dataset(self, download_url, output_path, progress).await
+    }
+}
@@ -0,0 +1,331 @@
+use crate::api::assets::{Asset, AssetUploadMetadata};
+use crate::api::idempotency::fingerprint;
+use crate::api::{ApiClient, ProgressCallback, ZereApi};
+use crate::config::{Config, OperationClass};
+use crate::error::{CliError, Result};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+/// Header carrying the hex SHA-256 of an uploaded part
+pub const PART_CHECKSUM_HEADER: &str = "X-Checksum-SHA256";
+
+/// Part size requested for chunked uploads
+pub const DEFAULT_PART_SIZE: u64 = 8 * 1_048_576;
+
+/// Files at least this large are uploaded in parts rather than in one request
+pub const CHUNKED_UPLOAD_THRESHOLD: u64 = 64 * 1_048_576;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InitiateUploadRequest {
+    pub file_name: String,
+    pub size_bytes: u64,
+    pub part_size: u64,
+    #[serde(flatten)]
+    pub metadata: AssetUploadMetadata,
+}
+
+/// A chunked upload in progress on the server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadSession {
+    pub upload_id: String,
+    /// Part size the server settled on; every part but the last must be exactly this long
+    pub part_size: u64,
+    /// Parts the server has already received
+    #[serde(default)]
+    pub parts: Vec<UploadedPart>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadedPart {
+    pub part_number: u32,
+    pub sha256: String,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct CompleteUploadRequest<'a> {
+    parts: &'a [UploadedPart],
+}
+
+impl ApiClient {
+    /// Start a chunked upload
+    pub async fn initiate_upload(&self, request: &InitiateUploadRequest) -> Result<UploadSession> {
+        let req = self.post("/api/v1/uploads").json(request);
+        self.send_json(req).await
+    }
+
+    /// Get a chunked upload and the parts received so far
+    pub async fn get_upload(&self, upload_id: &str) -> Result<UploadSession> {
+        let req = self.get(&format!("/api/v1/uploads/{}", upload_id));
+        self.send_json(req).await
+    }
+
+    /// Upload one part (numbered from 1); the server rejects it if `sha256` does not match
+    pub async fn upload_part(
+        &self,
+        upload_id: &str,
+        part_number: u32,
+        data: Vec<u8>,
+        sha256: &str,
+    ) -> Result<UploadedPart> {
+        let path = format!("/api/v1/uploads/{}/parts/{}", upload_id, part_number);
+        let req = self
+            .request(OperationClass::Upload, Method::PUT, &path)
+            .header(PART_CHECKSUM_HEADER, sha256)
+            .body(data);
+        self.send_upload_json(req).await
+    }
+
+    /// Assemble the uploaded parts into an asset
+    pub async fn complete_upload(&self, upload_id: &str, parts: &[UploadedPart]) -> Result<Asset> {
+        let req = self
+            .post(&format!("/api/v1/uploads/{}/complete", upload_id))
+            .json(&CompleteUploadRequest { parts });
+        self.send_json(req).await
+    }
+}
+
+/// Hex SHA-256 of a part, as sent in [`PART_CHECKSUM_HEADER`]
+pub fn part_checksum(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Local record of an unfinished chunked upload, so an interrupted transfer can be resumed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ResumeState {
+    upload_id: String,
+    file_path: PathBuf,
+}
+
+/// Where resume state for one file lives: `<dir>/<fingerprint>.json`.
+///
+/// The fingerprint covers the file's path, size and modification time, so a
+/// file that changed since the interrupted attempt starts a fresh upload.
+struct ResumeStore {
+    path: PathBuf,
+}
+
+impl ResumeStore {
+    fn for_file(dir: &Path, file_path: &Path, metadata: &std::fs::Metadata) -> Result<Self> {
+        let file_path = std::fs::canonicalize(file_path)?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        let fp = fingerprint(&[
+            &file_path.to_string_lossy(),
+            &metadata.len().to_string(),
+            &modified.to_string(),
+        ]);
+        Ok(ResumeStore {
+            path: dir.join(format!("{}.json", fp)),
+        })
+    }
+
+    fn load(&self) -> Result<Option<ResumeState>> {
+        match std::fs::read(&self.path) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes).ok()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn save(&self, state: &ResumeState) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_vec_pretty(state)?)?;
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<()> {
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Options for [`upload_chunked`]
+#[derive(Clone, Default)]
+pub struct ChunkedUploadOptions {
+    /// Continue an earlier interrupted upload of the same file if there is one
+    pub resume: bool,
+    /// Requested part size; defaults to [`DEFAULT_PART_SIZE`]
+    pub part_size: Option<u64>,
+    /// Directory for resume state; defaults to `<config dir>/uploads`
+    pub state_dir: Option<PathBuf>,
+    /// Called with the number of bytes the server has, including parts from an earlier attempt
+    pub progress: Option<ProgressCallback>,
+}
+
+/// Upload a file as an asset in checksummed parts.
+///
+/// The upload ID is saved locally before any part is sent, so if the transfer is
+/// interrupted, calling this again with `resume` set only sends the parts the
+/// server does not have yet. The saved state is removed once the asset exists.
+pub async fn upload_chunked(
+    api: &(impl ZereApi + ?Sized),
+    file_path: &Path,
+    metadata: AssetUploadMetadata,
+    options: ChunkedUploadOptions,
+) -> Result<Asset> {
+    let mut file = tokio::fs::File::open(file_path).await?;
+    let file_metadata = file.metadata().await?;
+    let size = file_metadata.len();
+
+    let state_dir = match options.state_dir {
+        Some(dir) => dir,
+        None => Config::config_dir()?.join("uploads"),
+    };
+    let store = ResumeStore::for_file(&state_dir, file_path, &file_metadata)?;
+
+    let mut session = None;
+    if options.resume {
+        if let Some(saved) = store.load()? {
+            match api.get_upload(&saved.upload_id).await {
+                Ok(existing) => session = Some(existing),
+                // Expired or already completed on the server; start over
+                Err(CliError::NotFound { .. }) => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    let session = match session {
+        Some(session) => session,
+        None => {
+            let request = InitiateUploadRequest {
+                file_name: file_path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("upload")
+                    .to_string(),
+                size_bytes: size,
+                part_size: options.part_size.unwrap_or(DEFAULT_PART_SIZE),
+                metadata,
+            };
+            let session = api.initiate_upload(&request).await?;
+            store.save(&ResumeState {
+                upload_id: session.upload_id.clone(),
+                file_path: file_path.to_path_buf(),
+            })?;
+            session
+        }
+    };
+
+    if session.part_size == 0 {
+        return Err(CliError::InvalidResponse);
+    }
+    let part_count = size.div_ceil(session.part_size).max(1) as u32;
+
+    let mut received: BTreeMap<u32, UploadedPart> = session
+        .parts
+        .into_iter()
+        .filter(|part| part.part_number >= 1 && part.part_number <= part_count)
+        .map(|part| (part.part_number, part))
+        .collect();
+
+    let report = |received: &BTreeMap<u32, UploadedPart>| {
+        if let Some(progress) = &options.progress {
+            progress(received.values().map(|part| part.size_bytes).sum());
+        }
+    };
+    report(&received);
+
+    for part_number in 1..=part_count {
+        if received.contains_key(&part_number) {
+            continue;
+        }
+
+        let offset = u64::from(part_number - 1) * session.part_size;
+        let length = session.part_size.min(size - offset);
+        let mut data = vec![0; length as usize];
+        file.seek(std::io::SeekFrom::Start(offset)).await?;
+        file.read_exact(&mut data).await?;
+
+        let sha256 = part_checksum(&data);
+        let part = api
+            .upload_part(&session.upload_id, part_number, data, &sha256)
+            .await?;
+        if part.sha256 != sha256 {
+            return Err(CliError::Other(format!(
+                "Checksum mismatch for part {} of upload {}",
+                part_number, session.upload_id
+            )));
+        }
+
+        received.insert(part_number, part);
+        report(&received);
+    }
+
+    let parts: Vec<UploadedPart> = received.into_values().collect();
+    let asset = api.complete_upload(&session.upload_id, &parts).await?;
+    store.clear()?;
+    Ok(asset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::fake::FakeApi;
+
+    fn metadata() -> AssetUploadMetadata {
+        AssetUploadMetadata {
+            name: "chair".to_string(),
+            category: None,
+            tags: Vec::new(),
+            sha256: None,
+            mesh: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resume_sends_only_missing_parts() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("chair.glb");
+        std::fs::write(&file, vec![7u8; 25]).unwrap();
+
+        let options = ChunkedUploadOptions {
+            part_size: Some(10),
+            state_dir: Some(dir.path().join("state")),
+            ..ChunkedUploadOptions::default()
+        };
+
+        // initiate, part 1, then part 2 drops
+        let api = FakeApi::new();
+        api.fail_nth(3, CliError::Other("connection reset".to_string()));
+        assert!(upload_chunked(&api, &file, metadata(), options.clone()).await.is_err());
+
+        let resumed = ChunkedUploadOptions {
+            resume: true,
+            ..options
+        };
+        let asset = upload_chunked(&api, &file, metadata(), resumed).await.unwrap();
+
+        assert_eq!(asset.size_bytes, 25);
+        let parts: Vec<String> = api
+            .calls()
+            .into_iter()
+            .filter(|call| call.starts_with("upload_part"))
+            .collect();
+        assert_eq!(
+            parts,
+            [
+                "upload_part upload-1 1",
+                "upload_part upload-1 2",
+                "upload_part upload-1 2",
+                "upload_part upload-1 3",
+            ]
+        );
+        // Resume state is gone once the asset exists
+        assert_eq!(std::fs::read_dir(dir.path().join("state")).unwrap().count(), 0);
+    }
+}
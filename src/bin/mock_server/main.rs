@@ -0,0 +1,131 @@
+//! In-memory stand-in for the ZereData backend, for offline development and CI.
+//!
+//! ```text
+//! cargo run --features mock-server --bin zere-mock-server -- --port 8001
+//! zere config set-endpoint http://localhost:8001
+//! zere login --api-key mock-api-key
+//! ```
+
+mod routes;
+mod state;
+
+use clap::Parser;
+use state::{MockState, Settings};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+#[derive(Parser)]
+#[command(name = "zere-mock-server")]
+#[command(about = "Local mock of the ZereData API for offline development", long_about = None)]
+#[command(version)]
+struct Args {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1")]
+    host: String,
+
+    /// Port to listen on
+    #[arg(short, long, default_value_t = 8001)]
+    port: u16,
+
+    /// API key accepted in addition to tokens issued by login
+    #[arg(long, default_value = "mock-api-key")]
+    api_key: String,
+
+    /// Lifetime of access tokens issued by login, in seconds
+    #[arg(long, default_value_t = 3600)]
+    token_ttl: i64,
+
+    /// How fast simulated jobs render
+    #[arg(long, default_value_t = 2.0)]
+    scenes_per_sec: f64,
+}
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    let args = Args::parse();
+
+    let addr: SocketAddr = format!("{}:{}", args.host, args.port)
+        .parse()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+
+    let state = Arc::new(Mutex::new(MockState::new(Settings {
+        api_key: args.api_key.clone(),
+        token_ttl_secs: args.token_ttl,
+        scenes_per_sec: args.scenes_per_sec,
+    })));
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    println!("Zere mock server listening on http://{}", addr);
+    println!("  zere config set-endpoint http://{}", addr);
+    println!("  zere login --api-key {}   (or any email/password)", args.api_key);
+
+    axum::serve(listener, routes::router(state)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zere_sdk::api::assets::{file_sha256, AssetUploadMetadata};
+    use zere_sdk::api::ApiClient;
+    use zere_sdk::config::Config;
+
+    async fn start() -> String {
+        let state = Arc::new(Mutex::new(MockState::new(Settings {
+            api_key: "mock-api-key".to_string(),
+            token_ttl_secs: 3600,
+            scenes_per_sec: 2.0,
+        })));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, routes::router(state)).await });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_login_list_upload_round_trip() {
+        let endpoint = start().await;
+
+        let session = ApiClient::new(endpoint.clone(), None)
+            .unwrap()
+            .login("dev@example.com".to_string(), "secret".to_string())
+            .await
+            .unwrap();
+        let mut config = Config::default();
+        config.set_endpoint(endpoint);
+        config.set_session(session);
+        let client = ApiClient::from_config(&config).unwrap();
+        assert_eq!(client.whoami().await.unwrap().email, "dev@zeredata.local");
+
+        let before = client.list_assets().await.unwrap().len();
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("crate.obj");
+        std::fs::write(&file, "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n").unwrap();
+        let sha256 = file_sha256(&file).await.unwrap();
+
+        let uploaded = client
+            .upload_asset(
+                &file,
+                AssetUploadMetadata {
+                    name: "crate".to_string(),
+                    category: Some("props".to_string()),
+                    tags: vec!["mock".to_string()],
+                    sha256: Some(sha256.clone()),
+                    mesh: None,
+                },
+                None,
+            )
+            .await
+            .unwrap();
+
+        let assets = client.list_assets().await.unwrap();
+        assert_eq!(assets.len(), before + 1);
+        assert!(assets.iter().any(|a| a.asset_id == uploaded.asset_id && a.name == "crate"));
+        let found = client.find_assets_by_hash(&sha256).await.unwrap();
+        assert_eq!(found.len(), 1);
+
+        let downloaded = dir.path().join("downloaded.obj");
+        client.download_asset(&uploaded.asset_id, &downloaded, None).await.unwrap();
+        assert_eq!(std::fs::read(&downloaded).unwrap(), std::fs::read(&file).unwrap());
+    }
+}
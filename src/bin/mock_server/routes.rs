@@ -0,0 +1,730 @@
+use crate::state::{timestamp, MockState, MockUpload};
+use axum::extract::{DefaultBodyLimit, Multipart, Path, Query, Request, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post, put};
+use axum::{Json, Router};
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use serde_json::{json, Value};
+use std::io::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use zere_sdk::api::assets::{AssetJobRef, AssetQuery, AssetSort, AssetUploadMetadata, AssetUsage};
+use zere_sdk::api::idempotency::IDEMPOTENCY_KEY_HEADER;
+use zere_sdk::api::jobs::JobStatus;
+use zere_sdk::api::pagination::DEFAULT_PAGE_SIZE;
+use zere_sdk::api::uploads::{part_checksum, UploadedPart, PART_CHECKSUM_HEADER};
+use zere_sdk::validate::MeshStats;
+use zip::write::SimpleFileOptions;
+
+type Shared = Arc<Mutex<MockState>>;
+
+static REQUEST_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+pub fn router(state: Shared) -> Router {
+    let protected = Router::new()
+        .route("/auth/me", get(me))
+        .route("/auth/logout", post(logout))
+        .route("/jobs", get(list_jobs).post(create_job))
+        .route("/jobs/{job_id}", get(get_job))
+        .route("/jobs/{job_id}/progress", get(job_progress))
+        .route("/jobs/{job_id}/cancel", post(cancel_job))
+        .route("/jobs/{job_id}/download", get(download_url))
+        .route("/assets", get(list_assets))
+        .route("/assets/upload", post(upload_asset))
+        .route("/assets/{asset_id}", get(get_asset).patch(update_asset).delete(delete_asset))
+        .route("/assets/{asset_id}/download", get(asset_download_url))
+        .route("/assets/{asset_id}/usage", get(asset_usage))
+        .route("/uploads", post(initiate_upload))
+        .route("/uploads/{upload_id}", get(get_upload))
+        .route("/uploads/{upload_id}/parts/{part_number}", put(upload_part))
+        .route("/uploads/{upload_id}/complete", post(complete_upload))
+        .route("/datasets", get(list_datasets))
+        .route("/datasets/{job_id}", get(get_dataset))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_auth));
+
+    let public = Router::new()
+        .route("/auth/login", post(login))
+        .route("/auth/refresh", post(refresh));
+
+    Router::new()
+        .nest("/api/v1", protected.merge(public))
+        // Stands in for the object store behind presigned dataset URLs
+        .route("/files/{file}", get(download_file))
+        .route("/files/assets/{asset_id}", get(download_asset_file))
+        .route("/files/thumbnails/{file}", get(thumbnail))
+        .layer(DefaultBodyLimit::disable())
+        .with_state(state)
+}
+
+/// Error in the backend's `{"error": {...}}` envelope
+struct ApiError {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+    fields: Vec<(&'static str, &'static str)>,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, code: &'static str, message: impl Into<String>) -> Self {
+        ApiError {
+            status,
+            code,
+            message: message.into(),
+            fields: Vec::new(),
+        }
+    }
+
+    fn not_found(resource: &str, id: &str) -> Self {
+        ApiError::new(StatusCode::NOT_FOUND, "not_found", format!("{} {} not found", resource, id))
+    }
+
+    fn internal(error: impl std::fmt::Display) -> Self {
+        ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "internal_error", error.to_string())
+    }
+
+    fn invalid_field(field: &'static str, message: &'static str) -> Self {
+        ApiError {
+            fields: vec![(field, message)],
+            ..ApiError::new(StatusCode::UNPROCESSABLE_ENTITY, "validation_error", "Request contains invalid fields")
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let request_id = format!("mock-{}", REQUEST_COUNTER.fetch_add(1, Ordering::Relaxed));
+        let fields: Vec<Value> = self
+            .fields
+            .iter()
+            .map(|(field, message)| json!({ "field": field, "message": message }))
+            .collect();
+        let body = json!({
+            "error": {
+                "code": self.code,
+                "message": self.message,
+                "fields": fields,
+                "request_id": request_id,
+            }
+        });
+        (self.status, [("x-request-id", request_id)], Json(body)).into_response()
+    }
+}
+
+type ApiResult<T> = std::result::Result<T, ApiError>;
+
+fn lock(state: &Shared) -> MutexGuard<'_, MockState> {
+    let mut state = state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    state.tick();
+    state
+}
+
+async fn require_auth(State(state): State<Shared>, request: Request, next: Next) -> Response {
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or_default()
+        .to_string();
+
+    if !lock(&state).is_authorized(&token) {
+        return ApiError::new(StatusCode::UNAUTHORIZED, "unauthorized", "Invalid or expired token").into_response();
+    }
+    next.run(request).await
+}
+
+#[derive(Deserialize)]
+struct LoginRequest {
+    email: String,
+    password: String,
+}
+
+#[derive(Deserialize)]
+struct RefreshRequest {
+    refresh_token: String,
+}
+
+#[derive(Deserialize)]
+struct PageQuery {
+    limit: Option<usize>,
+    offset: Option<usize>,
+}
+
+impl PageQuery {
+    fn slice<T: Clone>(&self, items: &[T]) -> Vec<T> {
+        items
+            .iter()
+            .skip(self.offset.unwrap_or(0))
+            .take(self.limit.unwrap_or(DEFAULT_PAGE_SIZE as usize))
+            .cloned()
+            .collect()
+    }
+}
+
+#[derive(Deserialize)]
+struct CreateJobRequest {
+    name: String,
+    num_scenes: i32,
+    config_yaml: Option<String>,
+}
+
+fn session(state: &mut MockState) -> Value {
+    let (access_token, refresh_token) = state.issue_tokens();
+    json!({
+        "access_token": access_token,
+        "refresh_token": refresh_token,
+        "expires_in": state.settings.token_ttl_secs,
+    })
+}
+
+async fn login(State(state): State<Shared>, Json(req): Json<LoginRequest>) -> ApiResult<Json<Value>> {
+    if req.email.is_empty() || req.password.is_empty() {
+        return Err(ApiError::new(StatusCode::UNAUTHORIZED, "invalid_credentials", "Invalid email or password"));
+    }
+    Ok(Json(session(&mut lock(&state))))
+}
+
+async fn refresh(State(state): State<Shared>, Json(req): Json<RefreshRequest>) -> ApiResult<Json<Value>> {
+    let mut state = lock(&state);
+    // Refresh tokens rotate on every use
+    if !state.refresh_tokens.remove(&req.refresh_token) {
+        return Err(ApiError::new(StatusCode::UNAUTHORIZED, "invalid_refresh_token", "Refresh token is invalid"));
+    }
+    Ok(Json(session(&mut state)))
+}
+
+async fn me() -> Json<Value> {
+    Json(json!({
+        "id": 1,
+        "email": "dev@zeredata.local",
+        "organization_id": 1,
+        "organization_name": "Mock Robotics",
+        "role": "admin",
+    }))
+}
+
+async fn logout() -> StatusCode {
+    StatusCode::NO_CONTENT
+}
+
+async fn list_jobs(State(state): State<Shared>, Query(page): Query<PageQuery>) -> Json<Value> {
+    let state = lock(&state);
+    let jobs: Vec<_> = state.jobs.iter().rev().map(|j| j.job.clone()).collect();
+    Json(json!({ "jobs": page.slice(&jobs), "total": jobs.len() }))
+}
+
+async fn create_job(
+    State(state): State<Shared>,
+    headers: HeaderMap,
+    Json(req): Json<CreateJobRequest>,
+) -> ApiResult<(StatusCode, Json<Value>)> {
+    if req.name.trim().is_empty() {
+        return Err(ApiError::invalid_field("name", "must not be empty"));
+    }
+    if req.num_scenes < 1 {
+        return Err(ApiError::invalid_field("num_scenes", "must be positive"));
+    }
+
+    let mut state = lock(&state);
+    let key = headers
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    // A replayed key returns the job it created instead of starting another render
+    if let Some(job_id) = key.as_ref().and_then(|key| state.idempotency_keys.get(key)).cloned() {
+        if let Some(job) = state.job_mut(&job_id) {
+            return Ok((StatusCode::OK, Json(json!(job.job))));
+        }
+    }
+
+    let job = state.create_job(req.name, req.num_scenes, req.config_yaml);
+    if let Some(key) = key {
+        state.idempotency_keys.insert(key, job.job_id.clone());
+    }
+    Ok((StatusCode::CREATED, Json(json!(job))))
+}
+
+async fn get_job(State(state): State<Shared>, Path(job_id): Path<String>) -> ApiResult<Json<Value>> {
+    let mut state = lock(&state);
+    let job = state.job_mut(&job_id).ok_or_else(|| ApiError::not_found("Job", &job_id))?;
+    Ok(Json(json!(job.job)))
+}
+
+async fn job_progress(State(state): State<Shared>, Path(job_id): Path<String>) -> ApiResult<Json<Value>> {
+    let mut state = lock(&state);
+    let scenes_per_sec = state.settings.scenes_per_sec;
+    let job = state.job_mut(&job_id).ok_or_else(|| ApiError::not_found("Job", &job_id))?;
+    Ok(Json(json!(job.progress(scenes_per_sec))))
+}
+
+async fn cancel_job(State(state): State<Shared>, Path(job_id): Path<String>) -> ApiResult<StatusCode> {
+    let mut state = lock(&state);
+    let job = state.job_mut(&job_id).ok_or_else(|| ApiError::not_found("Job", &job_id))?;
+    // Cancelling twice is a no-op, as the CLI may retry it
+    if !job.cancel() && job.job.status != JobStatus::Cancelled {
+        let message = format!("Job is already {}", job.job.status);
+        return Err(ApiError::new(StatusCode::CONFLICT, "invalid_state", message));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn download_url(
+    State(state): State<Shared>,
+    Path(job_id): Path<String>,
+    headers: HeaderMap,
+) -> ApiResult<Json<Value>> {
+    let state = lock(&state);
+    let dataset = state
+        .datasets()
+        .into_iter()
+        .find(|d| d.job_id == job_id)
+        .ok_or_else(|| ApiError::not_found("Dataset", &job_id))?;
+
+    let host = headers
+        .get(header::HOST)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("localhost");
+    let zip = dataset_zip(&dataset.job_id, num_scenes(&state, &job_id)).map_err(ApiError::internal)?;
+
+    Ok(Json(json!({
+        // Mimic a presigned URL so signature redaction can be exercised locally
+        "download_url": format!("http://{}/files/{}.zip?X-Amz-Signature=mock-signature", host, job_id),
+        "expires_at": timestamp(),
+        "size_bytes": zip.len(),
+        "format": dataset.format,
+    })))
+}
+
+async fn download_file(State(state): State<Shared>, Path(file): Path<String>) -> ApiResult<Response> {
+    let state = lock(&state);
+    let job_id = file.strip_suffix(".zip").unwrap_or(&file);
+    if !state.datasets().iter().any(|d| d.job_id == job_id) {
+        return Err(ApiError::not_found("File", &file));
+    }
+    let zip = dataset_zip(job_id, num_scenes(&state, job_id)).map_err(ApiError::internal)?;
+    Ok(([(header::CONTENT_TYPE, "application/zip")], zip).into_response())
+}
+
+async fn asset_download_url(
+    State(state): State<Shared>,
+    Path(asset_id): Path<String>,
+    headers: HeaderMap,
+) -> ApiResult<Json<Value>> {
+    let state = lock(&state);
+    let asset = state
+        .assets
+        .iter()
+        .find(|a| a.asset_id == asset_id)
+        .ok_or_else(|| ApiError::not_found("Asset", &asset_id))?;
+
+    let host = headers
+        .get(header::HOST)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("localhost");
+    Ok(Json(json!({
+        "download_url": format!("http://{}/files/assets/{}?X-Amz-Signature=mock-signature", host, asset_id),
+        "expires_at": timestamp(),
+        "size_bytes": asset.size_bytes,
+        "format": asset.file_type,
+    })))
+}
+
+async fn download_asset_file(State(state): State<Shared>, Path(asset_id): Path<String>) -> ApiResult<Response> {
+    let state = lock(&state);
+    let contents = state
+        .asset_files
+        .get(&asset_id)
+        .ok_or_else(|| ApiError::not_found("File", &asset_id))?;
+    Ok(([(header::CONTENT_TYPE, "application/octet-stream")], contents.clone()).into_response())
+}
+
+/// A shaded sphere in a colour picked from the asset ID, standing in for a rendered thumbnail
+async fn thumbnail(State(state): State<Shared>, Path(file): Path<String>) -> ApiResult<Response> {
+    let asset_id = file.strip_suffix(".png").unwrap_or(&file);
+    if !lock(&state).assets.iter().any(|a| a.asset_id == asset_id) {
+        return Err(ApiError::not_found("File", &file));
+    }
+
+    let hue = asset_id.bytes().fold(0u32, |hash, b| hash.wrapping_mul(31).wrapping_add(u32::from(b)));
+    let base = [(hue % 200 + 55) as f64, (hue / 7 % 200 + 55) as f64, (hue / 49 % 200 + 55) as f64];
+    let size = 128u32;
+    let image = image::RgbaImage::from_fn(size, size, |x, y| {
+        let (dx, dy) = (x as f64 / 64.0 - 1.0, y as f64 / 64.0 - 1.0);
+        let d2 = dx * dx + dy * dy;
+        if d2 > 0.8 {
+            return image::Rgba([0, 0, 0, 0]);
+        }
+        // Lit from the top left
+        let dz = (0.8 - d2).sqrt();
+        let light = ((-dx - dy + dz) / 1.9).clamp(0.15, 1.0);
+        let [r, g, b] = base.map(|c| (c * light) as u8);
+        image::Rgba([r, g, b, 255])
+    });
+
+    let mut png = Vec::new();
+    image
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(ApiError::internal)?;
+    Ok(([(header::CONTENT_TYPE, "image/png")], png).into_response())
+}
+
+fn num_scenes(state: &MockState, job_id: &str) -> i32 {
+    state
+        .jobs
+        .iter()
+        .find(|j| j.job.job_id == job_id)
+        .map(|j| j.job.num_scenes)
+        .unwrap_or(0)
+}
+
+/// Build a small COCO-style dataset archive for a job
+fn dataset_zip(job_id: &str, num_scenes: i32) -> zip::result::ZipResult<Vec<u8>> {
+    let images: Vec<Value> = (0..num_scenes)
+        .map(|i| json!({ "id": i, "file_name": format!("images/scene_{:05}.png", i), "width": 1280, "height": 720 }))
+        .collect();
+    let annotations = json!({
+        "info": { "description": format!("Mock dataset for {}", job_id) },
+        "images": images,
+        "annotations": [],
+        "categories": [{ "id": 1, "name": "box_small" }],
+    });
+
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+    writer.start_file("README.txt", options)?;
+    writeln!(writer, "Generated by zere-mock-server for {}", job_id)?;
+    writer.start_file("annotations/instances.json", options)?;
+    writer.write_all(annotations.to_string().as_bytes())?;
+    Ok(writer.finish()?.into_inner())
+}
+
+#[derive(Deserialize)]
+struct AssetFilter {
+    sha256: Option<String>,
+    #[serde(rename = "type")]
+    file_type: Option<String>,
+    category: Option<String>,
+    tag: Option<String>,
+    min_size: Option<u64>,
+    max_size: Option<u64>,
+    created_after: Option<String>,
+    created_before: Option<String>,
+    sort: Option<String>,
+    order: Option<String>,
+}
+
+async fn list_assets(
+    State(state): State<Shared>,
+    Query(page): Query<PageQuery>,
+    Query(filter): Query<AssetFilter>,
+) -> ApiResult<Json<Value>> {
+    let sort = match filter.sort.as_deref().map(|sort| AssetSort::from_str(sort, false)) {
+        Some(Err(_)) => return Err(ApiError::invalid_field("sort", "must be name, size or created")),
+        Some(Ok(sort)) => Some(sort),
+        None => None,
+    };
+    let query = AssetQuery {
+        file_type: filter.file_type,
+        category: filter.category,
+        tag: filter.tag,
+        name: None,
+        min_size: filter.min_size,
+        max_size: filter.max_size,
+        created_after: filter.created_after,
+        created_before: filter.created_before,
+        sort,
+        descending: filter.order.as_deref() == Some("desc"),
+    };
+
+    let state = lock(&state);
+    let mut assets: Vec<_> = state
+        .assets
+        .iter()
+        .filter(|a| filter.sha256.is_none() || a.sha256 == filter.sha256)
+        .filter(|a| query.matches(a))
+        .cloned()
+        .collect();
+    query.sort(&mut assets);
+    Ok(Json(json!({ "assets": page.slice(&assets), "total": assets.len() })))
+}
+
+async fn get_asset(State(state): State<Shared>, Path(asset_id): Path<String>) -> ApiResult<Json<Value>> {
+    let state = lock(&state);
+    let asset = state
+        .assets
+        .iter()
+        .find(|a| a.asset_id == asset_id)
+        .ok_or_else(|| ApiError::not_found("Asset", &asset_id))?;
+    Ok(Json(json!(asset)))
+}
+
+async fn upload_asset(State(state): State<Shared>, mut multipart: Multipart) -> ApiResult<(StatusCode, Json<Value>)> {
+    let bad_form = |e: axum::extract::multipart::MultipartError| {
+        ApiError::new(StatusCode::BAD_REQUEST, "invalid_form", e.body_text())
+    };
+
+    let mut file: Option<(String, Vec<u8>, String)> = None;
+    let mut name = None;
+    let mut category = None;
+    let mut tags = Vec::new();
+    let mut mesh = None;
+    while let Some(field) = multipart.next_field().await.map_err(bad_form)? {
+        match field.name() {
+            Some("file") => {
+                let file_name = field.file_name().unwrap_or("upload").to_string();
+                let bytes = field.bytes().await.map_err(bad_form)?;
+                let sha256 = part_checksum(&bytes);
+                file = Some((file_name, bytes.to_vec(), sha256));
+            }
+            Some("name") => name = Some(field.text().await.map_err(bad_form)?),
+            Some("category") => category = Some(field.text().await.map_err(bad_form)?),
+            Some("tags") => {
+                let text = field.text().await.map_err(bad_form)?;
+                tags = serde_json::from_str(&text)
+                    .map_err(|_| ApiError::invalid_field("tags", "must be a JSON array of strings"))?;
+            }
+            Some("mesh") => {
+                let text = field.text().await.map_err(bad_form)?;
+                mesh = Some(
+                    serde_json::from_str(&text)
+                        .map_err(|_| ApiError::invalid_field("mesh", "must be a JSON mesh statistics object"))?,
+                );
+            }
+            _ => {}
+        }
+    }
+
+    let (file_name, contents, sha256) = file.ok_or_else(|| ApiError::invalid_field("file", "field required"))?;
+    let metadata = AssetUploadMetadata {
+        name: name.unwrap_or_else(|| file_name.clone()),
+        category,
+        tags,
+        sha256: Some(sha256),
+        mesh,
+    };
+    let asset = lock(&state).create_asset(&file_name, contents, metadata);
+    Ok((StatusCode::CREATED, Json(json!(asset))))
+}
+
+/// Smallest and largest part size the mock accepts; requests outside are clamped
+const PART_SIZE_RANGE: (u64, u64) = (1_048_576, 64 * 1_048_576);
+
+#[derive(Deserialize)]
+struct InitiateUploadRequest {
+    file_name: String,
+    size_bytes: u64,
+    part_size: u64,
+    name: String,
+    category: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    sha256: Option<String>,
+    mesh: Option<MeshStats>,
+}
+
+#[derive(Deserialize)]
+struct CompleteUploadRequest {
+    parts: Vec<UploadedPart>,
+}
+
+fn upload_json(upload_id: &str, upload: &MockUpload) -> Value {
+    let parts: Vec<&UploadedPart> = upload.parts.values().collect();
+    json!({ "upload_id": upload_id, "part_size": upload.part_size, "parts": parts })
+}
+
+async fn initiate_upload(
+    State(state): State<Shared>,
+    Json(req): Json<InitiateUploadRequest>,
+) -> ApiResult<(StatusCode, Json<Value>)> {
+    if req.name.trim().is_empty() {
+        return Err(ApiError::invalid_field("name", "must not be empty"));
+    }
+
+    let mut state = lock(&state);
+    let upload_id = format!("upload_{:04}", state.next_id());
+    let upload = MockUpload {
+        file_name: req.file_name,
+        size_bytes: req.size_bytes,
+        part_size: req.part_size.clamp(PART_SIZE_RANGE.0, PART_SIZE_RANGE.1),
+        parts: BTreeMap::new(),
+        data: BTreeMap::new(),
+        metadata: AssetUploadMetadata {
+            name: req.name,
+            category: req.category,
+            tags: req.tags,
+            sha256: req.sha256,
+            mesh: req.mesh,
+        },
+    };
+    let body = upload_json(&upload_id, &upload);
+    state.uploads.insert(upload_id, upload);
+    Ok((StatusCode::CREATED, Json(body)))
+}
+
+async fn get_upload(State(state): State<Shared>, Path(upload_id): Path<String>) -> ApiResult<Json<Value>> {
+    let state = lock(&state);
+    let upload = state
+        .uploads
+        .get(&upload_id)
+        .ok_or_else(|| ApiError::not_found("Upload", &upload_id))?;
+    Ok(Json(upload_json(&upload_id, upload)))
+}
+
+async fn upload_part(
+    State(state): State<Shared>,
+    Path((upload_id, part_number)): Path<(String, u32)>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> ApiResult<Json<Value>> {
+    let mut state = lock(&state);
+    let upload = state
+        .uploads
+        .get_mut(&upload_id)
+        .ok_or_else(|| ApiError::not_found("Upload", &upload_id))?;
+
+    if part_number < 1 || part_number > upload.part_count() {
+        return Err(ApiError::invalid_field("part_number", "out of range"));
+    }
+    if body.len() as u64 != upload.part_len(part_number) {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_part_size",
+            format!("Part {} must be {} bytes", part_number, upload.part_len(part_number)),
+        ));
+    }
+    let sha256 = part_checksum(&body);
+    let expected = headers.get(PART_CHECKSUM_HEADER).and_then(|value| value.to_str().ok());
+    if expected != Some(sha256.as_str()) {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "checksum_mismatch",
+            format!("Part {} does not match its {} header", part_number, PART_CHECKSUM_HEADER),
+        ));
+    }
+
+    let part = UploadedPart {
+        part_number,
+        sha256,
+        size_bytes: body.len() as u64,
+    };
+    upload.parts.insert(part_number, part.clone());
+    upload.data.insert(part_number, body.to_vec());
+    Ok(Json(json!(part)))
+}
+
+async fn complete_upload(
+    State(state): State<Shared>,
+    Path(upload_id): Path<String>,
+    Json(req): Json<CompleteUploadRequest>,
+) -> ApiResult<(StatusCode, Json<Value>)> {
+    let mut state = lock(&state);
+    let upload = state
+        .uploads
+        .get(&upload_id)
+        .ok_or_else(|| ApiError::not_found("Upload", &upload_id))?;
+
+    if upload.parts.len() as u32 != upload.part_count() {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "upload_incomplete",
+            format!("{} of {} parts received", upload.parts.len(), upload.part_count()),
+        ));
+    }
+    if !req.parts.iter().eq(upload.parts.values()) {
+        return Err(ApiError::invalid_field("parts", "do not match the uploaded parts"));
+    }
+
+    let Some(upload) = state.uploads.remove(&upload_id) else {
+        return Err(ApiError::not_found("Upload", &upload_id));
+    };
+    let contents = upload.data.into_values().flatten().collect();
+    let asset = state.create_asset(&upload.file_name, contents, upload.metadata);
+    Ok((StatusCode::CREATED, Json(json!(asset))))
+}
+
+#[derive(Deserialize)]
+struct AssetPatch {
+    name: Option<String>,
+    category: Option<String>,
+    tags: Option<Vec<String>>,
+}
+
+async fn update_asset(
+    State(state): State<Shared>,
+    Path(asset_id): Path<String>,
+    Json(patch): Json<AssetPatch>,
+) -> ApiResult<Json<Value>> {
+    if patch.name.as_deref().is_some_and(|name| name.trim().is_empty()) {
+        return Err(ApiError::invalid_field("name", "must not be empty"));
+    }
+
+    let mut state = lock(&state);
+    let asset = state
+        .assets
+        .iter_mut()
+        .find(|a| a.asset_id == asset_id)
+        .ok_or_else(|| ApiError::not_found("Asset", &asset_id))?;
+    if let Some(name) = patch.name {
+        asset.name = name;
+    }
+    if let Some(category) = patch.category {
+        asset.category = Some(category);
+    }
+    if let Some(tags) = patch.tags {
+        asset.tags = tags;
+    }
+    Ok(Json(json!(asset)))
+}
+
+/// Jobs whose config mentions the asset ID; the mock has no saved configs
+async fn asset_usage(State(state): State<Shared>, Path(asset_id): Path<String>) -> ApiResult<Json<Value>> {
+    let mut state = lock(&state);
+    if !state.assets.iter().any(|a| a.asset_id == asset_id) {
+        return Err(ApiError::not_found("Asset", &asset_id));
+    }
+    state.tick();
+    let jobs: Vec<AssetJobRef> = state
+        .jobs
+        .iter()
+        .filter(|j| j.config_yaml.as_deref().is_some_and(|config| config.contains(&asset_id)))
+        .map(|j| AssetJobRef {
+            job_id: j.job.job_id.clone(),
+            name: j.job.name.clone(),
+            status: j.job.status.clone(),
+        })
+        .collect();
+    Ok(Json(json!(AssetUsage {
+        jobs,
+        configs: Vec::new(),
+    })))
+}
+
+async fn delete_asset(State(state): State<Shared>, Path(asset_id): Path<String>) -> ApiResult<StatusCode> {
+    let mut state = lock(&state);
+    let before = state.assets.len();
+    state.assets.retain(|a| a.asset_id != asset_id);
+    if state.assets.len() == before {
+        return Err(ApiError::not_found("Asset", &asset_id));
+    }
+    state.asset_files.remove(&asset_id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_datasets(State(state): State<Shared>, Query(page): Query<PageQuery>) -> Json<Value> {
+    let datasets = lock(&state).datasets();
+    Json(json!({ "datasets": page.slice(&datasets), "total": datasets.len() }))
+}
+
+async fn get_dataset(State(state): State<Shared>, Path(job_id): Path<String>) -> ApiResult<Json<Value>> {
+    let dataset = lock(&state)
+        .datasets()
+        .into_iter()
+        .find(|d| d.job_id == job_id)
+        .ok_or_else(|| ApiError::not_found("Dataset", &job_id))?;
+    Ok(Json(json!(dataset)))
+}
@@ -0,0 +1,246 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::{Duration, Instant};
+use zere_sdk::api::assets::{Asset, AssetUploadMetadata};
+use zere_sdk::api::datasets::Dataset;
+use zere_sdk::api::jobs::{Job, JobProgress, JobStatus};
+use zere_sdk::api::uploads::UploadedPart;
+
+/// How long a new job sits in the queue before it starts rendering
+const QUEUE_DELAY: Duration = Duration::from_secs(2);
+
+/// Fake on-disk size of one rendered scene in a dataset
+const SCENE_SIZE_BYTES: i64 = 2 * 1_048_576;
+
+/// Settings the server was started with
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub api_key: String,
+    pub token_ttl_secs: i64,
+    pub scenes_per_sec: f64,
+}
+
+/// A job plus what is needed to simulate its progress
+pub struct MockJob {
+    pub job: Job,
+    /// Scene config the job was created with; asset usage is found by searching it for asset IDs
+    pub config_yaml: Option<String>,
+    started: Instant,
+    /// Scenes done when the job was cancelled, which freezes its progress
+    cancelled_at: Option<i32>,
+}
+
+/// A chunked upload that has not been completed yet
+pub struct MockUpload {
+    pub file_name: String,
+    pub size_bytes: u64,
+    pub part_size: u64,
+    pub parts: BTreeMap<u32, UploadedPart>,
+    /// Received bytes of each part
+    pub data: BTreeMap<u32, Vec<u8>>,
+    /// Name, tags and whole-file hash the client declared when starting the upload
+    pub metadata: AssetUploadMetadata,
+}
+
+impl MockUpload {
+    pub fn part_count(&self) -> u32 {
+        self.size_bytes.div_ceil(self.part_size).max(1) as u32
+    }
+
+    /// Length part `part_number` must have
+    pub fn part_len(&self, part_number: u32) -> u64 {
+        let offset = u64::from(part_number - 1) * self.part_size;
+        self.part_size.min(self.size_bytes.saturating_sub(offset))
+    }
+}
+
+/// Everything the mock backend knows, kept in memory for the lifetime of the process
+pub struct MockState {
+    pub settings: Settings,
+    pub jobs: Vec<MockJob>,
+    pub assets: Vec<Asset>,
+    /// Asset ID -> uploaded file contents
+    pub asset_files: HashMap<String, Vec<u8>>,
+    /// Issued access token -> expiry
+    pub access_tokens: HashMap<String, Instant>,
+    pub refresh_tokens: HashSet<String>,
+    /// Idempotency key -> job it created
+    pub idempotency_keys: HashMap<String, String>,
+    pub uploads: HashMap<String, MockUpload>,
+    next_id: i32,
+    token_counter: u64,
+}
+
+impl MockState {
+    pub fn new(settings: Settings) -> Self {
+        MockState {
+            settings,
+            jobs: Vec::new(),
+            assets: Vec::new(),
+            asset_files: HashMap::new(),
+            access_tokens: HashMap::new(),
+            refresh_tokens: HashSet::new(),
+            idempotency_keys: HashMap::new(),
+            uploads: HashMap::new(),
+            next_id: 0,
+            token_counter: 0,
+        }
+    }
+
+    pub fn next_id(&mut self) -> i32 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    /// Issue a new access/refresh token pair
+    pub fn issue_tokens(&mut self) -> (String, String) {
+        self.token_counter += 1;
+        let n = self.token_counter;
+        let access = format!("mock-access-{}", n);
+        let refresh = format!("mock-refresh-{}", n);
+        let ttl = Duration::from_secs(self.settings.token_ttl_secs.max(0) as u64);
+        self.access_tokens.insert(access.clone(), Instant::now() + ttl);
+        self.refresh_tokens.insert(refresh.clone());
+        (access, refresh)
+    }
+
+    /// Whether a bearer token is the configured API key or a live access token
+    pub fn is_authorized(&self, token: &str) -> bool {
+        token == self.settings.api_key
+            || self
+                .access_tokens
+                .get(token)
+                .is_some_and(|expires| *expires > Instant::now())
+    }
+
+    pub fn create_job(&mut self, name: String, num_scenes: i32, config_yaml: Option<String>) -> Job {
+        let id = self.next_id();
+        let job = Job {
+            id,
+            job_id: format!("job_{:04}", id),
+            name,
+            status: JobStatus::Queued,
+            num_scenes,
+            progress: Some(0),
+            created_at: timestamp(),
+            completed_at: None,
+        };
+        self.jobs.push(MockJob {
+            job: job.clone(),
+            config_yaml,
+            started: Instant::now(),
+            cancelled_at: None,
+        });
+        job
+    }
+
+    pub fn create_asset(&mut self, file_name: &str, contents: Vec<u8>, metadata: AssetUploadMetadata) -> Asset {
+        let id = self.next_id();
+        let asset = Asset {
+            id,
+            asset_id: format!("asset_{:04}", id),
+            name: metadata.name,
+            category: metadata.category,
+            tags: metadata.tags,
+            file_type: std::path::Path::new(file_name)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or_default()
+                .to_ascii_lowercase(),
+            size_bytes: contents.len() as i64,
+            thumbnail_url: Some(format!("/files/thumbnails/asset_{:04}.png", id)),
+            created_at: timestamp(),
+            sha256: metadata.sha256,
+            mesh: metadata.mesh,
+        };
+        self.assets.push(asset.clone());
+        self.asset_files.insert(asset.asset_id.clone(), contents);
+        asset
+    }
+
+    pub fn job_mut(&mut self, job_id: &str) -> Option<&mut MockJob> {
+        self.jobs.iter_mut().find(|j| j.job.job_id == job_id)
+    }
+
+    /// Advance every job to where it should be by now
+    pub fn tick(&mut self) {
+        let scenes_per_sec = self.settings.scenes_per_sec;
+        for job in &mut self.jobs {
+            job.advance(scenes_per_sec);
+        }
+    }
+
+    /// Datasets exist for every completed job
+    pub fn datasets(&self) -> Vec<Dataset> {
+        self.jobs
+            .iter()
+            .filter(|j| j.job.status == JobStatus::Completed)
+            .map(|j| Dataset {
+                id: j.job.id,
+                job_id: j.job.job_id.clone(),
+                name: j.job.name.clone(),
+                size_bytes: i64::from(j.job.num_scenes) * SCENE_SIZE_BYTES,
+                format: "coco".to_string(),
+                created_at: j.job.completed_at.clone().unwrap_or_else(timestamp),
+            })
+            .collect()
+    }
+}
+
+impl MockJob {
+    fn advance(&mut self, scenes_per_sec: f64) {
+        if let Some(done) = self.cancelled_at {
+            self.job.progress = Some(done);
+            return;
+        }
+        if self.job.status.is_terminal() {
+            return;
+        }
+
+        let elapsed = self.started.elapsed();
+        if elapsed < QUEUE_DELAY {
+            return;
+        }
+
+        let rendering = (elapsed - QUEUE_DELAY).as_secs_f64();
+        let done = ((rendering * scenes_per_sec) as i32).min(self.job.num_scenes);
+        self.job.progress = Some(done);
+        if done >= self.job.num_scenes {
+            self.job.status = JobStatus::Completed;
+            self.job.completed_at = Some(timestamp());
+        } else {
+            self.job.status = JobStatus::Running;
+        }
+    }
+
+    pub fn cancel(&mut self) -> bool {
+        if !self.job.status.can_transition_to(&JobStatus::Cancelled) {
+            return false;
+        }
+        self.cancelled_at = Some(self.job.progress.unwrap_or(0));
+        self.job.status = JobStatus::Cancelled;
+        true
+    }
+
+    pub fn progress(&self, scenes_per_sec: f64) -> JobProgress {
+        let done = self.job.progress.unwrap_or(0);
+        let total = self.job.num_scenes.max(1);
+        let eta_seconds = match self.job.status {
+            JobStatus::Queued | JobStatus::Running if scenes_per_sec > 0.0 => {
+                Some((f64::from(total - done) / scenes_per_sec).ceil() as i32)
+            }
+            _ => None,
+        };
+        JobProgress {
+            status: self.job.status.clone(),
+            progress: self.job.num_scenes,
+            progress_percent: done as f32 * 100.0 / total as f32,
+            scenes_generated: done,
+            eta_seconds,
+        }
+    }
+}
+
+/// Current time as an RFC 3339 UTC timestamp, like the real backend returns
+pub fn timestamp() -> String {
+    zere_sdk::time::rfc3339(zere_sdk::time::unix_now())
+}
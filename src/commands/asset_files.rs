@@ -0,0 +1,221 @@
+use crate::error::{CliError, Result};
+use globset::{Glob, GlobMatcher, GlobSet, GlobSetBuilder};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Suffix of the per-file metadata sidecar, e.g. `chair.glb.zere.toml` next to `chair.glb`
+pub(crate) const SIDECAR_SUFFIX: &str = ".zere.toml";
+
+/// Manifest `zere assets sync` reads from the root of the synced directory
+pub(crate) const MANIFEST_FILE: &str = "assets.toml";
+
+/// Metadata for one file, read from its sidecar
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Sidecar {
+    pub name: Option<String>,
+    pub category: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl Sidecar {
+    /// Load the sidecar of `file`, if it has one
+    pub(crate) fn for_file(file: &Path) -> Result<Option<Self>> {
+        let mut path = file.as_os_str().to_owned();
+        path.push(SIDECAR_SUFFIX);
+        let path = PathBuf::from(path);
+
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        toml::from_str(&contents)
+            .map(Some)
+            .map_err(|e| CliError::Other(format!("Invalid sidecar {}: {}", path.display(), e.message())))
+    }
+}
+
+impl Sidecar {
+    /// Layer `over` on top: its name and category win, its tags are added
+    pub(crate) fn merge(&mut self, over: Sidecar) {
+        if over.name.is_some() {
+            self.name = over.name;
+        }
+        if over.category.is_some() {
+            self.category = over.category;
+        }
+        for tag in over.tags {
+            if !self.tags.contains(&tag) {
+                self.tags.push(tag);
+            }
+        }
+    }
+}
+
+/// `assets.toml`: which files of a directory to sync, and their metadata.
+///
+/// ```toml
+/// exclude = ["drafts/**"]
+///
+/// [[asset]]
+/// path = "chairs/*.glb"
+/// category = "seating"
+/// tags = ["oak"]
+///
+/// [[asset]]
+/// path = "lamp.glb"
+/// name = "Desk Lamp"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Manifest {
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    #[serde(default, rename = "asset")]
+    entries: Vec<ManifestEntry>,
+    #[serde(skip)]
+    matchers: Vec<GlobMatcher>,
+}
+
+/// One `[[asset]]`: metadata for a file, or for every file matching a glob
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ManifestEntry {
+    path: String,
+    name: Option<String>,
+    category: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+impl Manifest {
+    /// Load `dir`'s manifest; a directory without one syncs every file with default metadata
+    pub(crate) fn load(dir: &Path) -> Result<Manifest> {
+        let path = dir.join(MANIFEST_FILE);
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Manifest::default()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut manifest: Manifest = toml::from_str(&contents)
+            .map_err(|e| CliError::Other(format!("Invalid manifest {}: {}", path.display(), e.message())))?;
+
+        manifest.matchers = manifest
+            .entries
+            .iter()
+            .map(|entry| {
+                Glob::new(&entry.path)
+                    .map(|glob| glob.compile_matcher())
+                    .map_err(|e| CliError::Other(format!("Invalid pattern '{}' in {}: {}", entry.path, path.display(), e)))
+            })
+            .collect::<Result<_>>()?;
+        Ok(manifest)
+    }
+
+    /// Metadata for a file given by its path relative to the directory.
+    ///
+    /// Every matching entry applies in order, so a later entry overrides the name and
+    /// category of an earlier one and adds to its tags.
+    pub(crate) fn metadata_for(&self, relative: &Path) -> Sidecar {
+        let mut metadata = Sidecar::default();
+        for (entry, matcher) in self.entries.iter().zip(&self.matchers) {
+            if matcher.is_match(relative) {
+                metadata.merge(Sidecar {
+                    name: entry.name.clone(),
+                    category: entry.category.clone(),
+                    tags: entry.tags.clone(),
+                });
+            }
+        }
+        metadata
+    }
+}
+
+fn glob_set(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern)
+            .map_err(|e| CliError::Other(format!("Invalid pattern '{}': {}", pattern, e)))?;
+        builder.add(glob);
+    }
+    builder
+        .build()
+        .map_err(|e| CliError::Other(format!("Invalid patterns: {}", e)))
+}
+
+/// Expand upload arguments into the files to upload.
+///
+/// Files are taken as given. Directories are walked recursively, skipping hidden
+/// entries and sidecars; `include` and `exclude` globs are matched against paths
+/// relative to the directory, and a file must match some `include` (if any are
+/// given) and no `exclude`.
+pub(crate) fn collect_files(paths: &[PathBuf], include: &[String], exclude: &[String]) -> Result<Vec<PathBuf>> {
+    let include = glob_set(include)?;
+    let exclude = glob_set(exclude)?;
+
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_file() {
+            files.push(path.clone());
+            continue;
+        }
+        if !path.is_dir() {
+            return Err(CliError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("File not found: {}", path.display()),
+            )));
+        }
+
+        let walker = WalkDir::new(path)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|entry| entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.'));
+        for entry in walker {
+            let entry = entry.map_err(|e| CliError::Other(format!("Failed to read {}: {}", path.display(), e)))?;
+            if !entry.file_type().is_file() || entry.file_name().to_string_lossy().ends_with(SIDECAR_SUFFIX) {
+                continue;
+            }
+
+            let relative = entry.path().strip_prefix(path).unwrap_or(entry.path());
+            if (include.is_empty() || include.is_match(relative)) && !exclude.is_match(relative) {
+                files.push(entry.into_path());
+            }
+        }
+    }
+
+    // The same file may be named twice, e.g. directly and via its directory
+    let mut seen = HashSet::new();
+    files.retain(|file| seen.insert(file.clone()));
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_files_filters_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        for file in ["a.glb", "a.glb.zere.toml", "b.obj", "old/c.glb", ".cache/d.glb", "e.png"] {
+            let path = dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"").unwrap();
+        }
+
+        let include = ["*.glb".to_string(), "*.obj".to_string()];
+        let files = collect_files(&[dir.path().to_path_buf()], &include, &["old/**".to_string()]).unwrap();
+        assert_eq!(files, [dir.path().join("a.glb"), dir.path().join("b.obj")]);
+
+        std::fs::write(dir.path().join("a.glb.zere.toml"), "category = \"chairs\"\ntags = [\"oak\"]\n").unwrap();
+        let sidecar = Sidecar::for_file(&dir.path().join("a.glb")).unwrap().unwrap();
+        assert_eq!(sidecar.category.as_deref(), Some("chairs"));
+        assert_eq!(sidecar.tags, ["oak"]);
+        assert_eq!(Sidecar::for_file(&dir.path().join("b.obj")).unwrap(), None);
+    }
+}
@@ -0,0 +1,545 @@
+use crate::api::assets::{file_sha256, Asset, AssetQuery, AssetUpdate, AssetUploadMetadata, AssetUsage};
+use crate::api::pagination::DEFAULT_PAGE_SIZE;
+use crate::api::ZereApi;
+use crate::error::{CliError, Result};
+use crate::validate;
+use super::asset_files::{collect_files, Manifest, Sidecar, MANIFEST_FILE};
+use super::assets::{DELETE_CONCURRENCY, bytes_style, check_active_jobs, print_report, print_usage, upload_file};
+use console::style;
+use futures::{stream, StreamExt, TryStreamExt};
+use indicatif::ProgressBar;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Options for `zere assets sync`
+pub struct SyncOptions {
+    pub dir: PathBuf,
+    /// Only server assets in this category take part, and every local file is put in it
+    pub category: Option<String>,
+    /// Only server assets with this tag take part, and every local file gets it
+    pub tag: Option<String>,
+    /// Delete server assets that have no local file
+    pub prune: bool,
+    /// Print the plan without changing anything
+    pub dry_run: bool,
+    /// Apply deletions and replacements without asking
+    pub yes: bool,
+    /// Also replace and delete assets that queued, running or paused jobs use
+    pub force: bool,
+    /// Check files locally before sending them
+    pub validate: bool,
+}
+
+/// A file in the synced directory and the asset it should become
+struct LocalFile {
+    path: PathBuf,
+    /// Path relative to the synced directory, as shown in the plan
+    display: String,
+    metadata: AssetUploadMetadata,
+}
+
+impl LocalFile {
+    fn sha256(&self) -> &str {
+        self.metadata.sha256.as_deref().unwrap_or_default()
+    }
+
+    /// The category and tags to set on `asset`, where they differ.
+    ///
+    /// The API cannot clear a category, so a file without one leaves the asset's alone.
+    fn update_for(&self, asset: &Asset) -> AssetUpdate {
+        let mut wanted = self.metadata.tags.clone();
+        let mut current = asset.tags.clone();
+        wanted.sort();
+        current.sort();
+        AssetUpdate {
+            name: None,
+            category: self.metadata.category.clone().filter(|c| asset.category.as_ref() != Some(c)),
+            tags: (wanted != current).then(|| self.metadata.tags.clone()),
+        }
+    }
+}
+
+/// One step of the plan
+enum Change {
+    Create(LocalFile),
+    /// The content changed: upload the file, then delete the old asset
+    Replace(LocalFile, Asset),
+    /// Only the category or tags changed
+    Update(LocalFile, Asset, AssetUpdate),
+    Delete(Asset),
+}
+
+impl Change {
+    fn is_destructive(&self) -> bool {
+        self.removed().is_some()
+    }
+
+    /// The server asset the change deletes, if any
+    fn removed(&self) -> Option<&Asset> {
+        match self {
+            Change::Replace(_, asset) | Change::Delete(asset) => Some(asset),
+            Change::Create(_) | Change::Update(..) => None,
+        }
+    }
+
+    /// The file or, for deletions, the asset name the change is listed under
+    fn label(&self) -> &str {
+        match self {
+            Change::Create(file) | Change::Replace(file, _) | Change::Update(file, ..) => &file.display,
+            Change::Delete(asset) => &asset.name,
+        }
+    }
+
+    fn print(&self, width: usize) {
+        let label = format!("{:<width$}", self.label(), width = width);
+        match self {
+            Change::Create(file) => println!(
+                "  {} {}  {}",
+                style("+").green().bold(),
+                style(&label).green(),
+                style(format!("create '{}'", file.metadata.name)).dim()
+            ),
+            Change::Replace(_, asset) => println!(
+                "  {} {}  {}",
+                style("-/+").red().bold(),
+                style(&label).yellow(),
+                style(format!("replace {} (content changed)", asset.asset_id)).dim()
+            ),
+            Change::Update(_, asset, update) => {
+                let mut changes = Vec::new();
+                if let Some(category) = &update.category {
+                    changes.push(format!(
+                        "category {} → {}",
+                        asset.category.as_deref().unwrap_or("none"),
+                        category
+                    ));
+                }
+                if let Some(tags) = &update.tags {
+                    changes.push(format!("tags [{}] → [{}]", asset.tags.join(", "), tags.join(", ")));
+                }
+                println!(
+                    "  {} {}  {}",
+                    style("~").yellow().bold(),
+                    style(&label).yellow(),
+                    style(format!("update {}: {}", asset.asset_id, changes.join(", "))).dim()
+                );
+            }
+            Change::Delete(asset) => println!(
+                "  {} {}  {}",
+                style("-").red().bold(),
+                style(&label).red(),
+                style(format!("delete {}", asset.asset_id)).dim()
+            ),
+        }
+    }
+}
+
+/// What sync would do
+#[derive(Default)]
+struct Plan {
+    changes: Vec<Change>,
+    unchanged: usize,
+    /// Server assets without a local file, left alone because `prune` is off
+    orphans: usize,
+}
+
+impl Plan {
+    fn count(&self, kind: fn(&Change) -> bool) -> usize {
+        self.changes.iter().filter(|change| kind(change)).count()
+    }
+
+    fn print_summary(&self) {
+        println!(
+            "{} {} to create, {} to replace, {} to update, {} to delete. {} unchanged.",
+            style("Plan:").bold(),
+            style(self.count(|c| matches!(c, Change::Create(_)))).green().bold(),
+            style(self.count(|c| matches!(c, Change::Replace(..)))).yellow().bold(),
+            style(self.count(|c| matches!(c, Change::Update(..)))).yellow().bold(),
+            style(self.count(|c| matches!(c, Change::Delete(_)))).red().bold(),
+            self.unchanged
+        );
+    }
+}
+
+/// The directory's files with the metadata the manifest, their sidecars and the scope give them
+async fn local_files(options: &SyncOptions) -> Result<Vec<LocalFile>> {
+    if !options.dir.is_dir() {
+        return Err(CliError::Other(format!("Not a directory: {}", options.dir.display())));
+    }
+    let manifest = Manifest::load(&options.dir)?;
+    let manifest_path = options.dir.join(MANIFEST_FILE);
+    let paths = collect_files(std::slice::from_ref(&options.dir), &manifest.include, &manifest.exclude)?;
+
+    let mut files: Vec<LocalFile> = Vec::new();
+    let mut names: HashMap<String, String> = HashMap::new();
+    for path in paths.into_iter().filter(|path| *path != manifest_path) {
+        let relative = path.strip_prefix(&options.dir).unwrap_or(&path).to_path_buf();
+        let mut metadata = manifest.metadata_for(&relative);
+        if let Some(sidecar) = Sidecar::for_file(&path)? {
+            metadata.merge(sidecar);
+        }
+        if let Some(tag) = &options.tag {
+            metadata.merge(Sidecar {
+                tags: vec![tag.clone()],
+                ..Sidecar::default()
+            });
+        }
+
+        let display = relative.display().to_string();
+        let name = match metadata.name {
+            Some(name) => name,
+            None => path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("upload")
+                .to_string(),
+        };
+        // Assets are matched to files by name, so names must be unique
+        if let Some(other) = names.insert(name.clone(), display.clone()) {
+            return Err(CliError::Other(format!(
+                "{} and {} would both be named '{}'; give one of them a name in {}",
+                other, display, name, MANIFEST_FILE
+            )));
+        }
+
+        let sha256 = file_sha256(&path).await?;
+        files.push(LocalFile {
+            path,
+            display,
+            metadata: AssetUploadMetadata {
+                name,
+                category: options.category.clone().or(metadata.category),
+                tags: metadata.tags,
+                sha256: Some(sha256),
+                mesh: None,
+            },
+        });
+    }
+    Ok(files)
+}
+
+/// Match local files to server assets by name and work out what has to change
+fn plan(files: Vec<LocalFile>, assets: Vec<Asset>, prune: bool) -> Plan {
+    let mut by_name: HashMap<String, Vec<Asset>> = HashMap::new();
+    for asset in assets {
+        by_name.entry(asset.name.clone()).or_default().push(asset);
+    }
+
+    let mut plan = Plan::default();
+    for file in files {
+        let Some(candidates) = by_name.get_mut(&file.metadata.name).filter(|c| !c.is_empty()) else {
+            plan.changes.push(Change::Create(file));
+            continue;
+        };
+        // Of several assets with the same name, prefer one with the same content; the rest are orphans
+        let index = candidates
+            .iter()
+            .position(|asset| asset.sha256.as_deref() == Some(file.sha256()))
+            .unwrap_or(0);
+        let asset = candidates.remove(index);
+
+        // Without a stored hash the content cannot be compared, so it is left alone
+        if asset.sha256.as_deref().is_some_and(|sha256| sha256 != file.sha256()) {
+            plan.changes.push(Change::Replace(file, asset));
+            continue;
+        }
+        let update = file.update_for(&asset);
+        if update.is_empty() {
+            plan.unchanged += 1;
+        } else {
+            plan.changes.push(Change::Update(file, asset, update));
+        }
+    }
+
+    let mut orphans: Vec<Asset> = by_name.into_values().flatten().collect();
+    orphans.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.asset_id.cmp(&b.asset_id)));
+    if prune {
+        plan.changes.extend(orphans.into_iter().map(Change::Delete));
+    } else {
+        plan.orphans = orphans.len();
+    }
+    plan
+}
+
+/// Validate the files the plan uploads, printing those with issues
+async fn validate_uploads(plan: &Plan) -> Result<()> {
+    let mut invalid = 0;
+    for change in &plan.changes {
+        let (Change::Create(file) | Change::Replace(file, _)) = change else {
+            continue;
+        };
+        let report = validate::validate_file_async(file.path.clone()).await?;
+        if !report.issues.is_empty() {
+            print_report(&report);
+        }
+        if !report.is_valid() {
+            invalid += 1;
+        }
+    }
+    if invalid > 0 {
+        return Err(CliError::Other(format!(
+            "{} file{} failed validation (use --no-validate to sync anyway)",
+            invalid,
+            if invalid == 1 { "" } else { "s" }
+        )));
+    }
+    Ok(())
+}
+
+/// List the jobs and configs of every asset the plan deletes that active jobs use,
+/// returning how many there are
+async fn print_assets_in_use(api: &impl ZereApi, plan: &Plan) -> Result<usize> {
+    let removed: Vec<(&Asset, AssetUsage)> = stream::iter(plan.changes.iter().filter_map(Change::removed))
+        .map(|asset| async move { Ok::<_, CliError>((asset, api.asset_usage(&asset.asset_id).await?)) })
+        .buffered(DELETE_CONCURRENCY)
+        .try_collect()
+        .await?;
+
+    let mut in_use = 0;
+    for (asset, usage) in removed.iter().filter(|(_, usage)| usage.active_jobs().next().is_some()) {
+        in_use += 1;
+        println!();
+        println!("{} is referenced by:", style(&asset.name).white().bold());
+        print_usage(usage);
+    }
+    Ok(in_use)
+}
+
+/// Carry out one change, returning the asset it leaves on the server, if any
+async fn apply(api: &impl ZereApi, change: &Change) -> Result<Option<String>> {
+    match change {
+        Change::Create(file) | Change::Replace(file, _) => {
+            let pb = ProgressBar::new(0);
+            pb.set_style(bytes_style());
+            let (_, result) = upload_file(api, &file.path, file.metadata.clone(), false, &pb).await;
+            pb.finish_and_clear();
+            let asset = result?;
+            if let Change::Replace(_, old) = change {
+                api.delete_asset(&old.asset_id).await?;
+            }
+            Ok(Some(asset.asset_id))
+        }
+        Change::Update(_, asset, update) => Ok(Some(api.update_asset(&asset.asset_id, update).await?.asset_id)),
+        Change::Delete(asset) => {
+            api.delete_asset(&asset.asset_id).await?;
+            Ok(None)
+        }
+    }
+}
+
+pub async fn sync(api: &impl ZereApi, options: SyncOptions) -> Result<()> {
+    let files = local_files(&options).await?;
+    let query = AssetQuery {
+        category: options.category.clone(),
+        tag: options.tag.clone(),
+        ..AssetQuery::default()
+    };
+    let assets: Vec<Asset> = api.search_assets_stream(&query, DEFAULT_PAGE_SIZE).try_collect().await?;
+    let plan = plan(files, assets, options.prune);
+
+    println!(
+        "\n{}",
+        style(format!("Sync plan for {}", options.dir.display())).bold().underlined()
+    );
+    println!();
+    if plan.changes.is_empty() {
+        println!(
+            "{} Everything is up to date ({} unchanged)",
+            style("✓").green().bold(),
+            plan.unchanged
+        );
+    } else {
+        let width = plan.changes.iter().map(|change| change.label().len()).max().unwrap_or(0);
+        for change in &plan.changes {
+            change.print(width);
+        }
+        println!();
+        plan.print_summary();
+    }
+    if plan.orphans > 0 {
+        println!(
+            "{} {} asset{} on the server {} no local file. Use --prune to delete {}.",
+            style("ℹ").blue(),
+            plan.orphans,
+            if plan.orphans == 1 { "" } else { "s" },
+            if plan.orphans == 1 { "has" } else { "have" },
+            if plan.orphans == 1 { "it" } else { "them" }
+        );
+    }
+    if plan.changes.is_empty() {
+        return Ok(());
+    }
+
+    if options.validate {
+        validate_uploads(&plan).await?;
+    }
+    let in_use = print_assets_in_use(api, &plan).await?;
+    if in_use > 0 {
+        println!();
+    }
+    if options.dry_run {
+        println!("{}", style("Dry run: nothing was changed").yellow());
+        return Ok(());
+    }
+    check_active_jobs(in_use, options.force)?;
+
+    if !options.yes && plan.changes.iter().any(Change::is_destructive) {
+        let confirm = inquire::Confirm::new("Apply this plan? Replaced and deleted assets cannot be recovered.")
+            .with_default(false)
+            .prompt()
+            .unwrap_or(false);
+        if !confirm {
+            println!("{}", style("Cancelled").yellow());
+            return Ok(());
+        }
+    }
+
+    println!();
+    let width = plan.changes.iter().map(|change| change.label().len()).max().unwrap_or(0);
+    let mut failed = 0;
+    for change in &plan.changes {
+        let label = format!("{:<width$}", change.label(), width = width);
+        match apply(api, change).await {
+            Ok(Some(asset_id)) => {
+                println!("  {} {}  {}", style("✓").green().bold(), label, style(asset_id).cyan())
+            }
+            Ok(None) => println!("  {} {}  {}", style("✓").green().bold(), label, style("deleted").dim()),
+            Err(e) => {
+                failed += 1;
+                println!("  {} {}  {}", style("✗").red().bold(), label, style(e).red());
+            }
+        }
+    }
+
+    println!();
+    println!(
+        "  {} applied, {} failed",
+        style(plan.changes.len() - failed).green().bold(),
+        style(failed).red().bold()
+    );
+    if failed > 0 {
+        return Err(CliError::Other(format!("{} of {} changes failed", failed, plan.changes.len())));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::assets::AssetJobRef;
+    use crate::api::fake::{self, FakeApi};
+    use crate::api::jobs::JobStatus;
+
+    #[tokio::test]
+    async fn test_sync_plans_and_applies_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("chairs")).unwrap();
+        for (file, contents) in [("chairs/oak.glb", "oak"), ("chairs/pine.glb", "pine v2"), ("lamp.glb", "lamp")] {
+            std::fs::write(dir.path().join(file), contents).unwrap();
+        }
+        std::fs::write(
+            dir.path().join(MANIFEST_FILE),
+            "[[asset]]\npath = \"chairs/*\"\ncategory = \"seating\"\ntags = [\"wood\"]\n\n\
+             [[asset]]\npath = \"chairs/oak.glb\"\nname = \"Oak Chair\"\ntags = [\"oak\"]\n",
+        )
+        .unwrap();
+
+        let oak = file_sha256(&dir.path().join("chairs/oak.glb")).await.unwrap();
+        let stored = |asset_id: &str, name: &str, sha256: Option<String>, tags: &[&str]| Asset {
+            category: Some("seating".to_string()),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            sha256,
+            ..fake::asset(asset_id, name)
+        };
+        let api = FakeApi::new().with_assets(vec![
+            stored("asset-1", "Oak Chair", Some(oak), &["wood"]),
+            stored("asset-2", "pine", Some("0".repeat(64)), &["wood"]),
+            stored("asset-3", "stool", None, &[]),
+        ]);
+
+        let options = |prune, dry_run| SyncOptions {
+            dir: dir.path().to_path_buf(),
+            category: None,
+            tag: None,
+            prune,
+            dry_run,
+            yes: true,
+            force: false,
+            validate: false,
+        };
+        sync(&api, options(true, true)).await.unwrap();
+        assert_eq!(api.assets().len(), 3);
+
+        sync(&api, options(true, false)).await.unwrap();
+        let mut assets: Vec<(String, Option<String>, Vec<String>)> = api
+            .assets()
+            .into_iter()
+            .map(|asset| (asset.name, asset.category, asset.tags))
+            .collect();
+        assets.sort();
+        let seating = Some("seating".to_string());
+        assert_eq!(
+            assets,
+            [
+                ("Oak Chair".to_string(), seating.clone(), vec!["wood".to_string(), "oak".to_string()]),
+                ("lamp".to_string(), None, vec![]),
+                ("pine".to_string(), seating, vec!["wood".to_string()]),
+            ]
+        );
+        assert!(api.calls().contains(&"update_asset asset-1".to_string()));
+        assert!(api.calls().contains(&"delete_asset asset-2".to_string()));
+
+        // A second run has nothing left to do
+        let calls = api.calls().len();
+        sync(&api, options(true, false)).await.unwrap();
+        assert!(api.calls()[calls..].iter().all(|call| call.starts_with("search_assets_page")));
+    }
+
+    #[tokio::test]
+    async fn test_sync_leaves_assets_without_hash_or_in_use() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("stool.glb"), "stool").unwrap();
+        std::fs::write(dir.path().join("table.glb"), "table v2").unwrap();
+
+        let stored = |asset_id: &str, name: &str, sha256: Option<String>| Asset {
+            sha256,
+            ..fake::asset(asset_id, name)
+        };
+        let usage = AssetUsage {
+            jobs: vec![AssetJobRef {
+                job_id: "job-1".to_string(),
+                name: "Warehouse".to_string(),
+                status: JobStatus::Queued,
+            }],
+            configs: Vec::new(),
+        };
+        let api = FakeApi::new()
+            .with_assets(vec![
+                stored("asset-1", "stool", None),
+                stored("asset-2", "table", Some("0".repeat(64))),
+            ])
+            .with_usage("asset-2", usage);
+
+        let options = |dry_run, force| SyncOptions {
+            dir: dir.path().to_path_buf(),
+            category: None,
+            tag: None,
+            prune: false,
+            dry_run,
+            yes: true,
+            force,
+            validate: false,
+        };
+        sync(&api, options(true, false)).await.unwrap();
+
+        let err = sync(&api, options(false, false)).await.unwrap_err();
+        assert!(err.to_string().contains("1 asset is used by active jobs"), "{}", err);
+        assert_eq!(api.assets().len(), 2);
+
+        sync(&api, options(false, true)).await.unwrap();
+        let mut ids: Vec<String> = api.assets().into_iter().map(|asset| asset.asset_id).collect();
+        ids.sort();
+        assert_eq!(ids[0], "asset-1");
+        assert!(!ids.contains(&"asset-2".to_string()));
+    }
+}
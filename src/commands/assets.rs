@@ -1,23 +1,65 @@
-use crate::api::ApiClient;
-use crate::config::Config;
-use crate::error::Result;
-use console::style;
-use indicatif::{ProgressBar, ProgressStyle};
-use std::path::PathBuf;
+use crate::api::assets::{file_sha256, Asset, AssetQuery, AssetUpdate, AssetUploadMetadata, AssetUsage};
+use crate::api::pagination::{PageRequest, DEFAULT_PAGE_SIZE};
+use crate::api::uploads::{self, ChunkedUploadOptions, CHUNKED_UPLOAD_THRESHOLD};
+use crate::api::{ProgressCallback, ZereApi};
+use crate::error::{CliError, Result};
+use crate::preview::{Graphics, Thumbnail};
+use crate::time::parse_rfc3339;
+use crate::validate::{self, MeshStats, Report, Severity};
+use super::asset_files::{collect_files, Sidecar};
+use clap::ValueEnum;
+use console::{style, StyledObject};
+use futures::{stream, StreamExt, TryStreamExt};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-pub async fn list() -> Result<()> {
-    let config = Config::load()?;
-    let client = ApiClient::from_config(&config)?;
+/// Width in terminal cells of `assets info --preview`
+const PREVIEW_COLUMNS: u16 = 40;
 
-    let assets = client.list_assets().await?;
+/// How many assets `assets delete` and `assets sync` look up or remove at once
+pub(super) const DELETE_CONCURRENCY: usize = 8;
+
+pub async fn list(api: &impl ZereApi, limit: u32, page: u32, all: bool, query: AssetQuery) -> Result<()> {
+    let request = PageRequest::page(limit, page);
+    let (assets, offset, total) = if all {
+        let assets = matching_assets(api, &query, limit).await?;
+        let total = assets.len() as i32;
+        (assets, 0, total)
+    } else if query.is_empty() {
+        let page = api.list_assets_page(request).await?;
+        (page.items, page.offset, page.total)
+    } else {
+        let page = if query.needs_client_filtering() {
+            None
+        } else {
+            Some(api.search_assets_page(&query, request).await?)
+        };
+        match page {
+            Some(page) if page.items.iter().all(|a| query.matches(a)) && query.is_sorted(&page.items) => {
+                (page.items, page.offset, page.total)
+            }
+            // The server could not apply the whole query: do it here and cut the page out
+            _ => {
+                let assets = matching_assets(api, &query, limit).await?;
+                let total = assets.len() as i32;
+                let items = assets
+                    .into_iter()
+                    .skip(request.offset as usize)
+                    .take(limit as usize)
+                    .collect();
+                (items, request.offset, total)
+            }
+        }
+    };
 
     if assets.is_empty() {
         println!("{}", style("No assets found").yellow());
         return Ok(());
     }
 
-    println!("\n{}", style(format!("Assets ({})", assets.len())).bold().underlined());
-    println!();
+    let shown = assets.len();
+    super::print_list_heading("Assets", shown, offset, total);
 
     for asset in assets {
         let size_mb = asset.size_bytes as f64 / 1_048_576.0;
@@ -35,56 +77,552 @@ pub async fn list() -> Result<()> {
             style("Created:").dim(),
             asset.created_at
         );
+        if let Some(labels) = labels(&asset) {
+            println!("    {}", labels);
+        }
         println!();
     }
 
+    super::print_page_hint("assets list", &list_args(&query), shown, offset, limit, total);
+
     Ok(())
 }
 
-pub async fn info(asset_id: String) -> Result<()> {
-    let config = Config::load()?;
-    let client = ApiClient::from_config(&config)?;
+/// The `assets list` flags that select `query`
+fn list_args(query: &AssetQuery) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut push = |flag: &str, value: Option<String>| {
+        if let Some(value) = value {
+            args.push(flag.to_string());
+            args.push(value);
+        }
+    };
+    push("--type", query.file_type.clone());
+    push("--category", query.category.clone());
+    push("--tag", query.tag.clone());
+    push("--name", query.name.as_ref().map(|name| name.glob().glob().to_string()));
+    push("--min-size", query.min_size.map(|size| size.to_string()));
+    push("--max-size", query.max_size.map(|size| size.to_string()));
+    push("--created-after", query.created_after.clone());
+    push("--created-before", query.created_before.clone());
+    push("--sort", query.sort.and_then(|sort| sort.to_possible_value()).map(|value| value.get_name().to_string()));
+    if query.descending {
+        args.push("--desc".to_string());
+    }
+    args
+}
+
+/// Every asset matching `query`, sorted as it asks
+async fn matching_assets(api: &impl ZereApi, query: &AssetQuery, page_size: u32) -> Result<Vec<Asset>> {
+    let mut assets: Vec<Asset> = api.search_assets_stream(query, page_size).try_collect().await?;
+    query.sort(&mut assets);
+    Ok(assets)
+}
+
+/// Byte counts such as `500`, `20KB`, `1.5 MB` or `2GiB` (units are powers of 1024)
+pub fn parse_size(s: &str) -> std::result::Result<u64, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let number: f64 = number.parse().map_err(|_| format!("invalid size '{}'", s))?;
+    let multiplier: u64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1 << 10,
+        "m" | "mb" | "mib" => 1 << 20,
+        "g" | "gb" | "gib" => 1 << 30,
+        "t" | "tb" | "tib" => 1 << 40,
+        _ => return Err(format!("unknown size unit '{}' (expected B, KB, MB, GB or TB)", unit.trim())),
+    };
+    Ok((number * multiplier as f64).round() as u64)
+}
+
+/// A `YYYY-MM-DD` date or an RFC 3339 timestamp such as `2024-05-01T12:00:00Z`
+pub fn parse_date(s: &str) -> std::result::Result<String, String> {
+    match parse_rfc3339(s) {
+        Some(_) => Ok(s.to_string()),
+        None => Err(format!("invalid date '{}' (expected YYYY-MM-DD or YYYY-MM-DDTHH:MM:SSZ)", s)),
+    }
+}
+
+/// An age such as `90m`, `12h`, `30d` or `2w`, in seconds
+pub fn parse_age(s: &str) -> std::result::Result<u64, String> {
+    let invalid = || format!("invalid age '{}' (expected a number and s, m, h, d or w, e.g. 30d)", s);
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let number: u64 = number.parse().map_err(|_| invalid())?;
+    let unit_secs = match unit.trim().to_ascii_lowercase().as_str() {
+        "s" => 1,
+        "m" | "min" => 60,
+        "h" => 3600,
+        "d" => 86_400,
+        "w" => 7 * 86_400,
+        _ => return Err(invalid()),
+    };
+    Ok(number * unit_secs)
+}
+
+/// Category and tags on one line, if the asset has either
+fn labels(asset: &Asset) -> Option<String> {
+    let mut parts = Vec::new();
+    if let Some(category) = &asset.category {
+        parts.push(format!("{} {}", style("Category:").dim(), category));
+    }
+    if !asset.tags.is_empty() {
+        parts.push(format!("{} {}", style("Tags:").dim(), asset.tags.join(", ")));
+    }
+    (!parts.is_empty()).then(|| parts.join("  "))
+}
+
+pub async fn info(api: &impl ZereApi, asset_id: String, preview: bool) -> Result<()> {
+    let asset = api.get_asset(&asset_id).await?;
+    let thumbnail_url = asset.thumbnail_url.clone();
+    print_details(asset);
 
-    let asset = client.get_asset(&asset_id).await?;
+    if preview {
+        let Some(url) = thumbnail_url else {
+            println!("{}", style("No thumbnail available").yellow());
+            return Ok(());
+        };
+        let thumbnail = Thumbnail::decode(api.get_thumbnail(&url).await?)?;
+        let width = console::Term::stdout().size().1.saturating_sub(4);
+        thumbnail.print(Graphics::detect(), PREVIEW_COLUMNS.min(width).max(1))?;
+        println!();
+    }
+    Ok(())
+}
 
+fn print_details(asset: Asset) {
     println!("\n{}", style("Asset Details").bold().underlined());
     println!("  {} {}", style("ID:").dim(), asset.asset_id);
     println!("  {} {}", style("Name:").dim(), asset.name);
+    if let Some(category) = &asset.category {
+        println!("  {} {}", style("Category:").dim(), category);
+    }
+    if !asset.tags.is_empty() {
+        println!("  {} {}", style("Tags:").dim(), asset.tags.join(", "));
+    }
     println!("  {} {}", style("Type:").dim(), asset.file_type);
     println!("  {} {:.2} MB", style("Size:").dim(), asset.size_bytes as f64 / 1_048_576.0);
     println!("  {} {}", style("Created:").dim(), asset.created_at);
+    if let Some(mesh) = &asset.mesh {
+        let size = mesh
+            .bounds
+            .map(|bounds| {
+                let [x, y, z] = bounds.size();
+                format!(", {:.3} x {:.3} x {:.3} m", x, y, z)
+            })
+            .unwrap_or_default();
+        println!(
+            "  {} {} vertices, {} triangles{}",
+            style("Mesh:").dim(),
+            mesh.vertices,
+            mesh.triangles,
+            size
+        );
+    }
 
     if let Some(thumbnail) = asset.thumbnail_url {
         println!("  {} {}", style("Thumbnail:").dim(), thumbnail);
     }
 
     println!();
+}
+
+/// Changes requested by `zere assets update`
+pub struct UpdateOptions {
+    pub name: Option<String>,
+    pub category: Option<String>,
+    pub add_tags: Vec<String>,
+    pub remove_tags: Vec<String>,
+}
+
+/// `current` without `remove`, followed by the tags from `add` it does not already have
+fn edit_tags(current: &[String], add: &[String], remove: &[String]) -> Vec<String> {
+    let mut tags: Vec<String> = current.iter().filter(|tag| !remove.contains(tag)).cloned().collect();
+    for tag in add {
+        if !tags.contains(tag) {
+            tags.push(tag.clone());
+        }
+    }
+    tags
+}
+
+pub async fn update(api: &impl ZereApi, asset_id: String, options: UpdateOptions) -> Result<()> {
+    let mut update = AssetUpdate {
+        name: options.name,
+        category: options.category,
+        tags: None,
+    };
+    if !options.add_tags.is_empty() || !options.remove_tags.is_empty() {
+        let current = api.get_asset(&asset_id).await?;
+        let tags = edit_tags(&current.tags, &options.add_tags, &options.remove_tags);
+        if tags != current.tags {
+            update.tags = Some(tags);
+        }
+    }
+    if update.name.as_deref().is_some_and(|name| name.trim().is_empty()) {
+        return Err(CliError::Other("Asset name must not be empty".to_string()));
+    }
+    if update.is_empty() {
+        println!("{}", style("Nothing to update").yellow());
+        return Ok(());
+    }
+
+    let asset = api.update_asset(&asset_id, &update).await?;
+    println!(
+        "{} Asset updated: {} ({})",
+        style("✓").green().bold(),
+        style(&asset.name).white().bold(),
+        style(&asset.asset_id).cyan()
+    );
+    print_details(asset);
     Ok(())
 }
 
-pub async fn upload(
-    file_path: PathBuf,
-    name: Option<String>,
-    category: Option<String>,
-    tags: Vec<String>,
-) -> Result<()> {
-    let config = Config::load()?;
-    let client = ApiClient::from_config(&config)?;
+/// Options for `zere assets upload`
+#[derive(Clone)]
+pub struct UploadOptions {
+    /// Asset name; only allowed for a single file
+    pub name: Option<String>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub resume: bool,
+    /// Upload files even if identical content exists, deleting the older copies
+    pub replace: bool,
+    /// With `replace`, also delete older copies that queued, running or paused jobs use
+    pub force: bool,
+    /// Check files locally before sending them
+    pub validate: bool,
+    /// Globs selecting files inside directory arguments
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    /// How many files to upload at once
+    pub concurrency: usize,
+    /// Attach the mesh statistics `zere assets inspect` reports
+    pub inspect: bool,
+}
 
-    if !file_path.exists() {
-        return Err(crate::error::CliError::Io(std::io::Error::new(
-            std::io::ErrorKind::NotFound,
-            format!("File not found: {}", file_path.display()),
-        )));
+/// The defaults of `zere assets upload`
+impl Default for UploadOptions {
+    fn default() -> Self {
+        UploadOptions {
+            name: None,
+            category: None,
+            tags: Vec::new(),
+            resume: false,
+            replace: false,
+            force: false,
+            validate: true,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            concurrency: 4,
+            inspect: false,
+        }
     }
+}
 
-    let file_name = file_path
+/// Byte progress of one file
+pub(crate) fn bytes_style() -> ProgressStyle {
+    ProgressStyle::default_bar()
+        .template("{spinner:.green} [{bar:40.cyan/blue}] {bytes}/{total_bytes} {bytes_per_sec} ({eta})")
+        .unwrap()
+        .progress_chars("#>-")
+}
+
+/// Name, category and tags for one file: its sidecar wins over the command line
+fn metadata_for(file: &Path, options: &UploadOptions) -> Result<AssetUploadMetadata> {
+    let sidecar = Sidecar::for_file(file)?.unwrap_or_default();
+    let stem = file
         .file_stem()
         .and_then(|s| s.to_str())
         .unwrap_or("upload")
         .to_string();
 
-    let asset_name = name.unwrap_or_else(|| file_name.clone());
+    let mut tags = options.tags.clone();
+    tags.extend(sidecar.tags.into_iter().filter(|tag| !options.tags.contains(tag)));
+
+    Ok(AssetUploadMetadata {
+        name: sidecar.name.or_else(|| options.name.clone()).unwrap_or(stem),
+        category: sidecar.category.or_else(|| options.category.clone()),
+        tags,
+        sha256: None,
+        mesh: None,
+    })
+}
+
+/// Upload one file, in parts if it is large or `resume` is set.
+///
+/// Returns whether the chunked protocol was used along with the result.
+pub(crate) async fn upload_file(
+    api: &impl ZereApi,
+    file: &Path,
+    metadata: AssetUploadMetadata,
+    resume: bool,
+    pb: &ProgressBar,
+) -> (bool, Result<Asset>) {
+    let size = match tokio::fs::metadata(file).await {
+        Ok(meta) => meta.len(),
+        Err(e) => return (false, Err(e.into())),
+    };
+    pb.set_length(size);
+
+    let bar = pb.clone();
+    let progress: ProgressCallback = Arc::new(move |sent| bar.set_position(sent));
+
+    // Large files go up in parts so a dropped connection does not restart the whole transfer
+    let chunked = resume || size >= CHUNKED_UPLOAD_THRESHOLD;
+    let result = if chunked {
+        let options = ChunkedUploadOptions {
+            resume,
+            progress: Some(progress),
+            ..ChunkedUploadOptions::default()
+        };
+        uploads::upload_chunked(api, file, metadata, options).await
+    } else {
+        api.upload_asset(file, metadata, Some(progress)).await
+    };
+    (chunked, result)
+}
+
+fn print_resume_hint() {
+    eprintln!(
+        "{} Run the same command with --resume to continue where interrupted uploads stopped",
+        style("ℹ").blue()
+    );
+}
+
+/// Print a file's validation result and any issues found
+pub(crate) fn print_report(report: &Report) {
+    let format = report.format.map(|f| format!(" ({})", f)).unwrap_or_default();
+    let (mark, path) = if !report.is_valid() {
+        (style("✗").red().bold(), style(report.path.display()).red())
+    } else if !report.issues.is_empty() {
+        (style("⚠").yellow().bold(), style(report.path.display()).yellow())
+    } else {
+        (style("✓").green().bold(), style(report.path.display()).white())
+    };
+    println!("  {} {}{}", mark, path, style(format).dim());
+
+    for issue in &report.issues {
+        match issue.severity {
+            Severity::Error => println!("      {} {}", style("✗").red(), issue.message),
+            Severity::Warning => println!("      {} {}", style("⚠").yellow(), issue.message),
+        }
+    }
+}
+
+pub async fn validate(paths: Vec<PathBuf>, include: Vec<String>, exclude: Vec<String>) -> Result<()> {
+    let files = collect_files(&paths, &include, &exclude)?;
+    if files.is_empty() {
+        return Err(CliError::Other("No files matched".to_string()));
+    }
+
+    println!("\n{}", style("Asset Validation").bold().underlined());
+    println!();
+
+    let mut invalid = 0;
+    for file in &files {
+        let report = validate::validate_file_async(file.clone()).await?;
+        if !report.is_valid() {
+            invalid += 1;
+        }
+        print_report(&report);
+    }
+
+    println!();
+    println!(
+        "  {} valid, {} invalid",
+        style(files.len() - invalid).green().bold(),
+        style(invalid).red().bold()
+    );
+
+    if invalid > 0 {
+        return Err(CliError::Other(format!("{} of {} files failed validation", invalid, files.len())));
+    }
+    Ok(())
+}
+
+fn yes_no(value: bool) -> StyledObject<&'static str> {
+    if value {
+        style("yes").green()
+    } else {
+        style("no").yellow()
+    }
+}
+
+fn print_mesh_stats(mesh: &MeshStats) {
+    let metres = |point: [f64; 3]| format!("({:.3}, {:.3}, {:.3})", point[0], point[1], point[2]);
+    let source = if mesh.from_spec { "from the specification" } else { "guessed" };
+
+    println!();
+    println!("  {} {}", style("Vertices:").dim(), mesh.vertices);
+    println!("  {} {}", style("Triangles:").dim(), mesh.triangles);
+    println!("  {} {}", style("Materials:").dim(), mesh.materials);
+    println!("  {} {}", style("Textures:").dim(), mesh.textures.len());
+    for texture in &mesh.textures {
+        let resolution = match (texture.width, texture.height) {
+            (Some(width), Some(height)) => style(format!("{}x{}", width, height)).white(),
+            _ => style("unknown size".to_string()).yellow(),
+        };
+        println!("      {}  {}", texture.path, resolution);
+    }
+    println!("  {} {}", style("Normals:").dim(), yes_no(mesh.has_normals));
+    println!("  {} {}", style("UVs:").dim(), yes_no(mesh.has_uvs));
+    if let Some(bounds) = &mesh.bounds {
+        let [x, y, z] = bounds.size();
+        println!("  {} {:.3} x {:.3} x {:.3} m", style("Size:").dim(), x, y, z);
+        println!("  {} {} to {} m", style("Bounds:").dim(), metres(bounds.min), metres(bounds.max));
+    }
+    println!("  {} {} ({})", style("Up axis:").dim(), mesh.up_axis.as_str(), source);
+    println!("  {} {} ({})", style("Units:").dim(), mesh.units.as_str(), source);
+}
+
+/// Report a mesh file's geometry, as text or as the JSON validation report.
+///
+/// Fails if the file has errors, since the statistics of a broken file cannot be trusted.
+pub async fn inspect(path: PathBuf, json: bool) -> Result<()> {
+    let report = validate::validate_file_async(path.clone()).await?;
+    if let (None, Some(format), true) = (&report.mesh, report.format, report.is_valid()) {
+        return Err(CliError::Other(format!(
+            "The geometry of {} files cannot be inspected",
+            format
+        )));
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("\n{}", style("Mesh Inspection").bold().underlined());
+        println!();
+        print_report(&report);
+        if let Some(mesh) = &report.mesh {
+            print_mesh_stats(mesh);
+        }
+        println!();
+    }
+
+    if report.mesh.is_none() || !report.is_valid() {
+        return Err(CliError::Other(format!("{} failed validation", path.display())));
+    }
+    Ok(())
+}
+
+/// What checking a file before upload found
+#[derive(Clone, Default)]
+struct Checked {
+    /// Why the file must not be uploaded, if it must not
+    rejection: Option<String>,
+    /// Statistics to attach with `--inspect`
+    mesh: Option<MeshStats>,
+}
+
+/// Validate files before upload, printing those with issues.
+///
+/// `--validate` and `--inspect` share one validation pass per file.
+async fn validate_before_upload(files: &[PathBuf], options: &UploadOptions) -> Result<Vec<Checked>> {
+    if !options.validate && !options.inspect {
+        return Ok(vec![Checked::default(); files.len()]);
+    }
+
+    let mut checked = Vec::with_capacity(files.len());
+    for file in files {
+        let report = validate::validate_file_async(file.clone()).await?;
+        if !options.validate {
+            checked.push(Checked {
+                rejection: None,
+                mesh: report.mesh,
+            });
+            continue;
+        }
+        if !report.issues.is_empty() {
+            print_report(&report);
+        }
+        let rejection = report
+            .errors()
+            .next()
+            .map(|issue| format!("Validation failed: {}", issue.message));
+        checked.push(Checked {
+            rejection,
+            mesh: report.mesh.filter(|_| options.inspect),
+        });
+    }
+    Ok(checked)
+}
+
+pub async fn upload(api: &impl ZereApi, paths: Vec<PathBuf>, options: UploadOptions) -> Result<()> {
+    let files = collect_files(&paths, &options.include, &options.exclude)?;
+    match files.as_slice() {
+        [] => Err(CliError::Other("No files matched".to_string())),
+        [file] => upload_single(api, file, &options).await,
+        _ if options.name.is_some() => Err(CliError::Other(
+            "--name can only be used when uploading a single file".to_string(),
+        )),
+        _ => upload_many(api, files, &options).await,
+    }
+}
+
+/// What happened to one file
+enum Outcome {
+    Uploaded { asset: Asset, replaced: Vec<String> },
+    /// The same content is already stored as this asset
+    Skipped(Asset),
+}
+
+/// Hash one file, skip it if the server already has it, otherwise upload it.
+///
+/// With `replace`, duplicates are uploaded anyway and the older copies deleted once
+/// the new asset exists, unless active jobs use them and `force` is off. A server that
+/// deduplicates may answer with one of those copies, which is then kept. `mesh` comes
+/// from [`validate_before_upload`]. Returns whether the chunked protocol was used along
+/// with the outcome.
+async fn process_file(
+    api: &impl ZereApi,
+    file: &Path,
+    mesh: Option<MeshStats>,
+    options: &UploadOptions,
+    pb: &ProgressBar,
+) -> (bool, Result<Outcome>) {
+    let mut chunked = false;
+    let result = async {
+        let mut metadata = metadata_for(file, options)?;
+        metadata.mesh = mesh;
+        let sha256 = file_sha256(file).await?;
+        let existing = api.find_assets_by_hash(&sha256).await?;
+        if !options.replace {
+            if let Some(asset) = existing.first() {
+                return Ok(Outcome::Skipped(asset.clone()));
+            }
+        }
+        let mut active = 0;
+        for old in &existing {
+            if api.asset_usage(&old.asset_id).await?.active_jobs().next().is_some() {
+                active += 1;
+            }
+        }
+        check_active_jobs(active, options.force)?;
+
+        metadata.sha256 = Some(sha256);
+        let (used_chunks, result) = upload_file(api, file, metadata, options.resume, pb).await;
+        chunked = used_chunks;
+        let asset = result?;
+
+        let mut replaced = Vec::new();
+        for old in existing.into_iter().filter(|old| old.asset_id != asset.asset_id) {
+            api.delete_asset(&old.asset_id).await?;
+            replaced.push(old.asset_id);
+        }
+        Ok(Outcome::Uploaded { asset, replaced })
+    }
+    .await;
+    (chunked, result)
+}
+
+async fn upload_single(api: &impl ZereApi, file_path: &Path, options: &UploadOptions) -> Result<()> {
+    let checked = validate_before_upload(&[file_path.to_path_buf()], options).await?.remove(0);
+    if let Some(reason) = checked.rejection {
+        return Err(CliError::Other(format!("{} (use --no-validate to upload anyway)", reason)));
+    }
 
     println!(
         "{} {}",
@@ -92,46 +630,529 @@ pub async fn upload(
         style(file_path.display()).white()
     );
 
-    let pb = ProgressBar::new_spinner();
-    pb.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.cyan} {msg}")
-            .unwrap(),
+    let pb = ProgressBar::new(0);
+    pb.set_style(bytes_style());
+    let (chunked, result) = process_file(api, file_path, checked.mesh, options, &pb).await;
+
+    pb.finish_and_clear();
+    if chunked && result.is_err() {
+        print_resume_hint();
+    }
+
+    match result? {
+        Outcome::Uploaded { asset, replaced } => {
+            println!(
+                "{} Asset uploaded: {} ({})",
+                style("✓").green().bold(),
+                style(&asset.name).white().bold(),
+                style(&asset.asset_id).cyan()
+            );
+            if !replaced.is_empty() {
+                println!("  {} {}", style("Replaced:").dim(), replaced.join(", "));
+            }
+        }
+        Outcome::Skipped(existing) => {
+            println!(
+                "{} Skipped: identical to {} ({}). Use {} to upload it again.",
+                style("ℹ").blue(),
+                style(&existing.asset_id).cyan(),
+                existing.name,
+                style("--replace").white().bold()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn upload_many(api: &impl ZereApi, files: Vec<PathBuf>, options: &UploadOptions) -> Result<()> {
+    println!(
+        "{} {} files ({} at a time)",
+        style("Uploading").cyan(),
+        files.len(),
+        options.concurrency.max(1)
+    );
+
+    let checked = validate_before_upload(&files, options).await?;
+
+    let multi = MultiProgress::new();
+    let overall = multi.add(ProgressBar::new(files.len() as u64));
+    overall.set_style(
+        ProgressStyle::default_bar()
+            .template("{spinner:.green} Files [{bar:40.green}] {pos}/{len} ({elapsed})")
+            .unwrap()
+            .progress_chars("#>-"),
     );
-    pb.set_message("Uploading...");
-    pb.enable_steady_tick(std::time::Duration::from_millis(100));
 
-    let asset = client
-        .upload_asset(&file_path, asset_name.clone(), category, tags)
-        .await?;
+    let mut results: Vec<(usize, bool, Result<Outcome>)> = stream::iter(files.iter().enumerate())
+        .map(|(i, file)| {
+            let multi = &multi;
+            let overall = &overall;
+            let Checked { rejection, mesh } = checked[i].clone();
+            async move {
+                if let Some(reason) = rejection {
+                    overall.inc(1);
+                    return (i, false, Err(CliError::Other(reason)));
+                }
 
-    pb.finish_and_clear();
+                let pb = multi.insert_before(overall, ProgressBar::new(0));
+                pb.set_style(
+                    ProgressStyle::default_bar()
+                        .template("  {prefix:24!} [{bar:30.cyan/blue}] {bytes}/{total_bytes} {bytes_per_sec}")
+                        .unwrap()
+                        .progress_chars("#>-"),
+                );
+                pb.set_prefix(file.file_name().unwrap_or_default().to_string_lossy().into_owned());
+                let (chunked, result) = process_file(api, file, mesh, options, &pb).await;
+
+                pb.finish_and_clear();
+                multi.remove(&pb);
+                overall.inc(1);
+                (i, chunked, result)
+            }
+        })
+        .buffer_unordered(options.concurrency.max(1))
+        .collect()
+        .await;
+    overall.finish_and_clear();
+    results.sort_by_key(|(i, _, _)| *i);
 
+    let failed = results.iter().filter(|(_, _, result)| result.is_err()).count();
+    let skipped = results
+        .iter()
+        .filter(|(_, _, result)| matches!(result, Ok(Outcome::Skipped(_))))
+        .count();
+    let width = files.iter().map(|f| f.display().to_string().len()).max().unwrap_or(0);
+
+    println!("\n{}", style("Upload Summary").bold().underlined());
+    println!();
+    for (i, _, result) in &results {
+        let file = format!("{:<width$}", files[*i].display(), width = width);
+        match result {
+            Ok(Outcome::Uploaded { asset, replaced }) if replaced.is_empty() => println!(
+                "  {} {}  {}",
+                style("✓").green().bold(),
+                file,
+                style(&asset.asset_id).cyan()
+            ),
+            Ok(Outcome::Uploaded { asset, replaced }) => println!(
+                "  {} {}  {} {}",
+                style("✓").green().bold(),
+                file,
+                style(&asset.asset_id).cyan(),
+                style(format!("(replaced {})", replaced.join(", "))).dim()
+            ),
+            Ok(Outcome::Skipped(existing)) => println!(
+                "  {} {}  {}",
+                style("=").yellow().bold(),
+                file,
+                style(format!("skipped, duplicate of {}", existing.asset_id)).yellow()
+            ),
+            Err(e) => println!("  {} {}  {}", style("✗").red().bold(), file, style(e).red()),
+        }
+    }
+    println!();
     println!(
-        "{} Asset uploaded: {} ({})",
-        style("✓").green().bold(),
-        style(&asset.name).white().bold(),
-        style(&asset.asset_id).cyan()
+        "  {} uploaded, {} skipped as duplicates, {} failed",
+        style(results.len() - failed - skipped).green().bold(),
+        style(skipped).yellow().bold(),
+        style(failed).red().bold()
     );
 
-    Ok(())
+    if failed == 0 {
+        return Ok(());
+    }
+    if results.iter().any(|(_, chunked, result)| *chunked && result.is_err()) {
+        print_resume_hint();
+    }
+    Err(CliError::Other(format!("{} of {} uploads failed", failed, results.len())))
+}
+
+/// Which assets `zere assets download` fetches, and where to
+pub struct DownloadOptions {
+    /// Assets to download; when empty, every asset matching `query`
+    pub asset_ids: Vec<String>,
+    pub query: AssetQuery,
+    pub output_dir: PathBuf,
+}
+
+/// What happened to one download
+enum DownloadOutcome {
+    Downloaded(PathBuf),
+    /// The local file already matches the asset's hash
+    UpToDate(PathBuf),
+}
+
+/// File name for an asset: its name plus the file type, unless the name already has it
+fn local_file_name(asset: &Asset, suffix: Option<&str>) -> String {
+    let mut stem: String = asset
+        .name
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    if stem.is_empty() || stem.starts_with('.') {
+        stem = format!("{}{}", asset.asset_id, stem);
+    }
+
+    let ext = asset.file_type.to_ascii_lowercase();
+    if !ext.is_empty() && stem.to_ascii_lowercase().ends_with(&format!(".{}", ext)) {
+        stem.truncate(stem.len() - ext.len() - 1);
+    }
+    if let Some(suffix) = suffix {
+        stem = format!("{}-{}", stem, suffix);
+    }
+    if ext.is_empty() {
+        stem
+    } else {
+        format!("{}.{}", stem, ext)
+    }
+}
+
+/// Local file names for a batch; assets that would collide get their ID appended
+fn local_file_names(assets: &[Asset]) -> Vec<String> {
+    let names: Vec<String> = assets.iter().map(|asset| local_file_name(asset, None)).collect();
+    assets
+        .iter()
+        .zip(&names)
+        .map(|(asset, name)| {
+            let clashes = names.iter().filter(|other| other.eq_ignore_ascii_case(name)).count() > 1;
+            if clashes {
+                local_file_name(asset, Some(&asset.asset_id))
+            } else {
+                name.clone()
+            }
+        })
+        .collect()
+}
+
+/// Whether `path` already holds the asset's file; only assets with a known hash can match
+async fn is_up_to_date(asset: &Asset, path: &Path) -> Result<bool> {
+    let Some(sha256) = &asset.sha256 else {
+        return Ok(false);
+    };
+    match tokio::fs::metadata(path).await {
+        Ok(metadata) if metadata.len() == asset.size_bytes as u64 => Ok(file_sha256(path).await? == *sha256),
+        _ => Ok(false),
+    }
+}
+
+async fn download_one(api: &impl ZereApi, asset: &Asset, path: PathBuf, pb: &ProgressBar) -> Result<DownloadOutcome> {
+    if is_up_to_date(asset, &path).await? {
+        return Ok(DownloadOutcome::UpToDate(path));
+    }
+
+    pb.set_length(asset.size_bytes.max(0) as u64);
+    pb.set_position(0);
+    let bar = pb.clone();
+    let progress: ProgressCallback = Arc::new(move |written| bar.set_position(written));
+    api.download_asset(&asset.asset_id, &path, Some(progress)).await?;
+    Ok(DownloadOutcome::Downloaded(path))
+}
+
+pub async fn download(api: &impl ZereApi, options: DownloadOptions) -> Result<()> {
+    let mut assets = Vec::new();
+    if options.asset_ids.is_empty() {
+        assets = matching_assets(api, &options.query, DEFAULT_PAGE_SIZE).await?;
+    } else {
+        for asset_id in &options.asset_ids {
+            assets.push(api.get_asset(asset_id).await?);
+        }
+    }
+
+    if assets.is_empty() {
+        println!("{}", style("No assets match").yellow());
+        return Ok(());
+    }
+
+    tokio::fs::create_dir_all(&options.output_dir).await?;
+    let paths: Vec<PathBuf> = local_file_names(&assets)
+        .into_iter()
+        .map(|name| options.output_dir.join(name))
+        .collect();
+
+    println!(
+        "{} {} asset{} to {}",
+        style("Downloading").cyan(),
+        assets.len(),
+        if assets.len() == 1 { "" } else { "s" },
+        style(options.output_dir.display()).white()
+    );
+
+    let mut results = Vec::new();
+    for (asset, path) in assets.iter().zip(paths) {
+        let pb = ProgressBar::new(0);
+        pb.set_style(bytes_style());
+        let result = download_one(api, asset, path, &pb).await;
+        pb.finish_and_clear();
+        results.push(result);
+    }
+
+    let width = assets.iter().map(|asset| asset.asset_id.len()).max().unwrap_or(0);
+    println!();
+    for (asset, result) in assets.iter().zip(&results) {
+        let id = format!("{:<width$}", asset.asset_id, width = width);
+        match result {
+            Ok(DownloadOutcome::Downloaded(path)) => {
+                println!("  {} {}  {}", style("✓").green().bold(), style(id).cyan(), path.display())
+            }
+            Ok(DownloadOutcome::UpToDate(path)) => println!(
+                "  {} {}  {} {}",
+                style("=").yellow().bold(),
+                style(id).cyan(),
+                path.display(),
+                style("(up to date)").dim()
+            ),
+            Err(e) => println!("  {} {}  {}", style("✗").red().bold(), style(id).cyan(), style(e).red()),
+        }
+    }
+
+    let failed = results.iter().filter(|result| result.is_err()).count();
+    let up_to_date = results
+        .iter()
+        .filter(|result| matches!(result, Ok(DownloadOutcome::UpToDate(_))))
+        .count();
+    if results.len() > 1 {
+        println!();
+        println!(
+            "  {} downloaded, {} up to date, {} failed",
+            style(results.len() - failed - up_to_date).green().bold(),
+            style(up_to_date).yellow().bold(),
+            style(failed).red().bold()
+        );
+    }
+
+    if failed == 0 {
+        return Ok(());
+    }
+    if results.len() == 1 {
+        return results.into_iter().next().unwrap().map(|_| ());
+    }
+    Err(CliError::Other(format!("{} of {} downloads failed", failed, results.len())))
+}
+
+/// List the jobs and configs that reference an asset
+pub(super) fn print_usage(usage: &AssetUsage) {
+    if !usage.jobs.is_empty() {
+        let width = usage.jobs.iter().map(|job| job.job_id.len()).max().unwrap_or(0);
+        println!("  {}", style("Jobs").bold());
+        for job in &usage.jobs {
+            println!(
+                "    {:<width$}  {:<9}  {}",
+                style(&job.job_id).cyan(),
+                super::jobs::styled_status(&job.status),
+                job.name,
+                width = width
+            );
+        }
+    }
+    if !usage.configs.is_empty() {
+        let width = usage.configs.iter().map(|config| config.config_id.len()).max().unwrap_or(0);
+        println!("  {}", style("Configs").bold());
+        for config in &usage.configs {
+            println!("    {:<width$}  {}", style(&config.config_id).cyan(), config.name, width = width);
+        }
+    }
 }
 
-pub async fn delete(asset_id: String, force: bool) -> Result<()> {
-    let config = Config::load()?;
-    let client = ApiClient::from_config(&config)?;
+pub async fn usage(api: &impl ZereApi, asset_id: String) -> Result<()> {
+    let asset = api.get_asset(&asset_id).await?;
+    let usage = api.asset_usage(&asset_id).await?;
+
+    println!(
+        "\n{}",
+        style(format!("Usage of {} ({})", asset.name, asset.asset_id)).bold().underlined()
+    );
+    println!();
+    if usage.is_empty() {
+        println!("  {}", style("Not referenced by any job or config").dim());
+    } else {
+        print_usage(&usage);
+    }
+    Ok(())
+}
 
-    // Get asset info first
-    let asset = client.get_asset(&asset_id).await?;
+/// Options for `zere assets delete`
+#[derive(Clone)]
+pub struct DeleteOptions {
+    /// Assets to delete; when empty, every asset matching `query` is deleted
+    pub asset_ids: Vec<String>,
+    pub query: AssetQuery,
+    /// Show what would be deleted without deleting it
+    pub dry_run: bool,
+    /// Skip the confirmation prompt
+    pub yes: bool,
+    /// Also delete assets that queued, running or paused jobs use
+    pub force: bool,
+}
 
+/// Refuse to delete `active` assets that queued, running or paused jobs use.
+///
+/// With `force` the deletion goes ahead with a warning that the jobs may fail.
+pub(super) fn check_active_jobs(active: usize, force: bool) -> Result<()> {
+    if active == 0 {
+        return Ok(());
+    }
+    let (in_use, them) = if active == 1 {
+        ("1 asset is".to_string(), "it")
+    } else {
+        (format!("{} assets are", active), "them")
+    };
     if !force {
-        let confirm = inquire::Confirm::new(&format!(
-            "Delete asset '{}' ({})?",
-            asset.name, asset.asset_id
-        ))
-        .with_default(false)
-        .prompt()
-        .unwrap_or(false);
+        return Err(CliError::Other(format!(
+            "{} used by active jobs; wait for or cancel the jobs, or pass --force to delete {} anyway",
+            in_use, them
+        )));
+    }
+    eprintln!(
+        "{} {} used by active jobs, which may fail",
+        style("⚠ WARNING:").red().bold(),
+        style(in_use).red().bold()
+    );
+    Ok(())
+}
+
+/// Summary of what references an asset, for one table cell
+fn usage_cell(usage: &AssetUsage) -> StyledObject<String> {
+    let plural = |n: usize| if n == 1 { "" } else { "s" };
+    let active = usage.active_jobs().count();
+    if active > 0 {
+        style(format!("{} active job{}", active, plural(active))).red().bold()
+    } else if !usage.is_empty() {
+        let (jobs, configs) = (usage.jobs.len(), usage.configs.len());
+        style(format!("{} job{}, {} config{}", jobs, plural(jobs), configs, plural(configs))).yellow()
+    } else {
+        style("-".to_string()).dim()
+    }
+}
+
+/// Print the assets about to be deleted as a table, with their total size
+fn print_delete_table(assets: &[(Asset, AssetUsage)]) {
+    let width = |header: &str, cell: fn(&Asset) -> usize| {
+        assets.iter().map(|(asset, _)| cell(asset)).max().unwrap_or(0).max(header.len())
+    };
+    let id_width = width("ID", |asset| asset.asset_id.len());
+    let name_width = width("Name", |asset| asset.name.chars().count());
+    let type_width = width("Type", |asset| asset.file_type.len());
+    let created_width = width("Created", |asset| asset.created_at.len());
+
+    let header = format!(
+        "{:<id_width$}  {:<name_width$}  {:<type_width$}  {:>10}  {:<created_width$}  Used by",
+        "ID", "Name", "Type", "Size", "Created"
+    );
+    println!("  {}", style(header).dim());
+    for (asset, usage) in assets {
+        println!(
+            "  {:<id_width$}  {:<name_width$}  {:<type_width$}  {:>7.2} MB  {:<created_width$}  {}",
+            style(&asset.asset_id).cyan(),
+            asset.name,
+            asset.file_type,
+            asset.size_bytes as f64 / 1_048_576.0,
+            asset.created_at,
+            usage_cell(usage)
+        );
+    }
+
+    let total: i64 = assets.iter().map(|(asset, _)| asset.size_bytes).sum();
+    println!();
+    println!(
+        "  {} asset{}, {:.2} MB in total",
+        style(assets.len()).bold(),
+        if assets.len() == 1 { "" } else { "s" },
+        total as f64 / 1_048_576.0
+    );
+}
+
+/// Delete assets by ID or by filter.
+///
+/// Shows what will be removed and asks once before deleting them concurrently. Assets that
+/// queued, running or paused jobs still use are only deleted with `force`. IDs that cannot be
+/// looked up are reported as failures without stopping the rest.
+pub async fn delete(api: &impl ZereApi, options: DeleteOptions) -> Result<()> {
+    let mut unknown: Vec<(String, CliError)> = Vec::new();
+    let assets = if options.asset_ids.is_empty() {
+        matching_assets(api, &options.query, DEFAULT_PAGE_SIZE).await?
+    } else {
+        let mut assets = Vec::new();
+        for asset_id in &options.asset_ids {
+            match api.get_asset(asset_id).await {
+                Ok(asset) => assets.push(asset),
+                Err(e) => unknown.push((asset_id.clone(), e)),
+            }
+        }
+        assets
+    };
+    if assets.is_empty() && unknown.is_empty() {
+        println!("{}", style("No assets match").yellow());
+        return Ok(());
+    }
+
+    let assets: Vec<(Asset, AssetUsage)> = stream::iter(assets)
+        .map(|asset| async move {
+            let usage = api.asset_usage(&asset.asset_id).await?;
+            Ok::<_, CliError>((asset, usage))
+        })
+        .buffered(DELETE_CONCURRENCY)
+        .try_collect()
+        .await?;
+
+    let width = assets
+        .iter()
+        .map(|(asset, _)| asset.asset_id.as_str())
+        .chain(unknown.iter().map(|(asset_id, _)| asset_id.as_str()))
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    let print_unknown = || {
+        for (asset_id, e) in &unknown {
+            let id = format!("{:<width$}", asset_id, width = width);
+            println!("  {} {}  {}", style("✗").red().bold(), style(id).cyan(), style(e).red());
+        }
+    };
+
+    if !assets.is_empty() {
+        println!("\n{}", style("Assets to delete").bold().underlined());
+        println!();
+        print_delete_table(&assets);
+        for (asset, usage) in assets.iter().filter(|(_, usage)| !usage.is_empty()) {
+            println!();
+            println!("{} is referenced by:", style(&asset.name).white().bold());
+            print_usage(usage);
+        }
+    }
+    println!();
+
+    let total = assets.len() + unknown.len();
+    if options.dry_run || assets.is_empty() {
+        print_unknown();
+        if !unknown.is_empty() {
+            println!();
+        }
+        if options.dry_run {
+            println!("{}", style("Dry run: nothing was deleted").yellow());
+        }
+        if unknown.is_empty() {
+            return Ok(());
+        }
+        return Err(CliError::Other(format!("{} of {} assets could not be found", unknown.len(), total)));
+    }
+
+    let active = assets.iter().filter(|(_, usage)| usage.active_jobs().next().is_some()).count();
+    check_active_jobs(active, options.force)?;
+
+    if !options.yes {
+        let prompt = match assets.as_slice() {
+            [(asset, _)] => format!("Delete asset '{}' ({})?", asset.name, asset.asset_id),
+            _ => format!("Delete these {} assets?", assets.len()),
+        };
+        let confirm = inquire::Confirm::new(&prompt)
+            .with_default(false)
+            .prompt()
+            .unwrap_or(false);
 
         if !confirm {
             println!("{}", style("Cancelled").yellow());
@@ -139,13 +1160,311 @@ pub async fn delete(asset_id: String, force: bool) -> Result<()> {
         }
     }
 
-    client.delete_asset(&asset_id).await?;
+    let results: Vec<Result<()>> = stream::iter(&assets)
+        .map(|(asset, _)| api.delete_asset(&asset.asset_id))
+        .buffered(DELETE_CONCURRENCY)
+        .collect()
+        .await;
+
+    if let ([result], []) = (results.as_slice(), unknown.as_slice()) {
+        if result.is_ok() {
+            println!(
+                "{} Asset deleted: {}",
+                style("✓").green().bold(),
+                style(&assets[0].0.name).white()
+            );
+        }
+        return results.into_iter().next().unwrap();
+    }
+
+    for ((asset, _), result) in assets.iter().zip(&results) {
+        let id = format!("{:<width$}", asset.asset_id, width = width);
+        match result {
+            Ok(()) => println!("  {} {}  {}", style("✓").green().bold(), style(id).cyan(), asset.name),
+            Err(e) => println!(
+                "  {} {}  {}  {}",
+                style("✗").red().bold(),
+                style(id).cyan(),
+                asset.name,
+                style(e).red()
+            ),
+        }
+    }
+    print_unknown();
 
+    let failed = results.iter().filter(|result| result.is_err()).count() + unknown.len();
+    println!();
     println!(
-        "{} Asset deleted: {}",
-        style("✓").green().bold(),
-        style(&asset.name).white()
+        "  {} deleted, {} failed",
+        style(total - failed).green().bold(),
+        style(failed).red().bold()
     );
-
+    if failed > 0 {
+        return Err(CliError::Other(format!("{} of {} deletions failed", failed, total)));
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::assets::{AssetJobRef, AssetSort};
+    use crate::api::fake::FakeApi;
+    use crate::api::jobs::JobStatus;
+
+    #[test]
+    fn test_page_hint_repeats_list_filters() {
+        let query = AssetQuery {
+            tag: Some("oak wood".to_string()),
+            min_size: Some(1024),
+            sort: Some(AssetSort::Size),
+            descending: true,
+            ..AssetQuery::default()
+        }
+        .name_glob("chair*")
+        .unwrap();
+        assert_eq!(
+            crate::commands::command_line("assets list", &list_args(&query)),
+            "zere assets list --tag 'oak wood' --name 'chair*' --min-size 1024 --sort size --desc"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bulk_upload_reports_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        for file in ["a.glb", "b.glb", "c.glb"] {
+            std::fs::write(dir.path().join(file), file).unwrap();
+        }
+        std::fs::write(dir.path().join("b.glb.zere.toml"), "name = \"Bravo\"\ntags = [\"oak\"]\n").unwrap();
+
+        let api = FakeApi::new();
+        // Each file is a hash lookup then an upload; fail the upload of c.glb
+        api.fail_nth(6, CliError::Other("connection reset".to_string()));
+        let options = UploadOptions {
+            tags: vec!["sku".to_string()],
+            validate: false,
+            concurrency: 1,
+            ..UploadOptions::default()
+        };
+
+        let err = upload(&api, vec![dir.path().to_path_buf()], options).await.unwrap_err();
+        assert_eq!(err.to_string(), "1 of 3 uploads failed");
+
+        let names: Vec<String> = api.assets().into_iter().map(|asset| asset.name).collect();
+        assert_eq!(names, ["a", "Bravo"]);
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_upload_is_skipped_or_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("chair.glb");
+        std::fs::write(&file, b"mesh").unwrap();
+
+        let api = FakeApi::new();
+        let mut options = UploadOptions {
+            validate: false,
+            concurrency: 1,
+            ..UploadOptions::default()
+        };
+
+        upload(&api, vec![file.clone()], options.clone()).await.unwrap();
+        upload(&api, vec![file.clone()], options.clone()).await.unwrap();
+        let first = api.assets();
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].sha256.as_deref(), Some(file_sha256(&file).await.unwrap().as_str()));
+
+        options.replace = true;
+        upload(&api, vec![file.clone()], options.clone()).await.unwrap();
+        let replaced = api.assets();
+        assert_eq!(replaced.len(), 1);
+        assert_ne!(replaced[0].asset_id, first[0].asset_id);
+
+        // Replacing an asset that an active job uses needs --force
+        let api = FakeApi::new().with_assets(replaced.clone()).with_usage(
+            &replaced[0].asset_id,
+            AssetUsage {
+                jobs: vec![AssetJobRef {
+                    job_id: "job-1".to_string(),
+                    name: "Warehouse".to_string(),
+                    status: JobStatus::Running,
+                }],
+                configs: Vec::new(),
+            },
+        );
+        let err = upload(&api, vec![file.clone()], options.clone()).await.unwrap_err();
+        assert!(err.to_string().contains("1 asset is used by active jobs"), "{}", err);
+        assert_eq!(api.assets()[0].asset_id, replaced[0].asset_id);
+
+        options.force = true;
+        upload(&api, vec![file], options).await.unwrap();
+        assert_ne!(api.assets()[0].asset_id, replaced[0].asset_id);
+    }
+
+    #[tokio::test]
+    async fn test_replace_keeps_asset_the_server_deduplicated_to() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("chair.glb");
+        std::fs::write(&file, b"mesh").unwrap();
+        let existing = Asset {
+            sha256: Some(file_sha256(&file).await.unwrap()),
+            ..crate::api::fake::asset("asset-1", "chair")
+        };
+        let api = FakeApi::new().with_dedup().with_assets(vec![existing]);
+
+        let options = UploadOptions {
+            validate: false,
+            concurrency: 1,
+            replace: true,
+            ..UploadOptions::default()
+        };
+        upload(&api, vec![file], options).await.unwrap();
+
+        let ids: Vec<String> = api.assets().into_iter().map(|asset| asset.asset_id).collect();
+        assert_eq!(ids, ["asset-1"]);
+        assert!(!api.calls().iter().any(|call| call.starts_with("delete_asset")), "{:?}", api.calls());
+    }
+
+    #[tokio::test]
+    async fn test_update_edits_tags_in_place() {
+        let api = FakeApi::new().with_assets(vec![Asset {
+            tags: vec!["oak".to_string(), "draft".to_string()],
+            ..crate::api::fake::asset("asset-1", "Chair")
+        }]);
+
+        let options = UpdateOptions {
+            name: None,
+            category: Some("furniture".to_string()),
+            add_tags: vec!["sku-1042".to_string(), "oak".to_string()],
+            remove_tags: vec!["draft".to_string()],
+        };
+        update(&api, "asset-1".to_string(), options).await.unwrap();
+
+        let asset = &api.assets()[0];
+        assert_eq!(asset.name, "Chair");
+        assert_eq!(asset.category.as_deref(), Some("furniture"));
+        assert_eq!(asset.tags, ["oak", "sku-1042"]);
+    }
+
+    #[tokio::test]
+    async fn test_delete_refuses_while_active_jobs_use_asset() {
+        let job = |job_id: &str, status| AssetJobRef {
+            job_id: job_id.to_string(),
+            name: "Warehouse".to_string(),
+            status,
+        };
+        let usage = AssetUsage {
+            jobs: vec![job("job-1", JobStatus::Completed), job("job-2", JobStatus::Paused)],
+            configs: Vec::new(),
+        };
+        let api = FakeApi::new()
+            .with_assets(vec![crate::api::fake::asset("asset-1", "Chair")])
+            .with_usage("asset-1", usage);
+
+        let options = |dry_run, force| DeleteOptions {
+            asset_ids: vec!["asset-1".to_string()],
+            query: AssetQuery::default(),
+            dry_run,
+            yes: true,
+            force,
+        };
+        delete(&api, options(true, false)).await.unwrap();
+        let err = delete(&api, options(false, false)).await.unwrap_err();
+        assert!(err.to_string().contains("1 asset is used by active jobs"), "{}", err);
+        assert_eq!(api.assets().len(), 1);
+
+        delete(&api, options(false, true)).await.unwrap();
+        assert!(api.assets().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delete_reports_unknown_ids_and_deletes_the_rest() {
+        let api = FakeApi::new().with_assets(vec![
+            crate::api::fake::asset("asset-1", "Chair"),
+            crate::api::fake::asset("asset-2", "Table"),
+        ]);
+        let mut options = DeleteOptions {
+            asset_ids: vec!["asset-1".to_string(), "asset-9".to_string()],
+            query: AssetQuery::default(),
+            dry_run: true,
+            yes: true,
+            force: false,
+        };
+
+        let err = delete(&api, options.clone()).await.unwrap_err();
+        assert_eq!(err.to_string(), "1 of 2 assets could not be found");
+        assert_eq!(api.assets().len(), 2);
+
+        options.dry_run = false;
+        let err = delete(&api, options).await.unwrap_err();
+        assert_eq!(err.to_string(), "1 of 2 deletions failed");
+        let left: Vec<String> = api.assets().into_iter().map(|asset| asset.asset_id).collect();
+        assert_eq!(left, ["asset-2"]);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_delete_by_filter() {
+        let tagged = |asset_id: &str, tag: &str| Asset {
+            tags: vec![tag.to_string()],
+            ..crate::api::fake::asset(asset_id, asset_id)
+        };
+        let api = FakeApi::new().with_assets(vec![
+            tagged("asset-1", "test"),
+            tagged("asset-2", "keep"),
+            tagged("asset-3", "test"),
+            tagged("asset-4", "test"),
+        ]);
+        let mut options = DeleteOptions {
+            asset_ids: Vec::new(),
+            query: AssetQuery {
+                tag: Some("test".to_string()),
+                ..AssetQuery::default()
+            },
+            dry_run: true,
+            yes: true,
+            force: false,
+        };
+
+        delete(&api, options.clone()).await.unwrap();
+        assert_eq!(api.assets().len(), 4);
+
+        // One page of search, three usage lookups, then the deletions
+        api.fail_nth(6, CliError::Other("connection reset".to_string()));
+        options.dry_run = false;
+        let err = delete(&api, options).await.unwrap_err();
+        assert_eq!(err.to_string(), "1 of 3 deletions failed");
+        let left: Vec<String> = api.assets().into_iter().map(|asset| asset.asset_id).collect();
+        assert_eq!(left, ["asset-2", "asset-3"]);
+    }
+
+    #[tokio::test]
+    async fn test_download_by_category_names_files_uniquely() {
+        let dir = tempfile::tempdir().unwrap();
+        let chair = |asset_id: &str, name: &str| Asset {
+            category: Some("furniture".to_string()),
+            ..crate::api::fake::asset(asset_id, name)
+        };
+        let api = FakeApi::new().with_assets(vec![
+            chair("asset-1", "Chair"),
+            chair("asset-2", "chair.GLB"),
+            chair("asset-3", "Oak: Table"),
+            crate::api::fake::asset("asset-4", "Lamp"),
+        ]);
+
+        let options = DownloadOptions {
+            asset_ids: Vec::new(),
+            query: AssetQuery {
+                category: Some("furniture".to_string()),
+                ..AssetQuery::default()
+            },
+            output_dir: dir.path().to_path_buf(),
+        };
+        download(&api, options).await.unwrap();
+
+        let mut files: Vec<String> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        files.sort();
+        assert_eq!(files, ["Chair-asset-1.glb", "Oak_ Table.glb", "chair-asset-2.glb"]);
+    }
+}
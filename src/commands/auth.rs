@@ -1,18 +1,23 @@
 use crate::api::ApiClient;
-use crate::config::Config;
-use crate::error::Result;
+use crate::config::{AuthConfig, Config, Overrides};
+use crate::error::{CliError, Result};
 use console::style;
 
-pub async fn login(email: Option<String>, password: Option<String>, api_key: Option<String>) -> Result<()> {
+pub async fn login(
+    email: Option<String>,
+    password: Option<String>,
+    api_key: Option<String>,
+    overrides: &Overrides,
+) -> Result<()> {
     let mut config = Config::load()?;
 
     // Check environment variable for API key first
     let api_key = api_key.or_else(|| std::env::var("ZERE_API_KEY").ok());
 
-    let token = if let Some(key) = api_key {
+    if let Some(key) = api_key {
         // Use provided API key directly
         println!("{}", style("Using API key authentication...").cyan());
-        key
+        config.set_api_key(Some(key));
     } else {
         // Security warning if password provided via flag
         if password.is_some() {
@@ -22,35 +27,67 @@ pub async fn login(email: Option<String>, password: Option<String>, api_key: Opt
             eprintln!();
         }
 
-        // Prompt for email/password if not provided
-        let email = email.or_else(|| {
-            inquire::Text::new("Email:")
-                .prompt()
-                .ok()
-        }).ok_or_else(|| crate::error::CliError::Auth("Email required".to_string()))?;
-
-        let password = password.or_else(|| {
-            inquire::Password::new("Password:")
-                .without_confirmation()
-                .prompt()
-                .ok()
-        }).ok_or_else(|| crate::error::CliError::Auth("Password required".to_string()))?;
+        let (email, password) = prompt_credentials(email, password)?;
 
         // Login via API
         println!("\n{}", style("Logging in...").cyan());
-        let client = ApiClient::new(config.api.endpoint.clone(), None)?;
-        client.login(email, password).await?
-    };
+        let client = ApiClient::builder(&config).overrides(overrides.clone()).unauthenticated().build()?;
+        let session = client.login(email, password).await?;
+        config.set_session(session);
+    }
 
-    // Save API key to config
-    config.set_api_key(Some(token));
+    // Save credentials to config
     config.save()?;
 
     println!("{}", style("✓ Logged in successfully").green().bold());
     Ok(())
 }
 
-pub async fn logout() -> Result<()> {
+/// Prompt for whichever of email and password were not provided
+fn prompt_credentials(email: Option<String>, password: Option<String>) -> Result<(String, String)> {
+    let email = email.or_else(|| {
+        inquire::Text::new("Email:")
+            .prompt()
+            .ok()
+    }).ok_or_else(|| CliError::Auth {
+        message: "Email required".to_string(),
+        error: None,
+    })?;
+
+    let password = password.or_else(|| {
+        inquire::Password::new("Password:")
+            .without_confirmation()
+            .prompt()
+            .ok()
+    }).ok_or_else(|| CliError::Auth {
+        message: "Password required".to_string(),
+        error: None,
+    })?;
+
+    Ok((email, password))
+}
+
+/// Offer to log in again after the session expired mid-command, returning the new session
+pub async fn relogin(overrides: Overrides) -> Result<AuthConfig> {
+    eprintln!("{}", style("Your session has expired.").yellow());
+    let again = inquire::Confirm::new("Log in again now?")
+        .with_default(true)
+        .prompt()
+        .unwrap_or(false);
+    if !again {
+        return Err(CliError::SessionExpired);
+    }
+
+    let (email, password) = prompt_credentials(None, None)?;
+    ApiClient::builder(&Config::load()?)
+        .overrides(overrides)
+        .unauthenticated()
+        .build()?
+        .login(email, password)
+        .await
+}
+
+pub async fn logout(overrides: &Overrides) -> Result<()> {
     let mut config = Config::load()?;
 
     if !config.is_authenticated() {
@@ -58,8 +95,13 @@ pub async fn logout() -> Result<()> {
         return Ok(());
     }
 
-    // Try to logout on server (best effort)
-    if let Ok(client) = ApiClient::from_config(&config) {
+    // Try to logout on server (best effort). A refresh on the way rotates the refresh
+    // token, so it is saved like any other command's until the session is cleared below.
+    let client = ApiClient::builder(&config)
+        .overrides(overrides.clone())
+        .persist_credentials(true)
+        .build();
+    if let Ok(client) = client {
         let _ = client.logout().await;
     }
 
@@ -71,7 +113,7 @@ pub async fn logout() -> Result<()> {
     Ok(())
 }
 
-pub async fn whoami() -> Result<()> {
+pub async fn whoami(client: &ApiClient) -> Result<()> {
     let config = Config::load()?;
 
     if !config.is_authenticated() {
@@ -79,7 +121,6 @@ pub async fn whoami() -> Result<()> {
         return Ok(());
     }
 
-    let client = ApiClient::from_config(&config)?;
     let user = client.whoami().await?;
 
     println!("\n{}", style("User Information").bold().underlined());
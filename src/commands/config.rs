@@ -31,6 +31,26 @@ pub async fn show() -> Result<()> {
         }
     );
 
+    if let Some(proxy) = config.proxy.https.as_ref().or(config.proxy.http.as_ref()) {
+        println!("  {} {}", style("Proxy:").dim(), proxy);
+    }
+
+    if !config.tls.ca_certs.is_empty() {
+        println!("  {} {}", style("Extra CA Certs:").dim(), config.tls.ca_certs.len());
+    }
+
+    if let Some(cert) = &config.tls.client_cert {
+        println!("  {} {}", style("Client Cert:").dim(), cert.display());
+    }
+
+    if config.tls.insecure_skip_verify {
+        println!(
+            "  {} {}",
+            style("TLS Verify:").dim(),
+            style("DISABLED (insecure_skip_verify)").red().bold()
+        );
+    }
+
     if let Ok(path) = Config::config_path() {
         println!("  {} {}", style("Config File:").dim(), path.display());
     }
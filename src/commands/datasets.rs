@@ -1,23 +1,30 @@
-use crate::api::ApiClient;
-use crate::config::Config;
+use crate::api::datasets::Dataset;
+use crate::api::pagination::PageRequest;
+use crate::api::{ProgressCallback, ZereApi};
 use crate::error::Result;
 use console::style;
+use futures::TryStreamExt;
 use indicatif::{ProgressBar, ProgressStyle};
 use std::path::PathBuf;
-
-pub async fn list() -> Result<()> {
-    let config = Config::load()?;
-    let client = ApiClient::from_config(&config)?;
-
-    let datasets = client.list_datasets().await?;
+use std::sync::Arc;
+
+pub async fn list(api: &impl ZereApi, limit: u32, page: u32, all: bool) -> Result<()> {
+    let (datasets, offset, total) = if all {
+        let datasets: Vec<Dataset> = api.datasets_stream(limit).try_collect().await?;
+        let total = datasets.len() as i32;
+        (datasets, 0, total)
+    } else {
+        let page = api.list_datasets_page(PageRequest::page(limit, page)).await?;
+        (page.items, page.offset, page.total)
+    };
 
     if datasets.is_empty() {
         println!("{}", style("No datasets found").yellow());
         return Ok(());
     }
 
-    println!("\n{}", style(format!("Datasets ({})", datasets.len())).bold().underlined());
-    println!();
+    let shown = datasets.len();
+    super::print_list_heading("Datasets", shown, offset, total);
 
     for dataset in datasets {
         let size_gb = dataset.size_bytes as f64 / 1_073_741_824.0;
@@ -38,14 +45,13 @@ pub async fn list() -> Result<()> {
         println!();
     }
 
+    super::print_page_hint("datasets list", &[], shown, offset, limit, total);
+
     Ok(())
 }
 
-pub async fn info(dataset_id: String) -> Result<()> {
-    let config = Config::load()?;
-    let client = ApiClient::from_config(&config)?;
-
-    let dataset = client.get_dataset(&dataset_id).await?;
+pub async fn info(api: &impl ZereApi, dataset_id: String) -> Result<()> {
+    let dataset = api.get_dataset(&dataset_id).await?;
 
     println!("\n{}", style("Dataset Details").bold().underlined());
     println!("  {} {}", style("Job ID:").dim(), dataset.job_id);
@@ -58,13 +64,10 @@ pub async fn info(dataset_id: String) -> Result<()> {
     Ok(())
 }
 
-pub async fn download(job_id: String, output_path: Option<PathBuf>) -> Result<()> {
-    let config = Config::load()?;
-    let client = ApiClient::from_config(&config)?;
-
+pub async fn download(api: &impl ZereApi, job_id: String, output_path: Option<PathBuf>) -> Result<()> {
     println!("{} Generating download URL...", style("→").cyan());
 
-    let download_info = client.get_download_url(&job_id).await?;
+    let download_info = api.get_download_url(&job_id).await?;
     let size_mb = download_info.size_bytes as f64 / 1_048_576.0;
 
     println!(
@@ -89,8 +92,9 @@ pub async fn download(job_id: String, output_path: Option<PathBuf>) -> Result<()
             .progress_chars("#>-"),
     );
 
-    // Download (progress tracking would require streaming implementation)
-    client.download_dataset(&download_info.download_url, &output).await?;
+    let progress_bar = pb.clone();
+    let progress: ProgressCallback = Arc::new(move |written| progress_bar.set_position(written));
+    api.download_dataset(&download_info.download_url, &output, Some(progress)).await?;
 
     pb.finish_with_message(style("Downloaded!").green().to_string());
 
@@ -1,32 +1,45 @@
-use crate::api::ApiClient;
-use crate::config::Config;
+use crate::api::idempotency::{self, PendingKeys};
+use crate::api::jobs::{Job, JobStatus};
+use crate::api::pagination::PageRequest;
+use crate::api::ZereApi;
 use crate::error::Result;
-use console::style;
+use console::{style, StyledObject};
+use futures::TryStreamExt;
 use indicatif::{ProgressBar, ProgressStyle};
 use std::path::PathBuf;
 use std::time::Duration;
 
-pub async fn list() -> Result<()> {
-    let config = Config::load()?;
-    let client = ApiClient::from_config(&config)?;
+/// A job status coloured by how it ended, or yellow while the job is still active
+pub(crate) fn styled_status(status: &JobStatus) -> StyledObject<&JobStatus> {
+    match status {
+        JobStatus::Completed => style(status).green(),
+        JobStatus::Failed => style(status).red(),
+        JobStatus::Running => style(status).cyan(),
+        JobStatus::Cancelled | JobStatus::Unknown(_) => style(status).dim(),
+        _ => style(status).yellow(),
+    }
+}
 
-    let jobs = client.list_jobs().await?;
+pub async fn list(api: &impl ZereApi, limit: u32, page: u32, all: bool) -> Result<()> {
+    let (jobs, offset, total) = if all {
+        let jobs: Vec<Job> = api.jobs_stream(limit).try_collect().await?;
+        let total = jobs.len() as i32;
+        (jobs, 0, total)
+    } else {
+        let page = api.list_jobs_page(PageRequest::page(limit, page)).await?;
+        (page.items, page.offset, page.total)
+    };
 
     if jobs.is_empty() {
         println!("{}", style("No jobs found").yellow());
         return Ok(());
     }
 
-    println!("\n{}", style(format!("Jobs ({})", jobs.len())).bold().underlined());
-    println!();
+    let shown = jobs.len();
+    super::print_list_heading("Jobs", shown, offset, total);
 
     for job in jobs {
-        let status_style = match job.status.as_str() {
-            "completed" => style(&job.status).green(),
-            "failed" => style(&job.status).red(),
-            "running" => style(&job.status).cyan(),
-            _ => style(&job.status).yellow(),
-        };
+        let status_style = styled_status(&job.status);
 
         println!(
             "  {} {}",
@@ -57,14 +70,13 @@ pub async fn list() -> Result<()> {
         println!();
     }
 
+    super::print_page_hint("jobs list", &[], shown, offset, limit, total);
+
     Ok(())
 }
 
-pub async fn status(job_id: String) -> Result<()> {
-    let config = Config::load()?;
-    let client = ApiClient::from_config(&config)?;
-
-    let job = client.get_job(&job_id).await?;
+pub async fn status(api: &impl ZereApi, job_id: String) -> Result<()> {
+    let job = api.get_job(&job_id).await?;
 
     println!("\n{}", style("Job Details").bold().underlined());
     println!("  {} {}", style("ID:").dim(), job.job_id);
@@ -87,13 +99,12 @@ pub async fn status(job_id: String) -> Result<()> {
 }
 
 pub async fn create(
+    api: &impl ZereApi,
     name: String,
     num_scenes: i32,
     config_file: Option<PathBuf>,
+    idempotency_key: Option<String>,
 ) -> Result<()> {
-    let config = Config::load()?;
-    let client = ApiClient::from_config(&config)?;
-
     let config_yaml = if let Some(path) = config_file {
         Some(tokio::fs::read_to_string(path).await?)
     } else {
@@ -102,7 +113,7 @@ pub async fn create(
 
     println!("{} Creating job '{}'...", style("→").cyan(), style(&name).white().bold());
 
-    let job = client.create_job(name, num_scenes, config_yaml).await?;
+    let job = create_once(api, name, num_scenes, config_yaml, idempotency_key).await?;
 
     println!(
         "{} Job created: {} ({})",
@@ -116,10 +127,7 @@ pub async fn create(
     Ok(())
 }
 
-pub async fn watch(job_id: String) -> Result<()> {
-    let config = Config::load()?;
-    let client = ApiClient::from_config(&config)?;
-
+pub async fn watch(api: &impl ZereApi, job_id: String) -> Result<()> {
     println!("{} Watching job {}...\n", style("→").cyan(), style(&job_id).cyan().bold());
 
     let pb = ProgressBar::new(100);
@@ -131,7 +139,7 @@ pub async fn watch(job_id: String) -> Result<()> {
     );
 
     loop {
-        match client.get_job_progress(&job_id).await {
+        match api.get_job_progress(&job_id).await {
             Ok(progress) => {
                 pb.set_position(progress.progress_percent as u64);
 
@@ -142,13 +150,21 @@ pub async fn watch(job_id: String) -> Result<()> {
                 };
                 pb.set_message(msg);
 
-                if progress.status == "completed" {
-                    pb.finish_with_message(style("Completed!").green().to_string());
-                    println!("\n{} Job completed successfully", style("✓").green().bold());
-                    break;
-                } else if progress.status == "failed" {
-                    pb.finish_with_message(style("Failed").red().to_string());
-                    println!("\n{} Job failed", style("✗").red().bold());
+                if progress.status.is_terminal() {
+                    match progress.status {
+                        JobStatus::Completed => {
+                            pb.finish_with_message(style("Completed!").green().to_string());
+                            println!("\n{} Job completed successfully", style("✓").green().bold());
+                        }
+                        JobStatus::Failed => {
+                            pb.finish_with_message(style("Failed").red().to_string());
+                            println!("\n{} Job failed", style("✗").red().bold());
+                        }
+                        status => {
+                            pb.finish_with_message(style(&status).yellow().to_string());
+                            println!("\n{} Job was {}", style("ℹ").blue().bold(), status);
+                        }
+                    }
                     break;
                 }
             }
@@ -164,10 +180,7 @@ pub async fn watch(job_id: String) -> Result<()> {
     Ok(())
 }
 
-pub async fn cancel(job_id: String, force: bool) -> Result<()> {
-    let config = Config::load()?;
-    let client = ApiClient::from_config(&config)?;
-
+pub async fn cancel(api: &impl ZereApi, job_id: String, force: bool) -> Result<()> {
     if !force {
         let confirm = inquire::Confirm::new(&format!("Cancel job '{}'?", job_id))
             .with_default(false)
@@ -180,7 +193,7 @@ pub async fn cancel(job_id: String, force: bool) -> Result<()> {
         }
     }
 
-    client.cancel_job(&job_id).await?;
+    api.cancel_job(&job_id).await?;
 
     println!(
         "{} Job cancelled: {}",
@@ -191,15 +204,59 @@ pub async fn cancel(job_id: String, force: bool) -> Result<()> {
     Ok(())
 }
 
+/// Create a job so that rerunning the command after a dropped connection cannot start a duplicate.
+///
+/// Without an explicit key, the key generated for these parameters is kept on disk until
+/// the job is created or the server rejects it, and reused by the next identical attempt.
+async fn create_once(
+    api: &impl ZereApi,
+    name: String,
+    num_scenes: i32,
+    config_yaml: Option<String>,
+    idempotency_key: Option<String>,
+) -> Result<Job> {
+    if idempotency_key.is_some() {
+        return api.create_job(name, num_scenes, config_yaml, idempotency_key).await;
+    }
+
+    let fingerprint = idempotency::fingerprint(&[
+        "create_job",
+        &name,
+        &num_scenes.to_string(),
+        config_yaml.as_deref().unwrap_or_default(),
+    ]);
+    let mut pending = PendingKeys::load()?;
+    let (key, reused) = pending.key_for(&fingerprint)?;
+    if reused {
+        println!(
+            "{} Resuming an earlier attempt that did not finish; no duplicate job will be started",
+            style("ℹ").blue()
+        );
+    }
+
+    match api.create_job(name, num_scenes, config_yaml, Some(key)).await {
+        Ok(job) => {
+            pending.complete(&fingerprint)?;
+            Ok(job)
+        }
+        Err(e) => {
+            if !idempotency::outcome_unknown(&e) {
+                // Best effort: the rejection is what the user needs to see
+                let _ = pending.complete(&fingerprint);
+            }
+            Err(e)
+        }
+    }
+}
+
 /// Quick Start Mode - Generate with procedural objects (no assets needed)
 pub async fn quick_start(
+    api: &impl ZereApi,
     num_scenes: i32,
     objects_range: Option<String>,
     environment: Option<String>,
+    idempotency_key: Option<String>,
 ) -> Result<()> {
-    let config = Config::load()?;
-    let client = ApiClient::from_config(&config)?;
-
     // Parse object range (e.g., "20-30")
     let (min_objects, max_objects) = if let Some(range) = objects_range {
         let parts: Vec<&str> = range.split('-').collect();
@@ -233,7 +290,7 @@ pub async fn quick_start(
     println!("{} Creating quick start job...", style("→").cyan());
 
     let job_name = format!("Quick Start - {} scenes", num_scenes);
-    let job = client.create_job(job_name.clone(), num_scenes, Some(config_yaml)).await?;
+    let job = create_once(api, job_name, num_scenes, Some(config_yaml), idempotency_key).await?;
 
     println!(
         "{} Job created: {} ({})",
@@ -305,3 +362,47 @@ lighting:
         environment
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::fake::{self, FakeApi};
+
+    #[tokio::test(start_paused = true)]
+    async fn test_watch_stops_at_terminal_status() {
+        let api = FakeApi::new().with_jobs(vec![fake::job("job-1", JobStatus::Queued)]);
+        api.script_progress(
+            "job-1",
+            [
+                fake::progress(JobStatus::Running, 10.0),
+                fake::progress(JobStatus::Paused, 40.0),
+                fake::progress(JobStatus::Cancelled, 40.0),
+            ],
+        );
+
+        watch(&api, "job-1".to_string()).await.unwrap();
+
+        let polls = api.calls().iter().filter(|c| c.starts_with("get_job_progress")).count();
+        assert_eq!(polls, 3);
+    }
+
+    #[tokio::test]
+    async fn test_forced_cancel() {
+        let api = FakeApi::new().with_jobs(vec![fake::job("job-1", JobStatus::Running)]);
+
+        cancel(&api, "job-1".to_string(), true).await.unwrap();
+
+        assert_eq!(api.jobs()[0].status, JobStatus::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_list_all_walks_every_page() {
+        let jobs = (0..7).map(|i| fake::job(&format!("job-{}", i), JobStatus::Completed)).collect();
+        let api = FakeApi::new().with_jobs(jobs);
+
+        list(&api, 3, 1, true).await.unwrap();
+
+        let pages = api.calls().iter().filter(|c| c.starts_with("list_jobs_page")).count();
+        assert_eq!(pages, 3);
+    }
+}
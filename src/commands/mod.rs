@@ -1,5 +1,64 @@
+pub mod asset_files;
+pub mod asset_sync;
 pub mod auth;
 pub mod assets;
 pub mod config;
 pub mod datasets;
 pub mod jobs;
+
+use crate::api::pagination::DEFAULT_PAGE_SIZE;
+use console::style;
+
+/// Print the heading of a list command, e.g. "Jobs (51-100 of 2345)"
+pub(crate) fn print_list_heading(title: &str, shown: usize, offset: u32, total: i32) {
+    let heading = if shown as i64 == i64::from(total) {
+        format!("{} ({})", title, shown)
+    } else {
+        format!("{} ({}-{} of {})", title, offset as usize + 1, offset as usize + shown, total)
+    };
+    println!("\n{}", style(heading).bold().underlined());
+    println!();
+}
+
+/// Tell the user how to see the rest of a partially shown list.
+///
+/// `args` are the filter flags the list was run with, so the suggested commands continue
+/// the same listing.
+pub(crate) fn print_page_hint(command: &str, args: &[String], shown: usize, offset: u32, limit: u32, total: i32) {
+    let seen = offset as i64 + shown as i64;
+    if seen < i64::from(total) {
+        let command = command_line(command, args);
+        let next_page = offset / limit.max(1) + 2;
+        let next = if limit == DEFAULT_PAGE_SIZE {
+            format!("{} --page {}", command, next_page)
+        } else {
+            format!("{} --limit {} --page {}", command, limit, next_page)
+        };
+        println!(
+            "{} Showing {} of {}. Use {} or {} to see more.",
+            style("ℹ").blue(),
+            shown,
+            total,
+            style(next).white().bold(),
+            style(format!("{} --all", command)).white().bold()
+        );
+    }
+}
+
+/// `zere <command> <args>`, with arguments quoted where a shell would split or expand them
+pub(crate) fn command_line(command: &str, args: &[String]) -> String {
+    let mut line = format!("zere {}", command);
+    for arg in args {
+        let plain = !arg.is_empty()
+            && arg
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c));
+        if plain {
+            line.push(' ');
+            line.push_str(arg);
+        } else {
+            line.push_str(&format!(" '{}'", arg.replace('\'', "'\\''")));
+        }
+    }
+    line
+}
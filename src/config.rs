@@ -1,8 +1,10 @@
 use crate::error::{CliError, Result};
+use crate::time::unix_now;
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -10,6 +12,14 @@ pub struct Config {
     pub api: ApiConfig,
     #[serde(default)]
     pub auth: AuthConfig,
+    #[serde(default)]
+    pub retry: RetryConfig,
+    #[serde(default)]
+    pub proxy: ProxyConfig,
+    #[serde(default)]
+    pub tls: TlsConfig,
+    #[serde(default)]
+    pub timeouts: TimeoutsConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,7 +29,173 @@ pub struct ApiConfig {
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AuthConfig {
+    /// Access token (or long-lived API key) sent as the bearer token
     pub api_key: Option<String>,
+    /// Refresh token used to obtain a new access token when it expires
+    pub refresh_token: Option<String>,
+    /// Access token expiry as a Unix timestamp in seconds
+    pub expires_at: Option<i64>,
+}
+
+impl AuthConfig {
+    /// Whether the access token expires within `secs` seconds (false if the expiry is unknown)
+    pub fn expires_within(&self, secs: i64) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at - unix_now() <= secs)
+    }
+
+    /// Whether the session can be renewed without asking for credentials
+    pub fn can_refresh(&self) -> bool {
+        self.refresh_token.is_some()
+    }
+}
+
+/// Retry behaviour for transient API failures (failed connections, timeouts, 429, 502-504)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    /// Total attempts per request, including the first one (1 disables retries)
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubles on every further attempt
+    pub initial_backoff_ms: u64,
+    /// Upper bound for the computed backoff delay
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            max_attempts: 3,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 10_000,
+        }
+    }
+}
+
+/// HTTP(S) proxy settings. When unset, the standard `HTTP_PROXY`/`HTTPS_PROXY`/`NO_PROXY`
+/// environment variables apply.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ProxyConfig {
+    /// Proxy for plain HTTP requests, e.g. `http://proxy.corp:3128`
+    pub http: Option<String>,
+    /// Proxy for HTTPS requests
+    pub https: Option<String>,
+    /// Hosts, domains (`.corp.example`) or CIDR ranges that bypass the proxy
+    pub no_proxy: Vec<String>,
+}
+
+impl ProxyConfig {
+    pub fn is_configured(&self) -> bool {
+        self.http.is_some() || self.https.is_some()
+    }
+}
+
+/// TLS settings for on-prem deployments
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct TlsConfig {
+    /// Extra root certificates (PEM bundle or DER) trusted in addition to the system store
+    pub ca_certs: Vec<PathBuf>,
+    /// Client identity for mTLS: a PKCS#12 archive (`.p12`/`.pfx`) or a PEM certificate chain
+    pub client_cert: Option<PathBuf>,
+    /// PKCS#8 PEM private key, if not bundled in `client_cert`
+    pub client_key: Option<PathBuf>,
+    /// Password of the PKCS#12 archive
+    pub client_cert_password: Option<String>,
+    /// Skip server certificate verification. Only for local development!
+    pub insecure_skip_verify: bool,
+}
+
+/// Class of API operation, each with its own timeouts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationClass {
+    /// Small JSON calls: listing, fetching and creating resources
+    Metadata,
+    /// Asset uploads
+    Upload,
+    /// Dataset and file downloads
+    Download,
+    /// Progress polling while watching jobs
+    Poll,
+}
+
+/// Timeout overrides for one operation class, in seconds (0 disables the timeout)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct TimeoutConfig {
+    /// Time allowed to establish a connection
+    pub connect_secs: Option<u64>,
+    /// Time allowed without receiving any data (or, for uploads, sending any) before the transfer is considered stalled
+    pub read_idle_secs: Option<u64>,
+    /// Time allowed for the whole request, including the body
+    pub total_secs: Option<u64>,
+}
+
+/// Per-operation-class timeouts, e.g. `[timeouts.upload]`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct TimeoutsConfig {
+    pub metadata: TimeoutConfig,
+    pub upload: TimeoutConfig,
+    pub download: TimeoutConfig,
+    pub poll: TimeoutConfig,
+}
+
+/// Resolved timeouts for one operation class; `None` means no limit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Timeouts {
+    pub connect: Option<Duration>,
+    pub read_idle: Option<Duration>,
+    pub total: Option<Duration>,
+}
+
+impl TimeoutsConfig {
+    /// Timeouts for an operation class: `overrides` win over the config file, which wins over defaults.
+    ///
+    /// Downloads have no total limit by default, so large files only fail when they stall;
+    /// uploads are capped at six hours as a backstop. The total timeout override only
+    /// applies to metadata and poll requests for the same reason; a transfer limit can
+    /// still be set per class in the config file.
+    pub fn resolve(&self, class: OperationClass, overrides: &Overrides) -> Timeouts {
+        // (connect, read idle, total) defaults in seconds
+        let (configured, defaults) = match class {
+            OperationClass::Metadata => (&self.metadata, (10, 30, 60)),
+            OperationClass::Upload => (&self.upload, (10, 120, 6 * 60 * 60)),
+            OperationClass::Download => (&self.download, (10, 120, 0)),
+            OperationClass::Poll => (&self.poll, (10, 90, 120)),
+        };
+        let pick = |flag: Option<u64>, file: Option<u64>, default: u64| {
+            match flag.or(file).unwrap_or(default) {
+                0 => None,
+                secs => Some(Duration::from_secs(secs)),
+            }
+        };
+
+        let total_override = match class {
+            OperationClass::Metadata | OperationClass::Poll => overrides.total_timeout_secs,
+            OperationClass::Upload | OperationClass::Download => None,
+        };
+
+        Timeouts {
+            connect: pick(overrides.connect_timeout_secs, configured.connect_secs, defaults.0),
+            read_idle: pick(overrides.read_timeout_secs, configured.read_idle_secs, defaults.1),
+            total: pick(total_override, configured.total_secs, defaults.2),
+        }
+    }
+}
+
+/// Per-invocation settings, e.g. from global command-line flags.
+///
+/// These take precedence over the config file but are never written back to it.
+/// Pass them to [`ApiClientBuilder::overrides`](crate::api::client::ApiClientBuilder::overrides).
+#[derive(Debug, Clone, Default)]
+pub struct Overrides {
+    /// Retries after the first attempt (0 disables retries)
+    pub retries: Option<u32>,
+    pub connect_timeout_secs: Option<u64>,
+    pub read_timeout_secs: Option<u64>,
+    pub total_timeout_secs: Option<u64>,
 }
 
 fn default_api_endpoint() -> ApiConfig {
@@ -33,6 +209,10 @@ impl Default for Config {
         Config {
             api: default_api_endpoint(),
             auth: AuthConfig::default(),
+            retry: RetryConfig::default(),
+            proxy: ProxyConfig::default(),
+            tls: TlsConfig::default(),
+            timeouts: TimeoutsConfig::default(),
         }
     }
 }
@@ -52,15 +232,18 @@ impl Config {
 
     /// Load config from file, or create default if it doesn't exist
     pub fn load() -> Result<Self> {
-        let path = Self::config_path()?;
+        Self::load_from(&Self::config_path()?)
+    }
 
+    /// Load config from a specific file, or create default there if it doesn't exist
+    pub fn load_from(path: &Path) -> Result<Self> {
         if !path.exists() {
             let config = Config::default();
-            config.save()?;
+            config.save_to(path)?;
             return Ok(config);
         }
 
-        let contents = fs::read_to_string(&path)
+        let contents = fs::read_to_string(path)
             .map_err(|e| CliError::Config(format!("Failed to read config file: {}", e)))?;
 
         toml::from_str(&contents)
@@ -69,15 +252,20 @@ impl Config {
 
     /// Save config to file
     pub fn save(&self) -> Result<()> {
-        let dir = Self::config_dir()?;
-        fs::create_dir_all(&dir)
-            .map_err(|e| CliError::Config(format!("Failed to create config directory: {}", e)))?;
+        self.save_to(&Self::config_path()?)
+    }
+
+    /// Save config to a specific file
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| CliError::Config(format!("Failed to create config directory: {}", e)))?;
+        }
 
-        let path = Self::config_path()?;
         let contents = toml::to_string_pretty(self)
             .map_err(|e| CliError::Config(format!("Failed to serialize config: {}", e)))?;
 
-        fs::write(&path, contents)
+        fs::write(path, contents)
             .map_err(|e| CliError::Config(format!("Failed to write config file: {}", e)))?;
 
         Ok(())
@@ -88,9 +276,26 @@ impl Config {
         self.api.endpoint = endpoint;
     }
 
-    /// Set API key
+    /// Set API key, dropping any session tokens from a previous login
     pub fn set_api_key(&mut self, api_key: Option<String>) {
-        self.auth.api_key = api_key;
+        self.auth = AuthConfig {
+            api_key,
+            ..AuthConfig::default()
+        };
+    }
+
+    /// Store the tokens of a login or refreshed session
+    pub fn set_session(&mut self, auth: AuthConfig) {
+        self.auth = auth;
+    }
+
+    /// Retry settings with `overrides` applied
+    pub fn effective_retry(&self, overrides: &Overrides) -> RetryConfig {
+        let mut retry = self.retry.clone();
+        if let Some(retries) = overrides.retries {
+            retry.max_attempts = retries.saturating_add(1);
+        }
+        retry
     }
 
     /// Check if user is authenticated
@@ -125,4 +330,106 @@ mod tests {
         let parsed: Config = toml::from_str(&toml_str).unwrap();
         assert_eq!(parsed.api.endpoint, config.api.endpoint);
     }
+
+    #[test]
+    fn test_config_without_retry_section() {
+        let parsed: Config = toml::from_str("[api]\nendpoint = \"http://localhost:8001\"\n").unwrap();
+        assert_eq!(parsed.retry.max_attempts, 3);
+        assert_eq!(parsed.retry.initial_backoff_ms, 500);
+    }
+
+    #[test]
+    fn test_retries_override_counts_retries_after_the_first_attempt() {
+        let config = Config::default();
+        let retries = |retries| Overrides {
+            retries,
+            ..Overrides::default()
+        };
+        assert_eq!(config.effective_retry(&retries(None)).max_attempts, 3);
+        assert_eq!(config.effective_retry(&retries(Some(0))).max_attempts, 1);
+        assert_eq!(config.effective_retry(&retries(Some(5))).max_attempts, 6);
+    }
+
+    #[test]
+    fn test_network_sections() {
+        let parsed: Config = toml::from_str(
+            r#"
+            [proxy]
+            https = "http://proxy.corp:3128"
+            no_proxy = ["localhost", ".corp.example"]
+
+            [tls]
+            ca_certs = ["/etc/ssl/corp-ca.pem"]
+            client_cert = "/etc/zere/client.p12"
+            "#,
+        )
+        .unwrap();
+
+        assert!(parsed.proxy.is_configured());
+        assert_eq!(parsed.proxy.no_proxy.len(), 2);
+        assert_eq!(parsed.tls.ca_certs.len(), 1);
+        assert!(!parsed.tls.insecure_skip_verify);
+    }
+
+    #[test]
+    fn test_timeouts_per_operation_class() {
+        let parsed: Config = toml::from_str(
+            r#"
+            [timeouts.upload]
+            read_idle_secs = 600
+
+            [timeouts.metadata]
+            total_secs = 0
+            "#,
+        )
+        .unwrap();
+
+        let upload = parsed.timeouts.resolve(OperationClass::Upload, &Overrides::default());
+        assert_eq!(upload.read_idle, Some(Duration::from_secs(600)));
+        assert_eq!(upload.connect, Some(Duration::from_secs(10)));
+        assert_eq!(upload.total, Some(Duration::from_secs(6 * 60 * 60)));
+
+        let metadata = parsed.timeouts.resolve(OperationClass::Metadata, &Overrides::default());
+        assert_eq!(metadata.total, None);
+        assert_eq!(metadata.read_idle, Some(Duration::from_secs(30)));
+
+        let overrides = Overrides {
+            read_timeout_secs: Some(5),
+            total_timeout_secs: Some(20),
+            ..Overrides::default()
+        };
+        let upload = parsed.timeouts.resolve(OperationClass::Upload, &overrides);
+        assert_eq!(upload.read_idle, Some(Duration::from_secs(5)));
+        // --timeout would cut off large transfers that are still making progress
+        assert_eq!(upload.total, Some(Duration::from_secs(6 * 60 * 60)));
+        let metadata = parsed.timeouts.resolve(OperationClass::Metadata, &overrides);
+        assert_eq!(metadata.total, Some(Duration::from_secs(20)));
+    }
+
+    #[test]
+    fn test_token_expiry() {
+        let mut auth = AuthConfig {
+            api_key: Some("token".to_string()),
+            refresh_token: Some("refresh".to_string()),
+            expires_at: Some(unix_now() + 20),
+        };
+        assert!(auth.expires_within(60));
+        assert!(!auth.expires_within(10));
+
+        auth.expires_at = None;
+        assert!(!auth.expires_within(60));
+    }
+
+    #[test]
+    fn test_set_api_key_clears_session() {
+        let mut config = Config::default();
+        config.set_session(AuthConfig {
+            api_key: Some("token".to_string()),
+            refresh_token: Some("refresh".to_string()),
+            expires_at: Some(1),
+        });
+        config.set_api_key(Some("key".to_string()));
+        assert!(config.auth.refresh_token.is_none());
+        assert!(config.auth.expires_at.is_none());
+    }
 }
@@ -1,3 +1,6 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fmt;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -5,8 +8,34 @@ pub enum CliError {
     #[error("API error: {0}")]
     Api(String),
 
-    #[error("Authentication error: {0}")]
-    Auth(String),
+    #[error("API error ({}): {}", .0.status, .0.message)]
+    Server(ApiError),
+
+    #[error("Validation failed: {}", .0.message)]
+    Validation(ApiError),
+
+    #[error("Rate limited: {}", .error.message)]
+    RateLimited {
+        error: ApiError,
+        retry_after_secs: Option<u64>,
+    },
+
+    #[error("Conflict: {}", .0.message)]
+    Conflict(ApiError),
+
+    #[error("{resource} not found: {id}")]
+    NotFound {
+        resource: String,
+        id: String,
+        request_id: Option<String>,
+    },
+
+    /// Rejected credentials or permissions, with the server's details when it sent the rejection
+    #[error("Authentication error: {message}")]
+    Auth {
+        message: String,
+        error: Option<Box<ApiError>>,
+    },
 
     #[error("Configuration error: {0}")]
     Config(String),
@@ -23,6 +52,9 @@ pub enum CliError {
     #[error("Not authenticated. Please run 'zere login' first")]
     NotAuthenticated,
 
+    #[error("Session expired. Please run 'zere login' again")]
+    SessionExpired,
+
     #[error("Invalid response from server")]
     InvalidResponse,
 
@@ -30,6 +62,214 @@ pub enum CliError {
     Other(String),
 }
 
+/// Structured error returned by the backend for a non-2xx response
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiError {
+    pub status: u16,
+    pub code: Option<String>,
+    pub message: String,
+    pub field_errors: Vec<FieldError>,
+    pub request_id: Option<String>,
+}
+
+/// A validation error attached to a single request field
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+impl ApiError {
+    /// Decode an error body from the backend.
+    ///
+    /// Understands both the `{"error": {"code", "message", "fields", "request_id"}}`
+    /// envelope and FastAPI-style `{"detail": ...}` bodies. Anything else is kept
+    /// verbatim as the message.
+    pub fn from_body(status: u16, body: &str, request_id: Option<String>) -> Self {
+        let mut error = ApiError {
+            status,
+            code: None,
+            message: String::new(),
+            field_errors: Vec::new(),
+            request_id,
+        };
+
+        match serde_json::from_str::<Value>(body) {
+            Ok(value) => error.fill_from_json(&value),
+            Err(_) => error.message = body.trim().to_string(),
+        }
+
+        if error.message.is_empty() {
+            error.message = match error.field_errors.first() {
+                Some(_) => "Request contains invalid fields".to_string(),
+                None => format!("Request failed with status {}", status),
+            };
+        }
+
+        error
+    }
+
+    fn fill_from_json(&mut self, value: &Value) {
+        let obj = match value.get("error") {
+            Some(inner) if inner.is_object() => inner,
+            Some(Value::String(message)) => {
+                self.message = message.clone();
+                value
+            }
+            _ => value,
+        };
+
+        if let Some(code) = obj.get("code").and_then(value_as_string) {
+            self.code = Some(code);
+        }
+        if let Some(message) = obj.get("message").and_then(Value::as_str) {
+            self.message = message.to_string();
+        }
+        if self.request_id.is_none() {
+            self.request_id = obj.get("request_id").and_then(value_as_string);
+        }
+
+        for key in ["fields", "field_errors", "errors"] {
+            if let Some(fields) = obj.get(key) {
+                self.field_errors.extend(parse_field_errors(fields));
+            }
+        }
+
+        match obj.get("detail") {
+            Some(Value::String(detail)) if self.message.is_empty() => {
+                self.message = detail.clone();
+            }
+            Some(detail @ Value::Array(_)) => self.field_errors.extend(parse_field_errors(detail)),
+            _ => {}
+        }
+    }
+}
+
+/// Field errors come either as `[{"field"|"loc", "message"|"msg"}]` or `{"field": "message"}`
+fn parse_field_errors(value: &Value) -> Vec<FieldError> {
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|item| {
+                let message = item
+                    .get("message")
+                    .or_else(|| item.get("msg"))
+                    .and_then(Value::as_str)?;
+                let field = match (item.get("field"), item.get("loc")) {
+                    (Some(field), _) => value_as_string(field)?,
+                    (None, Some(Value::Array(loc))) => loc
+                        .iter()
+                        .filter_map(value_as_string)
+                        // FastAPI prefixes the location with where the field came from
+                        .filter(|part| !matches!(part.as_str(), "body" | "query" | "path"))
+                        .collect::<Vec<_>>()
+                        .join("."),
+                    _ => String::new(),
+                };
+                Some(FieldError {
+                    field,
+                    message: message.to_string(),
+                })
+            })
+            .collect(),
+        Value::Object(map) => map
+            .iter()
+            .filter_map(|(field, message)| {
+                let message = match message {
+                    Value::Array(messages) => messages
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .collect::<Vec<_>>()
+                        .join("; "),
+                    other => value_as_string(other)?,
+                };
+                Some(FieldError {
+                    field: field.clone(),
+                    message,
+                })
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn value_as_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+impl CliError {
+    /// Structured server error details, if this error came from an API response
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            CliError::Server(error)
+            | CliError::Validation(error)
+            | CliError::Conflict(error)
+            | CliError::RateLimited { error, .. } => Some(error),
+            CliError::Auth { error, .. } => error.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Request ID assigned by the backend, for quoting in support tickets
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            CliError::NotFound { request_id, .. } => request_id.as_deref(),
+            _ => self.api_error().and_then(|e| e.request_id.as_deref()),
+        }
+    }
+
+    /// Short machine-readable name of the error kind
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CliError::Api(_) | CliError::Server(_) => "api",
+            CliError::Validation(_) => "validation",
+            CliError::RateLimited { .. } => "rate_limited",
+            CliError::Conflict(_) => "conflict",
+            CliError::NotFound { .. } => "not_found",
+            CliError::Auth { .. } | CliError::NotAuthenticated | CliError::SessionExpired => "auth",
+            CliError::Config(_) => "config",
+            CliError::Network(_) => "network",
+            CliError::Io(_) => "io",
+            CliError::Serialization(_) | CliError::InvalidResponse => "invalid_response",
+            CliError::Other(_) => "other",
+        }
+    }
+
+    /// JSON representation used by `--format json`
+    pub fn to_json(&self) -> Value {
+        let mut error = json!({
+            "kind": self.kind(),
+            "message": self.to_string(),
+        });
+
+        if let Some(api) = self.api_error() {
+            error["status"] = json!(api.status);
+            error["code"] = json!(api.code);
+            error["field_errors"] = json!(api.field_errors);
+        }
+        if let CliError::RateLimited { retry_after_secs, .. } = self {
+            error["retry_after_secs"] = json!(retry_after_secs);
+        }
+        if let CliError::NotFound { resource, id, .. } = self {
+            error["resource"] = json!(resource);
+            error["id"] = json!(id);
+        }
+        error["request_id"] = json!(self.request_id());
+
+        json!({ "error": error })
+    }
+}
+
 impl From<toml::de::Error> for CliError {
     fn from(err: toml::de::Error) -> Self {
         CliError::Serialization(err.to_string())
@@ -49,3 +289,65 @@ impl From<serde_json::Error> for CliError {
 }
 
 pub type Result<T> = std::result::Result<T, CliError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error_envelope() {
+        let body = r#"{"error": {"code": "invalid_scenes", "message": "Invalid job",
+            "fields": [{"field": "num_scenes", "message": "must be positive"}],
+            "request_id": "req-123"}}"#;
+        let error = ApiError::from_body(422, body, None);
+
+        assert_eq!(error.code.as_deref(), Some("invalid_scenes"));
+        assert_eq!(error.message, "Invalid job");
+        assert_eq!(error.field_errors.len(), 1);
+        assert_eq!(error.field_errors[0].field, "num_scenes");
+        assert_eq!(error.request_id.as_deref(), Some("req-123"));
+    }
+
+    #[test]
+    fn test_parse_fastapi_validation_detail() {
+        let body = r#"{"detail": [{"loc": ["body", "name"], "msg": "field required", "type": "value_error.missing"}]}"#;
+        let error = ApiError::from_body(422, body, Some("hdr-1".to_string()));
+
+        assert_eq!(error.field_errors[0].field, "name");
+        assert_eq!(error.field_errors[0].message, "field required");
+        assert_eq!(error.message, "Request contains invalid fields");
+        assert_eq!(error.request_id.as_deref(), Some("hdr-1"));
+    }
+
+    #[test]
+    fn test_parse_plain_text_body() {
+        let error = ApiError::from_body(500, "upstream exploded\n", None);
+        assert_eq!(error.message, "upstream exploded");
+        assert!(error.field_errors.is_empty());
+    }
+
+    #[test]
+    fn test_error_json_includes_request_id() {
+        let error = CliError::Conflict(ApiError::from_body(409, r#"{"detail": "Job already exists"}"#, Some("req-9".to_string())));
+        let value = error.to_json();
+
+        assert_eq!(value["error"]["kind"], "conflict");
+        assert_eq!(value["error"]["status"], 409);
+        assert_eq!(value["error"]["request_id"], "req-9");
+    }
+
+    #[test]
+    fn test_auth_error_keeps_server_details() {
+        let api = ApiError::from_body(403, r#"{"detail": "Not a member of this organization"}"#, Some("req-4".to_string()));
+        let error = CliError::Auth {
+            message: format!("Forbidden: {}", api.message),
+            error: Some(Box::new(api)),
+        };
+        let value = error.to_json();
+
+        assert_eq!(value["error"]["kind"], "auth");
+        assert_eq!(value["error"]["status"], 403);
+        assert_eq!(value["error"]["message"], "Authentication error: Forbidden: Not a member of this organization");
+        assert_eq!(value["error"]["request_id"], "req-4");
+    }
+}
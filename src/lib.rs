@@ -0,0 +1,36 @@
+//! Client library for the ZereData API.
+//!
+//! This is the layer the `zere` CLI and TUI are built on. It can be used on its
+//! own to submit and monitor synthetic data jobs, manage assets and download
+//! datasets from Rust services.
+//!
+//! ```no_run
+//! use zere_sdk::{ApiClient, Config, Result};
+//!
+//! # async fn run() -> Result<()> {
+//! // Reads ~/.config/zere/config.toml, as written by `zere login`
+//! let config = Config::load()?;
+//! let client = ApiClient::from_config(&config)?;
+//!
+//! let job = client.create_job("pallets".to_string(), 100, None, None).await?;
+//!
+//! let progress = client.get_job_progress(&job.job_id).await?;
+//! if progress.status.is_terminal() {
+//!     println!("{} finished as {}", job.job_id, progress.status);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Services that don't use the CLI config file can build a client directly with
+//! [`ApiClient::new`].
+
+pub mod api;
+pub mod config;
+pub mod error;
+pub mod time;
+pub mod validate;
+
+pub use api::ApiClient;
+pub use config::Config;
+pub use error::{ApiError, CliError, FieldError, Result};
@@ -0,0 +1,70 @@
+use crate::api::HTTP_TARGET;
+use crate::config::Config;
+use crate::error::{CliError, Result};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::EnvFilter;
+
+/// Number of daily log files kept under the config dir
+const MAX_LOG_FILES: usize = 7;
+
+/// Where log output is written
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogDestination {
+    Stderr,
+    /// Daily-rotated files under `<config dir>/logs`
+    File,
+}
+
+/// Set up logging from `-v` flags and the `ZERE_LOG` / `ZERE_LOG_FILE` env vars.
+///
+/// `-v` logs method, URL, status and latency of every request; `-vv` adds
+/// (redacted) bodies. `ZERE_LOG` takes a filter such as `debug` or
+/// `zere::http=trace` and wins over `-v`. Returns a guard that must be kept
+/// alive so buffered file output is flushed on exit.
+pub fn init(verbosity: u8, destination: LogDestination) -> Result<Option<WorkerGuard>> {
+    let filter = match std::env::var("ZERE_LOG") {
+        Ok(filter) if !filter.is_empty() => EnvFilter::try_new(&filter)
+            .map_err(|e| CliError::Config(format!("Invalid ZERE_LOG filter '{}': {}", filter, e)))?,
+        _ => match verbosity {
+            0 => return Ok(None),
+            1 => EnvFilter::new(format!("{}=debug", HTTP_TARGET)),
+            _ => EnvFilter::new(format!("{}=trace", HTTP_TARGET)),
+        },
+    };
+
+    let destination = if std::env::var_os("ZERE_LOG_FILE").is_some() {
+        LogDestination::File
+    } else {
+        destination
+    };
+
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_target(false);
+
+    match destination {
+        LogDestination::Stderr => {
+            builder.with_writer(std::io::stderr).init();
+            Ok(None)
+        }
+        LogDestination::File => {
+            let dir = log_dir()?;
+            let appender = RollingFileAppender::builder()
+                .rotation(Rotation::DAILY)
+                .filename_prefix("zere")
+                .filename_suffix("log")
+                .max_log_files(MAX_LOG_FILES)
+                .build(&dir)
+                .map_err(|e| CliError::Config(format!("Failed to open log file in {}: {}", dir.display(), e)))?;
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            builder.with_writer(writer).with_ansi(false).init();
+            Ok(Some(guard))
+        }
+    }
+}
+
+/// Directory holding rotated log files
+pub fn log_dir() -> Result<std::path::PathBuf> {
+    Ok(Config::config_dir()?.join("logs"))
+}
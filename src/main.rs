@@ -1,12 +1,15 @@
-mod api;
 mod commands;
-mod config;
-mod error;
+mod logging;
+mod preview;
 mod tui;
 
-use clap::{Parser, Subcommand};
+use zere_sdk::{api, config, error, time, validate};
+
+use api::assets::{AssetQuery, AssetSort};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use console::style;
 use std::path::PathBuf;
+use std::process::ExitCode;
 
 #[derive(Parser)]
 #[command(name = "zere")]
@@ -19,9 +22,50 @@ struct Cli {
     /// Launch interactive TUI mode
     #[arg(short, long)]
     interactive: bool,
+
+    /// Retry failed API requests up to N times after the first attempt (overrides config)
+    #[arg(long, global = true, value_name = "N")]
+    retries: Option<u32>,
+
+    /// Disable automatic retries of failed API requests
+    #[arg(long, global = true, conflicts_with = "retries")]
+    no_retry: bool,
+
+    /// Seconds allowed to connect, for every operation (overrides config)
+    #[arg(long, global = true, value_name = "SECS")]
+    connect_timeout: Option<u64>,
+
+    /// Seconds a transfer may stall without sending or receiving data before failing (overrides config)
+    #[arg(long, global = true, value_name = "SECS")]
+    read_timeout: Option<u64>,
+
+    /// Seconds allowed for a whole API call, 0 for no limit; uploads and downloads are
+    /// limited by --read-timeout instead (overrides config)
+    #[arg(long, global = true, value_name = "SECS")]
+    timeout: Option<u64>,
+
+    /// Log HTTP requests to stderr (-v: method, URL, status, latency; -vv: also bodies)
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Write logs to a daily-rotated file under the config dir instead of stderr
+    #[arg(long, global = true)]
+    log_file: bool,
+
+    /// Output format for errors and `assets inspect`
+    ///
+    /// Named --format rather than --output, which the download commands use for the destination.
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
 }
 
-#[derive(Subcommand)]
+#[derive(Subcommand, Clone)]
 enum Commands {
     /// Authentication commands
     #[command(subcommand)]
@@ -65,7 +109,23 @@ enum Commands {
     Config(ConfigCommands),
 }
 
-#[derive(Subcommand)]
+/// Pagination flags shared by the `list` commands
+#[derive(Args, Clone)]
+struct PageArgs {
+    /// Number of items per page
+    #[arg(long, default_value_t = api::pagination::DEFAULT_PAGE_SIZE, value_parser = clap::value_parser!(u32).range(1..))]
+    limit: u32,
+
+    /// Page to show (1-based)
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    page: u32,
+
+    /// Fetch and show every page
+    #[arg(long, conflicts_with = "page")]
+    all: bool,
+}
+
+#[derive(Subcommand, Clone)]
 enum AuthCommands {
     /// Login to Zere platform
     Login {
@@ -89,17 +149,61 @@ enum AuthCommands {
     Whoami,
 }
 
-#[derive(Subcommand)]
+#[derive(Subcommand, Clone)]
 enum AssetCommands {
-    /// List all assets
-    List,
+    /// List assets
+    List {
+        #[command(flatten)]
+        pages: PageArgs,
 
-    /// Upload an asset
+        /// Only assets of this file type, e.g. glb
+        #[arg(long = "type")]
+        file_type: Option<String>,
+
+        /// Only assets in this category
+        #[arg(short, long)]
+        category: Option<String>,
+
+        /// Only assets with this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+
+        /// Only assets whose name matches this glob, e.g. 'oak*' (case-insensitive)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Only assets at least this big, e.g. 10MB
+        #[arg(long, value_parser = commands::assets::parse_size)]
+        min_size: Option<u64>,
+
+        /// Only assets at most this big, e.g. 1.5GB
+        #[arg(long, value_parser = commands::assets::parse_size)]
+        max_size: Option<u64>,
+
+        /// Only assets created on or after this date (YYYY-MM-DD or RFC 3339)
+        #[arg(long, value_parser = commands::assets::parse_date)]
+        created_after: Option<String>,
+
+        /// Only assets created before this date (YYYY-MM-DD or RFC 3339)
+        #[arg(long, value_parser = commands::assets::parse_date)]
+        created_before: Option<String>,
+
+        /// Sort order
+        #[arg(long, value_enum)]
+        sort: Option<AssetSort>,
+
+        /// Sort in descending order
+        #[arg(long, requires = "sort")]
+        desc: bool,
+    },
+
+    /// Upload assets: files, or directories walked recursively
     Upload {
-        /// Path to the asset file
-        file: PathBuf,
+        /// Files or directories to upload
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
 
-        /// Asset name (defaults to filename)
+        /// Asset name (defaults to filename; single file only)
         #[arg(short, long)]
         name: Option<String>,
 
@@ -110,29 +214,198 @@ enum AssetCommands {
         /// Tags (can be specified multiple times)
         #[arg(short, long)]
         tags: Vec<String>,
+
+        /// Continue an interrupted chunked upload of this file
+        #[arg(long)]
+        resume: bool,
+
+        /// Upload files whose content already exists, deleting the older copies
+        #[arg(long)]
+        replace: bool,
+
+        /// With --replace, delete older copies even if active jobs use them
+        #[arg(short, long, requires = "replace")]
+        force: bool,
+
+        /// Skip local format checks before uploading
+        #[arg(long)]
+        no_validate: bool,
+
+        /// Only upload files in directories matching this glob (can be specified multiple times)
+        #[arg(long)]
+        include: Vec<String>,
+
+        /// Skip files in directories matching this glob (can be specified multiple times)
+        #[arg(long)]
+        exclude: Vec<String>,
+
+        /// Number of files to upload in parallel
+        #[arg(short = 'j', long, default_value_t = 4, value_parser = clap::value_parser!(u32).range(1..))]
+        concurrency: u32,
+
+        /// Attach mesh statistics (as reported by `assets inspect`) to each asset
+        #[arg(long)]
+        inspect: bool,
+    },
+
+    /// Check asset files locally without uploading them
+    Validate {
+        /// Files or directories to check
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+
+        /// Only check files in directories matching this glob (can be specified multiple times)
+        #[arg(long)]
+        include: Vec<String>,
+
+        /// Skip files in directories matching this glob (can be specified multiple times)
+        #[arg(long)]
+        exclude: Vec<String>,
+    },
+
+    /// Report a mesh file's bounds, counts, textures and units without uploading it
+    Inspect {
+        /// glTF, GLB, OBJ, PLY or STL file
+        file: PathBuf,
+    },
+
+    /// Change an asset's name, category or tags
+    Update {
+        /// Asset ID
+        asset_id: String,
+
+        /// New asset name
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// New asset category
+        #[arg(short, long)]
+        category: Option<String>,
+
+        /// Tag to add (can be specified multiple times)
+        #[arg(long = "add-tag")]
+        add_tags: Vec<String>,
+
+        /// Tag to remove (can be specified multiple times)
+        #[arg(long = "remove-tag")]
+        remove_tags: Vec<String>,
+    },
+
+    /// Download original asset files
+    Download {
+        /// Asset IDs; omit to download every asset matching --tag/--category
+        #[arg(required_unless_present_any = ["tag", "category"], conflicts_with_all = ["tag", "category"])]
+        asset_ids: Vec<String>,
+
+        /// Download every asset with this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+
+        /// Download every asset in this category
+        #[arg(short, long)]
+        category: Option<String>,
+
+        /// Directory to save files in
+        #[arg(short, long, default_value = ".")]
+        output: PathBuf,
+    },
+
+    /// Make the server's assets match a directory, as described by its assets.toml
+    Sync {
+        /// Directory to sync
+        dir: PathBuf,
+
+        /// Only sync assets in this category, putting every file in it
+        #[arg(short, long)]
+        category: Option<String>,
+
+        /// Only sync assets with this tag, adding it to every file
+        #[arg(short, long)]
+        tag: Option<String>,
+
+        /// Delete assets that have no local file
+        #[arg(long)]
+        prune: bool,
+
+        /// Show the plan without changing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Skip local format checks before uploading
+        #[arg(long)]
+        no_validate: bool,
+
+        /// Apply replacements and deletions without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Also replace and delete assets that active jobs use (still asks unless --yes is given)
+        #[arg(short, long)]
+        force: bool,
     },
 
     /// Get asset information
     Info {
         /// Asset ID
         asset_id: String,
+
+        /// Draw the asset's thumbnail in the terminal
+        #[arg(long)]
+        preview: bool,
     },
 
-    /// Delete an asset
+    /// Delete assets by ID, or every asset matching the filters
     Delete {
-        /// Asset ID
-        asset_id: String,
+        /// Asset IDs; omit to delete every asset matching the filters
+        #[arg(
+            required_unless_present_any = ["tag", "file_type", "older_than", "name"],
+            conflicts_with_all = ["tag", "file_type", "older_than", "name"]
+        )]
+        asset_ids: Vec<String>,
+
+        /// Only assets with this tag
+        #[arg(short, long)]
+        tag: Option<String>,
+
+        /// Only assets of this file type, e.g. glb
+        #[arg(long = "type")]
+        file_type: Option<String>,
+
+        /// Only assets created longer ago than this, e.g. 12h, 30d or 2w
+        #[arg(long, value_parser = commands::assets::parse_age)]
+        older_than: Option<u64>,
+
+        /// Only assets whose name matches this glob, e.g. 'test-*' (case-insensitive)
+        #[arg(long)]
+        name: Option<String>,
 
-        /// Force deletion without confirmation
+        /// Show what would be deleted without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Delete without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Also delete assets that active jobs use (still asks unless --yes is given)
         #[arg(short, long)]
         force: bool,
     },
+
+    /// List the jobs and configs that reference an asset
+    Usage {
+        /// Asset ID
+        asset_id: String,
+    },
 }
 
-#[derive(Subcommand)]
+#[derive(Subcommand, Clone)]
 enum JobCommands {
-    /// List all jobs
-    List,
+    /// List jobs
+    List {
+        #[command(flatten)]
+        pages: PageArgs,
+    },
 
     /// Create a new job
     Create {
@@ -147,6 +420,10 @@ enum JobCommands {
         /// Path to config YAML file
         #[arg(short, long)]
         config: Option<PathBuf>,
+
+        /// Key that makes retries of this request safe (generated and remembered if omitted)
+        #[arg(long)]
+        idempotency_key: Option<String>,
     },
 
     /// Quick Start - Generate with procedural objects (no assets needed)
@@ -162,6 +439,10 @@ enum JobCommands {
         /// Environment type (warehouse_shelf, floor, table)
         #[arg(short, long, default_value = "warehouse_shelf")]
         environment: String,
+
+        /// Key that makes retries of this request safe (generated and remembered if omitted)
+        #[arg(long)]
+        idempotency_key: Option<String>,
     },
 
     /// Get job status
@@ -187,10 +468,13 @@ enum JobCommands {
     },
 }
 
-#[derive(Subcommand)]
+#[derive(Subcommand, Clone)]
 enum DatasetCommands {
-    /// List all datasets
-    List,
+    /// List datasets
+    List {
+        #[command(flatten)]
+        pages: PageArgs,
+    },
 
     /// Get dataset information
     Info {
@@ -209,7 +493,7 @@ enum DatasetCommands {
     },
 }
 
-#[derive(Subcommand)]
+#[derive(Subcommand, Clone)]
 enum ConfigCommands {
     /// Set API endpoint
     SetEndpoint {
@@ -222,49 +506,217 @@ enum ConfigCommands {
 }
 
 #[tokio::main]
-async fn main() {
+async fn main() -> ExitCode {
     let cli = Cli::parse();
 
-    let result = match cli.command {
+    let globals = Globals {
+        format: cli.format,
+        overrides: config::Overrides {
+            retries: if cli.no_retry { Some(0) } else { cli.retries },
+            connect_timeout_secs: cli.connect_timeout,
+            read_timeout_secs: cli.read_timeout,
+            total_timeout_secs: cli.timeout,
+        },
+    };
+
+    // Logging to stderr would garble the TUI, so it always logs to a file
+    let log_destination = if cli.log_file || (cli.interactive && cli.command.is_none()) {
+        logging::LogDestination::File
+    } else {
+        logging::LogDestination::Stderr
+    };
+    let _log_guard = match logging::init(cli.verbose, log_destination) {
+        Ok(guard) => guard,
+        Err(e) => {
+            print_error(&e, cli.format);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // Returning instead of exiting drops the log guard, which flushes buffered log lines
+    match run(cli.command, cli.interactive, &globals).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            print_error(&e, cli.format);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Settings from global flags that commands need
+struct Globals {
+    format: OutputFormat,
+    overrides: config::Overrides,
+}
+
+async fn run(command: Option<Commands>, interactive: bool, globals: &Globals) -> error::Result<()> {
+    match command {
         Some(Commands::Auth(auth_cmd)) => match auth_cmd {
             AuthCommands::Login { email, password, api_key } => {
-                commands::auth::login(email, password, api_key).await
+                commands::auth::login(email, password, api_key, &globals.overrides).await
             }
-            AuthCommands::Logout => commands::auth::logout().await,
-            AuthCommands::Whoami => commands::auth::whoami().await,
+            AuthCommands::Logout => commands::auth::logout(&globals.overrides).await,
+            AuthCommands::Whoami => commands::auth::whoami(&client(globals)?).await,
         },
         Some(Commands::Login { email, password, api_key }) => {
-            commands::auth::login(email, password, api_key).await
+            commands::auth::login(email, password, api_key, &globals.overrides).await
         }
-        Some(Commands::Logout) => commands::auth::logout().await,
-        Some(Commands::Whoami) => commands::auth::whoami().await,
+        Some(Commands::Logout) => commands::auth::logout(&globals.overrides).await,
+        Some(Commands::Whoami) => commands::auth::whoami(&client(globals)?).await,
         Some(Commands::Assets(asset_cmd)) => match asset_cmd {
-            AssetCommands::List => commands::assets::list().await,
-            AssetCommands::Upload { file, name, category, tags } => {
-                commands::assets::upload(file, name, category, tags).await
+            AssetCommands::List {
+                pages,
+                file_type,
+                category,
+                tag,
+                name,
+                min_size,
+                max_size,
+                created_after,
+                created_before,
+                sort,
+                desc,
+            } => {
+                let mut query = AssetQuery {
+                    file_type,
+                    category,
+                    tag,
+                    name: None,
+                    min_size,
+                    max_size,
+                    created_after,
+                    created_before,
+                    sort,
+                    descending: desc,
+                };
+                if let Some(pattern) = name {
+                    query = query.name_glob(&pattern)?;
+                }
+                commands::assets::list(&client(globals)?, pages.limit, pages.page, pages.all, query).await
+            }
+            AssetCommands::Upload {
+                paths,
+                name,
+                category,
+                tags,
+                resume,
+                replace,
+                force,
+                no_validate,
+                include,
+                exclude,
+                concurrency,
+                inspect,
+            } => {
+                let options = commands::assets::UploadOptions {
+                    name,
+                    category,
+                    tags,
+                    resume,
+                    replace,
+                    force,
+                    validate: !no_validate,
+                    include,
+                    exclude,
+                    concurrency: concurrency as usize,
+                    inspect,
+                };
+                commands::assets::upload(&client(globals)?, paths, options).await
+            }
+            AssetCommands::Validate { paths, include, exclude } => {
+                commands::assets::validate(paths, include, exclude).await
+            }
+            AssetCommands::Inspect { file } => commands::assets::inspect(file, globals.format == OutputFormat::Json).await,
+            AssetCommands::Update { asset_id, name, category, add_tags, remove_tags } => {
+                let options = commands::assets::UpdateOptions { name, category, add_tags, remove_tags };
+                commands::assets::update(&client(globals)?, asset_id, options).await
+            }
+            AssetCommands::Download { asset_ids, tag, category, output } => {
+                let options = commands::assets::DownloadOptions {
+                    asset_ids,
+                    query: AssetQuery {
+                        tag,
+                        category,
+                        ..AssetQuery::default()
+                    },
+                    output_dir: output,
+                };
+                commands::assets::download(&client(globals)?, options).await
+            }
+            AssetCommands::Sync { dir, category, tag, prune, dry_run, no_validate, yes, force } => {
+                let options = commands::asset_sync::SyncOptions {
+                    dir,
+                    category,
+                    tag,
+                    prune,
+                    dry_run,
+                    yes,
+                    force,
+                    validate: !no_validate,
+                };
+                commands::asset_sync::sync(&client(globals)?, options).await
             }
-            AssetCommands::Info { asset_id } => commands::assets::info(asset_id).await,
-            AssetCommands::Delete { asset_id, force } => {
-                commands::assets::delete(asset_id, force).await
+            AssetCommands::Info { asset_id, preview } => {
+                commands::assets::info(&client(globals)?, asset_id, preview).await
             }
+            AssetCommands::Delete {
+                asset_ids,
+                tag,
+                file_type,
+                older_than,
+                name,
+                dry_run,
+                yes,
+                force,
+            } => {
+                let mut query = AssetQuery {
+                    tag,
+                    file_type,
+                    created_before: older_than.map(|secs| time::rfc3339(time::unix_now() - secs as i64)),
+                    ..AssetQuery::default()
+                };
+                if let Some(pattern) = name {
+                    query = query.name_glob(&pattern)?;
+                }
+                let options = commands::assets::DeleteOptions {
+                    asset_ids,
+                    query,
+                    dry_run,
+                    yes,
+                    force,
+                };
+                commands::assets::delete(&client(globals)?, options).await
+            }
+            AssetCommands::Usage { asset_id } => commands::assets::usage(&client(globals)?, asset_id).await,
         },
         Some(Commands::Jobs(job_cmd)) => match job_cmd {
-            JobCommands::List => commands::jobs::list().await,
-            JobCommands::Create { name, num_scenes, config } => {
-                commands::jobs::create(name, num_scenes, config).await
+            JobCommands::List { pages } => {
+                commands::jobs::list(&client(globals)?, pages.limit, pages.page, pages.all).await
+            }
+            JobCommands::Create { name, num_scenes, config, idempotency_key } => {
+                commands::jobs::create(&client(globals)?, name, num_scenes, config, idempotency_key).await
             }
-            JobCommands::QuickStart { num_scenes, objects, environment } => {
-                commands::jobs::quick_start(num_scenes, Some(objects), Some(environment)).await
+            JobCommands::QuickStart { num_scenes, objects, environment, idempotency_key } => {
+                commands::jobs::quick_start(
+                    &client(globals)?,
+                    num_scenes,
+                    Some(objects),
+                    Some(environment),
+                    idempotency_key,
+                )
+                .await
             }
-            JobCommands::Status { job_id } => commands::jobs::status(job_id).await,
-            JobCommands::Watch { job_id } => commands::jobs::watch(job_id).await,
-            JobCommands::Cancel { job_id, force } => commands::jobs::cancel(job_id, force).await,
+            JobCommands::Status { job_id } => commands::jobs::status(&client(globals)?, job_id).await,
+            JobCommands::Watch { job_id } => commands::jobs::watch(&client(globals)?, job_id).await,
+            JobCommands::Cancel { job_id, force } => commands::jobs::cancel(&client(globals)?, job_id, force).await,
         },
         Some(Commands::Datasets(dataset_cmd)) => match dataset_cmd {
-            DatasetCommands::List => commands::datasets::list().await,
-            DatasetCommands::Info { dataset_id } => commands::datasets::info(dataset_id).await,
+            DatasetCommands::List { pages } => {
+                commands::datasets::list(&client(globals)?, pages.limit, pages.page, pages.all).await
+            }
+            DatasetCommands::Info { dataset_id } => commands::datasets::info(&client(globals)?, dataset_id).await,
             DatasetCommands::Download { job_id, output } => {
-                commands::datasets::download(job_id, output).await
+                commands::datasets::download(&client(globals)?, job_id, output).await
             }
         },
         Some(Commands::Config(config_cmd)) => match config_cmd {
@@ -274,9 +726,9 @@ async fn main() {
             ConfigCommands::Show => commands::config::show().await,
         },
         None => {
-            if cli.interactive {
+            if interactive {
                 // Launch TUI mode
-                tui::run().await
+                tui::run(client_builder(globals)?.build()?).await
             } else {
                 // Show help
                 println!("{}", style("Zere CLI - Synthetic data generation for robotics").bold());
@@ -286,10 +738,51 @@ async fn main() {
                 Ok(())
             }
         }
-    };
+    }
+}
+
+/// Client for commands that talk to the API, built from the saved config.
+///
+/// Refreshed sessions are saved. When one can no longer be refreshed and someone is
+/// at the terminal, they are asked to log in again and the failed request is resent.
+fn client(globals: &Globals) -> error::Result<api::ApiClient> {
+    let builder = client_builder(globals)?;
+    if globals.format == OutputFormat::Text && console::user_attended() {
+        let overrides = globals.overrides.clone();
+        builder
+            .on_session_expired(move || commands::auth::relogin(overrides.clone()))
+            .build()
+    } else {
+        builder.build()
+    }
+}
+
+/// Builder for a client from the saved config and global flags that saves refreshed sessions
+fn client_builder(globals: &Globals) -> error::Result<api::ApiClientBuilder> {
+    Ok(api::ApiClient::builder(&config::Config::load()?)
+        .overrides(globals.overrides.clone())
+        .persist_credentials(true))
+}
+
+fn print_error(e: &error::CliError, format: OutputFormat) {
+    if format == OutputFormat::Json {
+        eprintln!("{}", e.to_json());
+        return;
+    }
+
+    eprintln!("{} {}", style("Error:").red().bold(), e);
+
+    if let Some(api_error) = e.api_error() {
+        for field_error in &api_error.field_errors {
+            eprintln!("  {} {}", style("•").red(), field_error);
+        }
+    }
+
+    if let error::CliError::RateLimited { retry_after_secs: Some(secs), .. } = e {
+        eprintln!("  {} {}s", style("Retry after:").dim(), secs);
+    }
 
-    if let Err(e) = result {
-        eprintln!("{} {}", style("Error:").red().bold(), e);
-        std::process::exit(1);
+    if let Some(request_id) = e.request_id() {
+        eprintln!("  {} {}", style("Request ID:").dim(), request_id);
     }
 }
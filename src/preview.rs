@@ -0,0 +1,297 @@
+//! Asset thumbnails drawn in the terminal
+
+use crate::error::{CliError, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use image::imageops::{self, FilterType};
+use image::{Rgba, RgbaImage};
+use std::io::{self, Write};
+
+/// Decoded thumbnails are scaled down to this size; previews are never more than a few dozen cells wide
+const MAX_DECODED_SIDE: u32 = 256;
+
+/// Cell size in pixels to assume when the terminal does not report one
+const DEFAULT_CELL_PX: (u32, u32) = (8, 16);
+
+/// Sixel images use a fixed colour cube with this many levels per channel
+const SIXEL_LEVELS: u32 = 6;
+
+/// How the terminal can draw images
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Graphics {
+    Kitty,
+    Iterm,
+    Sixel,
+    /// Unicode half blocks in 24-bit colour, which works almost everywhere
+    Blocks,
+}
+
+impl Graphics {
+    /// Guess from the environment; `ZERE_GRAPHICS=kitty|iterm|sixel|blocks` overrides the guess
+    pub fn detect() -> Graphics {
+        let var = |name| std::env::var(name).unwrap_or_default();
+        match var("ZERE_GRAPHICS").to_ascii_lowercase().as_str() {
+            "kitty" => return Graphics::Kitty,
+            "iterm" => return Graphics::Iterm,
+            "sixel" => return Graphics::Sixel,
+            "blocks" => return Graphics::Blocks,
+            _ => {}
+        }
+
+        let term = var("TERM");
+        let program = var("TERM_PROGRAM");
+        // Multiplexers swallow or garble graphics escapes
+        if std::env::var_os("TMUX").is_some() || term.starts_with("screen") {
+            Graphics::Blocks
+        } else if std::env::var_os("KITTY_WINDOW_ID").is_some() || term.contains("kitty") || term.contains("ghostty") {
+            Graphics::Kitty
+        } else if program == "iTerm.app" || program == "WezTerm" || var("LC_TERMINAL") == "iTerm2" {
+            Graphics::Iterm
+        } else if term.contains("sixel") || term.starts_with("foot") || term.starts_with("mlterm") {
+            Graphics::Sixel
+        } else {
+            Graphics::Blocks
+        }
+    }
+}
+
+/// One terminal cell of a half-block image: the colours of its upper and lower pixel
+pub type HalfBlock = ([u8; 3], [u8; 3]);
+
+/// A decoded thumbnail
+pub struct Thumbnail {
+    image: RgbaImage,
+    /// The file as downloaded, which iTerm displays itself
+    bytes: Vec<u8>,
+}
+
+impl Thumbnail {
+    /// Decode a PNG, JPEG or WebP thumbnail
+    pub fn decode(bytes: Vec<u8>) -> Result<Thumbnail> {
+        let image = image::load_from_memory(&bytes)
+            .map_err(|e| CliError::Other(format!("Could not decode thumbnail: {}", e)))?;
+        let image = if image.width().max(image.height()) > MAX_DECODED_SIDE {
+            image.thumbnail(MAX_DECODED_SIDE, MAX_DECODED_SIDE)
+        } else {
+            image
+        };
+        Ok(Thumbnail {
+            image: image.to_rgba8(),
+            bytes,
+        })
+    }
+
+    /// Rows needed to draw the image `cols` cells wide, taking cells to be twice as tall as wide
+    pub fn rows_for(&self, cols: u16) -> u16 {
+        let (width, height) = self.image.dimensions();
+        let rows = (u64::from(cols) * u64::from(height)).div_ceil(2 * u64::from(width.max(1)));
+        rows.clamp(1, u64::from(u16::MAX)) as u16
+    }
+
+    /// The largest size in cells that keeps the aspect ratio and fits in `cols` x `rows`
+    fn fit(&self, cols: u16, rows: u16) -> (u16, u16) {
+        let needed = self.rows_for(cols);
+        if needed <= rows {
+            return (cols, needed);
+        }
+        let (width, height) = self.image.dimensions();
+        let cols = (u64::from(rows) * 2 * u64::from(width)) / u64::from(height.max(1));
+        (cols.clamp(1, u64::from(u16::MAX)) as u16, rows)
+    }
+
+    /// Cells of the image scaled to fit in `cols` x `rows`, row by row
+    pub fn half_blocks(&self, cols: u16, rows: u16) -> Vec<Vec<HalfBlock>> {
+        let (cols, rows) = self.fit(cols.max(1), rows.max(1));
+        let scaled = imageops::resize(&self.image, cols.into(), u32::from(rows) * 2, FilterType::Triangle);
+        (0..u32::from(rows))
+            .map(|y| {
+                (0..u32::from(cols))
+                    .map(|x| (opaque(scaled.get_pixel(x, 2 * y)), opaque(scaled.get_pixel(x, 2 * y + 1))))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Draw the image `cols` cells wide on stdout
+    pub fn print(&self, graphics: Graphics, cols: u16) -> Result<()> {
+        let rows = self.rows_for(cols);
+        let mut out = io::stdout().lock();
+        match graphics {
+            Graphics::Kitty => write_kitty(&mut out, &self.image, cols, rows)?,
+            Graphics::Iterm => write!(
+                out,
+                "\x1b]1337;File=inline=1;size={};width={};preserveAspectRatio=1:{}\x07",
+                self.bytes.len(),
+                cols,
+                STANDARD.encode(&self.bytes)
+            )?,
+            Graphics::Sixel => {
+                let (cell_width, cell_height) = cell_px();
+                let scaled = imageops::resize(
+                    &self.image,
+                    u32::from(cols) * cell_width,
+                    u32::from(rows) * cell_height,
+                    FilterType::Triangle,
+                );
+                write_sixel(&mut out, &scaled)?;
+            }
+            Graphics::Blocks => {
+                for row in self.half_blocks(cols, rows) {
+                    for ([r, g, b], [r2, g2, b2]) in row {
+                        write!(out, "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m▀", r, g, b, r2, g2, b2)?;
+                    }
+                    writeln!(out, "\x1b[0m")?;
+                }
+            }
+        }
+        if graphics != Graphics::Blocks {
+            writeln!(out)?;
+        }
+        out.flush()?;
+        Ok(())
+    }
+}
+
+/// Flatten a pixel onto a black background
+fn opaque(pixel: &Rgba<u8>) -> [u8; 3] {
+    let [r, g, b, a] = pixel.0;
+    let scale = |c: u8| (u16::from(c) * u16::from(a) / 255) as u8;
+    [scale(r), scale(g), scale(b)]
+}
+
+/// Size of one terminal cell in pixels
+fn cell_px() -> (u32, u32) {
+    match crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => (
+            u32::from(size.width / size.columns).max(1),
+            u32::from(size.height / size.rows).max(1),
+        ),
+        _ => DEFAULT_CELL_PX,
+    }
+}
+
+/// Kitty graphics protocol: raw RGBA sent in base64 chunks of at most 4096 bytes
+fn write_kitty(out: &mut impl Write, image: &RgbaImage, cols: u16, rows: u16) -> io::Result<()> {
+    let data = STANDARD.encode(image.as_raw());
+    let chunks: Vec<&[u8]> = data.as_bytes().chunks(4096).collect();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        if i == 0 {
+            // q=2: the terminal would otherwise answer on stdin
+            write!(
+                out,
+                "\x1b_Ga=T,f=32,q=2,s={},v={},c={},r={},m={};",
+                image.width(),
+                image.height(),
+                cols,
+                rows,
+                more
+            )?;
+        } else {
+            write!(out, "\x1b_Gm={};", more)?;
+        }
+        out.write_all(chunk)?;
+        out.write_all(b"\x1b\\")?;
+    }
+    Ok(())
+}
+
+/// Sixel graphics: each band of six pixel rows is drawn once per colour it uses
+fn write_sixel(out: &mut impl Write, image: &RgbaImage) -> io::Result<()> {
+    let (width, height) = image.dimensions();
+    let level = |c: u8| (u32::from(c) * (SIXEL_LEVELS - 1) + 127) / 255;
+    let colours: Vec<u32> = image
+        .pixels()
+        .map(|pixel| {
+            let [r, g, b] = opaque(pixel);
+            (level(r) * SIXEL_LEVELS + level(g)) * SIXEL_LEVELS + level(b)
+        })
+        .collect();
+
+    write!(out, "\x1bPq\"1;1;{};{}", width, height)?;
+    let percent = |level: u32| level * 100 / (SIXEL_LEVELS - 1);
+    for colour in 0..SIXEL_LEVELS.pow(3) {
+        let (r, g, b) = (colour / 36, colour / 6 % 6, colour % 6);
+        write!(out, "#{};2;{};{};{}", colour, percent(r), percent(g), percent(b))?;
+    }
+
+    for top in (0..height).step_by(6) {
+        let band = top..(top + 6).min(height);
+        let mut used: Vec<u32> = band
+            .clone()
+            .flat_map(|y| colours[(y * width) as usize..((y + 1) * width) as usize].iter().copied())
+            .collect();
+        used.sort_unstable();
+        used.dedup();
+
+        for (i, colour) in used.into_iter().enumerate() {
+            if i > 0 {
+                // Back to the start of the band for the next colour
+                out.write_all(b"$")?;
+            }
+            write!(out, "#{}", colour)?;
+            let mut run: Option<(u8, u32)> = None;
+            for x in 0..width {
+                let bits = band
+                    .clone()
+                    .filter(|y| colours[(y * width + x) as usize] == colour)
+                    .fold(0u8, |bits, y| bits | 1 << (y - top));
+                let sixel = b'?' + bits;
+                run = match run {
+                    Some((current, count)) if current == sixel => Some((current, count + 1)),
+                    Some((current, count)) => {
+                        write_run(out, current, count)?;
+                        Some((sixel, 1))
+                    }
+                    None => Some((sixel, 1)),
+                };
+            }
+            if let Some((current, count)) = run {
+                write_run(out, current, count)?;
+            }
+        }
+        out.write_all(b"-")?;
+    }
+    out.write_all(b"\x1b\\")
+}
+
+fn write_run(out: &mut impl Write, sixel: u8, count: u32) -> io::Result<()> {
+    if count > 3 {
+        write!(out, "!{}{}", count, sixel as char)
+    } else {
+        out.write_all(&vec![sixel; count as usize])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::ImageFormat;
+    use std::io::Cursor;
+
+    fn png(width: u32, height: u32, pixel: impl Fn(u32, u32) -> Rgba<u8>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        RgbaImage::from_fn(width, height, pixel)
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_half_blocks_keep_aspect_ratio() {
+        // Red on top, half-transparent blue below
+        let bytes = png(8, 8, |_, y| if y < 4 { Rgba([255, 0, 0, 255]) } else { Rgba([0, 0, 255, 128]) });
+        let thumbnail = Thumbnail::decode(bytes).unwrap();
+
+        assert_eq!(thumbnail.rows_for(8), 4);
+        let cells = thumbnail.half_blocks(40, 2);
+        assert_eq!((cells[0].len(), cells.len()), (4, 2));
+        assert_eq!(cells[0][0].0, [255, 0, 0]);
+        assert_eq!(cells[1][3].1, [0, 0, 128]);
+
+        let mut sixel = Vec::new();
+        write_sixel(&mut sixel, &thumbnail.image).unwrap();
+        assert!(sixel.starts_with(b"\x1bPq\"1;1;8;8"));
+        assert!(sixel.ends_with(b"-\x1b\\"));
+    }
+}
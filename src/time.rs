@@ -0,0 +1,144 @@
+//! Unix time and the RFC 3339 timestamps the backend uses.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+
+/// Current time as a Unix timestamp in seconds
+pub fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// A Unix timestamp as an RFC 3339 UTC timestamp, the format the backend uses
+pub fn rfc3339(secs: i64) -> String {
+    let (days, rem) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+/// A `YYYY-MM-DD` date (midnight UTC) or an RFC 3339 timestamp as Unix time in nanoseconds.
+///
+/// Accepts fractional seconds and any UTC offset, e.g. `2024-05-01T12:00:00.250+02:00`.
+pub fn parse_rfc3339(s: &str) -> Option<i128> {
+    let (date, time) = match s.len() {
+        10 => (s, None),
+        _ if matches!(s.as_bytes().get(10), Some(b'T' | b't' | b' ')) => (s.get(..10)?, Some(s.get(11..)?)),
+        _ => return None,
+    };
+
+    let date = date.as_bytes();
+    if date[4] != b'-' || date[7] != b'-' {
+        return None;
+    }
+    let year = digits(&date[..4])?;
+    let month = digits(&date[5..7])?;
+    let day = digits(&date[8..10])?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let mut secs = days_from_civil(year, month, day) * 86_400;
+
+    let Some(time) = time else {
+        return Some(i128::from(secs) * NANOS_PER_SEC);
+    };
+    let time = time.as_bytes();
+    if time.len() < 9 || time[2] != b':' || time[5] != b':' {
+        return None;
+    }
+    let (hour, minute, second) = (digits(&time[..2])?, digits(&time[3..5])?, digits(&time[6..8])?);
+    // 60 allows for leap seconds
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    secs += hour * 3600 + minute * 60 + second;
+
+    let mut rest = &time[8..];
+    let mut nanos = 0;
+    if let Some(fraction) = rest.strip_prefix(b".") {
+        let len = fraction.iter().take_while(|b| b.is_ascii_digit()).count();
+        if len == 0 {
+            return None;
+        }
+        // Digits past nanosecond precision are dropped
+        let kept = &fraction[..len.min(9)];
+        nanos = digits(kept)? * 10_i64.pow(9 - kept.len() as u32);
+        rest = &fraction[len..];
+    }
+
+    match rest {
+        b"Z" | b"z" => {}
+        [sign @ (b'+' | b'-'), h1, h2, b':', m1, m2] => {
+            let offset = digits(&[*h1, *h2])? * 3600 + digits(&[*m1, *m2])? * 60;
+            secs += if *sign == b'+' { -offset } else { offset };
+        }
+        _ => return None,
+    }
+    Some(i128::from(secs) * NANOS_PER_SEC + i128::from(nanos))
+}
+
+/// An all-digit ASCII field as a number
+fn digits(bytes: &[u8]) -> Option<i64> {
+    if bytes.is_empty() || !bytes.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    std::str::from_utf8(bytes).ok()?.parse().ok()
+}
+
+/// Days since the Unix epoch to a (year, month, day) date in the proleptic Gregorian calendar
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Inverse of [`civil_from_days`]
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rfc3339_normalizes_offsets_and_fractions() {
+        let secs = |s: &str| parse_rfc3339(s).map(|nanos| nanos / NANOS_PER_SEC);
+        assert_eq!(secs("2024-05-01"), Some(1_714_521_600));
+        assert_eq!(secs("2024-05-01T12:00:00Z"), Some(1_714_564_800));
+        assert_eq!(secs("2024-05-01T14:00:00+02:00"), Some(1_714_564_800));
+        assert_eq!(secs("2024-05-01T07:30:00-04:30"), Some(1_714_564_800));
+        assert_eq!(rfc3339(secs("1969-12-31T23:59:59Z").unwrap() as i64), "1969-12-31T23:59:59Z");
+
+        let plain = parse_rfc3339("2024-05-01T12:00:00Z").unwrap();
+        assert_eq!(parse_rfc3339("2024-05-01T12:00:00.25Z"), Some(plain + 250_000_000));
+        assert_eq!(parse_rfc3339("2024-05-01T12:00:00.1234567891Z"), Some(plain + 123_456_789));
+
+        for invalid in ["2024-5-01", "2024-13-01", "2024-05-01T12:00:00", "2024-05-01T12:00Z", "2024-05-01T12:00:00.Z", "yesterday"] {
+            assert_eq!(parse_rfc3339(invalid), None, "{}", invalid);
+        }
+    }
+}
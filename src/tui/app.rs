@@ -1,10 +1,28 @@
-use crate::api::ApiClient;
+use crate::api::{ApiClient, ZereApi};
 use crate::api::assets::Asset;
-use crate::api::jobs::Job;
-use crate::config::Config;
-use crate::error::Result;
+use crate::api::jobs::{Job, JobStatus};
+use crate::api::pagination::{Page, PageRequest, DEFAULT_PAGE_SIZE};
+use crate::error::{CliError, Result};
+use crate::preview::Thumbnail;
 use crate::tui::components::{JobWizard, SearchBox};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// Start fetching the next page when the selection gets this close to the last loaded row
+const LOAD_MORE_THRESHOLD: usize = 5;
+
+/// Decoded thumbnails kept in memory before the cache is emptied
+const MAX_CACHED_PREVIEWS: usize = 100;
+
+/// How long to wait before fetching a thumbnail again after a failed attempt
+const PREVIEW_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// A finished thumbnail fetch: the asset ID and, unless the failure may be
+/// temporary, what to cache for it
+type PreviewResult = (String, Option<Option<Thumbnail>>);
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum AppState {
@@ -17,34 +35,53 @@ pub enum AppState {
 
 pub struct App {
     pub state: AppState,
-    pub client: ApiClient,
+    pub client: Arc<dyn ZereApi>,
     pub jobs: Vec<Job>,
     pub assets: Vec<Asset>,
+    /// Total number of jobs on the server (may exceed `jobs.len()`)
+    pub jobs_total: usize,
+    /// Total number of assets on the server (may exceed `assets.len()`)
+    pub assets_total: usize,
     pub selected_job_index: usize,
     pub selected_asset_index: usize,
     pub show_help: bool,
     pub job_wizard: JobWizard,
     pub search_box: SearchBox,
     pub status_message: Option<String>,
+    /// Asset ID -> its thumbnail, or `None` if it has none or it could not be decoded
+    pub previews: HashMap<String, Option<Thumbnail>>,
+    /// Thumbnails being fetched in the background, or that failed and when to try again
+    preview_fetches: HashMap<String, Option<Instant>>,
+    preview_tx: mpsc::UnboundedSender<PreviewResult>,
+    preview_rx: mpsc::UnboundedReceiver<PreviewResult>,
     last_refresh: Instant,
 }
 
 impl App {
-    pub async fn new() -> Result<App> {
-        let config = Config::load()?;
-        let client = ApiClient::from_config(&config)?;
+    pub async fn new(client: ApiClient) -> Result<App> {
+        App::with_api(Arc::new(client)).await
+    }
 
+    /// Create the app on top of any API implementation and load the first pages
+    pub async fn with_api(client: Arc<dyn ZereApi>) -> Result<App> {
+        let (preview_tx, preview_rx) = mpsc::unbounded_channel();
         let mut app = App {
             state: AppState::Dashboard,
             client,
             jobs: Vec::new(),
             assets: Vec::new(),
+            jobs_total: 0,
+            assets_total: 0,
             selected_job_index: 0,
             selected_asset_index: 0,
             show_help: false,
             job_wizard: JobWizard::new(),
             search_box: SearchBox::new(),
             status_message: None,
+            previews: HashMap::new(),
+            preview_fetches: HashMap::new(),
+            preview_tx,
+            preview_rx,
             last_refresh: Instant::now(),
         };
 
@@ -52,17 +89,128 @@ impl App {
         Ok(app)
     }
 
+    /// Re-fetch as many rows as are already loaded, so the scrolling position survives.
+    ///
+    /// A list that fails to load keeps its current rows.
     pub async fn refresh(&mut self) -> Result<()> {
-        // Fetch jobs
-        self.jobs = self.client.list_jobs().await.unwrap_or_default();
-
-        // Fetch assets
-        self.assets = self.client.list_assets().await.unwrap_or_default();
+        let client = self.client.clone();
+        if let Ok((jobs, total)) = reload(self.jobs.len(), |page| client.list_jobs_page(page)).await {
+            self.jobs = jobs;
+            self.jobs_total = total;
+        }
+        if let Ok((assets, total)) = reload(self.assets.len(), |page| client.list_assets_page(page)).await {
+            self.assets = assets;
+            self.assets_total = total;
+        }
 
+        self.clamp_selection();
         self.last_refresh = Instant::now();
         Ok(())
     }
 
+    /// Fetch the next page of the current list when the selection nears the last loaded row
+    pub async fn load_more_if_needed(&mut self) -> Result<()> {
+        match self.state {
+            AppState::Jobs
+                if self.jobs.len() < self.jobs_total
+                    && self.selected_job_index + LOAD_MORE_THRESHOLD >= self.jobs.len() =>
+            {
+                let page = PageRequest {
+                    limit: DEFAULT_PAGE_SIZE,
+                    offset: self.jobs.len() as u32,
+                };
+                if let Ok(page) = self.client.list_jobs_page(page).await {
+                    self.jobs_total = page.total.max(0) as usize;
+                    self.jobs.extend(page.items);
+                }
+            }
+            AppState::Assets
+                if self.assets.len() < self.assets_total
+                    && self.selected_asset_index + LOAD_MORE_THRESHOLD >= self.assets.len() =>
+            {
+                let page = PageRequest {
+                    limit: DEFAULT_PAGE_SIZE,
+                    offset: self.assets.len() as u32,
+                };
+                if let Ok(page) = self.client.list_assets_page(page).await {
+                    self.assets_total = page.total.max(0) as usize;
+                    self.assets.extend(page.items);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Start fetching the thumbnail of the selected asset if it is not cached yet.
+    ///
+    /// The fetch runs in the background; [`App::receive_previews`] collects the result.
+    pub fn load_preview_if_needed(&mut self) {
+        if self.state != AppState::Assets {
+            return;
+        }
+        let Some(asset) = self.selected_asset() else {
+            return;
+        };
+        if self.previews.contains_key(&asset.asset_id) {
+            return;
+        }
+        match self.preview_fetches.get(&asset.asset_id) {
+            Some(None) => return,
+            Some(Some(retry_at)) if Instant::now() < *retry_at => return,
+            _ => {}
+        }
+
+        let asset_id = asset.asset_id.clone();
+        let Some(url) = asset.thumbnail_url.clone() else {
+            self.cache_preview(asset_id, None);
+            return;
+        };
+        self.preview_fetches.insert(asset_id.clone(), None);
+        let client = self.client.clone();
+        let tx = self.preview_tx.clone();
+        tokio::spawn(async move {
+            let preview = match client.get_thumbnail(&url).await {
+                Ok(bytes) => Some(Thumbnail::decode(bytes).ok()),
+                Err(CliError::NotFound { .. }) => Some(None),
+                Err(_) => None,
+            };
+            // The app may have quit in the meantime
+            let _ = tx.send((asset_id, preview));
+        });
+    }
+
+    /// Cache the thumbnails whose fetches have finished
+    pub fn receive_previews(&mut self) {
+        while let Ok(result) = self.preview_rx.try_recv() {
+            self.finish_preview(result);
+        }
+    }
+
+    fn finish_preview(&mut self, (asset_id, preview): PreviewResult) {
+        match preview {
+            Some(preview) => {
+                self.preview_fetches.remove(&asset_id);
+                self.cache_preview(asset_id, preview);
+            }
+            None => {
+                self.preview_fetches.insert(asset_id, Some(Instant::now() + PREVIEW_RETRY_DELAY));
+            }
+        }
+    }
+
+    fn cache_preview(&mut self, asset_id: String, preview: Option<Thumbnail>) {
+        if self.previews.len() >= MAX_CACHED_PREVIEWS {
+            self.previews.clear();
+        }
+        self.previews.insert(asset_id, preview);
+    }
+
+    fn clamp_selection(&mut self) {
+        self.selected_job_index = self.selected_job_index.min(self.jobs.len().saturating_sub(1));
+        self.selected_asset_index = self.selected_asset_index.min(self.assets.len().saturating_sub(1));
+    }
+
     pub fn should_refresh(&self) -> bool {
         self.last_refresh.elapsed() > Duration::from_secs(5)
     }
@@ -89,15 +237,11 @@ impl App {
 
     pub fn on_up(&mut self) {
         match self.state {
-            AppState::Jobs => {
-                if self.selected_job_index > 0 {
-                    self.selected_job_index -= 1;
-                }
+            AppState::Jobs if self.selected_job_index > 0 => {
+                self.selected_job_index -= 1;
             }
-            AppState::Assets => {
-                if self.selected_asset_index > 0 {
-                    self.selected_asset_index -= 1;
-                }
+            AppState::Assets if self.selected_asset_index > 0 => {
+                self.selected_asset_index -= 1;
             }
             _ => {}
         }
@@ -105,15 +249,11 @@ impl App {
 
     pub fn on_down(&mut self) {
         match self.state {
-            AppState::Jobs => {
-                if self.selected_job_index < self.jobs.len().saturating_sub(1) {
-                    self.selected_job_index += 1;
-                }
+            AppState::Jobs if self.selected_job_index < self.jobs.len().saturating_sub(1) => {
+                self.selected_job_index += 1;
             }
-            AppState::Assets => {
-                if self.selected_asset_index < self.assets.len().saturating_sub(1) {
-                    self.selected_asset_index += 1;
-                }
+            AppState::Assets if self.selected_asset_index < self.assets.len().saturating_sub(1) => {
+                self.selected_asset_index += 1;
             }
             _ => {}
         }
@@ -135,15 +275,15 @@ impl App {
     }
 
     pub fn running_jobs_count(&self) -> usize {
-        self.jobs.iter().filter(|j| j.status == "running").count()
+        self.jobs.iter().filter(|j| j.status == JobStatus::Running).count()
     }
 
     pub fn completed_jobs_count(&self) -> usize {
-        self.jobs.iter().filter(|j| j.status == "completed").count()
+        self.jobs.iter().filter(|j| j.status == JobStatus::Completed).count()
     }
 
     pub fn queued_jobs_count(&self) -> usize {
-        self.jobs.iter().filter(|j| j.status == "queued").count()
+        self.jobs.iter().filter(|j| j.status == JobStatus::Queued).count()
     }
 
     pub fn start_create_job(&mut self) {
@@ -173,6 +313,7 @@ impl App {
             self.job_wizard.job_name.clone(),
             num_scenes,
             config_yaml,
+            None,
         ).await {
             Ok(_job) => {
                 self.status_message = Some("Job created successfully!".to_string());
@@ -208,7 +349,7 @@ impl App {
             let mut results: Vec<_> = self.jobs.iter()
                 .filter(|job| {
                     self.search_box.matches(&job.name) ||
-                    self.search_box.matches(&job.status)
+                    self.search_box.matches(job.status.as_str())
                 })
                 .collect();
 
@@ -230,7 +371,9 @@ impl App {
             let mut results: Vec<_> = self.assets.iter()
                 .filter(|asset| {
                     self.search_box.matches(&asset.name) ||
-                    self.search_box.matches(&asset.file_type)
+                    self.search_box.matches(&asset.file_type) ||
+                    asset.category.as_deref().is_some_and(|c| self.search_box.matches(c)) ||
+                    asset.tags.iter().any(|tag| self.search_box.matches(tag))
                 })
                 .collect();
 
@@ -245,7 +388,168 @@ impl App {
         }
     }
 
+    /// The highlighted row of the assets view
+    pub fn selected_asset(&self) -> Option<&Asset> {
+        self.filtered_assets().get(self.selected_asset_index).copied()
+    }
+
     pub fn clear_status_message(&mut self) {
         self.status_message = None;
     }
 }
+
+/// The first `loaded` rows of a list (at least one page), fetched a page at a time, and the list's total
+async fn reload<T, F, Fut>(loaded: usize, fetch: F) -> Result<(Vec<T>, usize)>
+where
+    F: Fn(PageRequest) -> Fut,
+    Fut: Future<Output = Result<Page<T>>>,
+{
+    let wanted = loaded.max(DEFAULT_PAGE_SIZE as usize);
+    let mut items = Vec::new();
+    let mut offset = Some(0);
+    let mut total = 0;
+    while let Some(next) = offset.filter(|_| items.len() < wanted) {
+        let page = fetch(PageRequest {
+            limit: DEFAULT_PAGE_SIZE,
+            offset: next,
+        })
+        .await?;
+        total = page.total.max(0) as usize;
+        offset = page.next_offset();
+        items.extend(page.items);
+    }
+    Ok((items, total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::fake::{self, FakeApi};
+
+    fn jobs(count: usize) -> Vec<Job> {
+        (0..count)
+            .map(|i| fake::job(&format!("job-{}", i), JobStatus::Running))
+            .collect()
+    }
+
+    /// Wait for every thumbnail fetch in flight to finish
+    async fn wait_for_previews(app: &mut App) {
+        while app.preview_fetches.values().any(Option::is_none) {
+            let Some(result) = app.preview_rx.recv().await else {
+                return;
+            };
+            app.finish_preview(result);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scrolling_loads_next_page() {
+        let api = Arc::new(FakeApi::new().with_jobs(jobs(120)));
+        let mut app = App::with_api(api.clone()).await.unwrap();
+        assert_eq!(app.jobs.len(), DEFAULT_PAGE_SIZE as usize);
+        assert_eq!(app.jobs_total, 120);
+        assert_eq!(app.running_jobs_count(), DEFAULT_PAGE_SIZE as usize);
+
+        app.state = AppState::Jobs;
+        for _ in 0..DEFAULT_PAGE_SIZE {
+            app.on_down();
+            app.load_more_if_needed().await.unwrap();
+        }
+        assert_eq!(app.jobs.len(), 100);
+        assert_eq!(app.selected_job_index, DEFAULT_PAGE_SIZE as usize);
+
+        // Refreshing re-reads what is loaded one page at a time, and keeps it if that fails
+        let before = api.calls().len();
+        app.refresh().await.unwrap();
+        assert_eq!(api.calls()[before..before + 2], ["list_jobs_page 0", "list_jobs_page 50"]);
+        assert_eq!(app.jobs.len(), 100);
+
+        api.fail_nth(2, CliError::Other("connection reset".to_string()));
+        app.refresh().await.unwrap();
+        assert_eq!(app.jobs.len(), 100);
+    }
+
+    #[tokio::test]
+    async fn test_submit_job_switches_to_jobs_view() {
+        let api = Arc::new(FakeApi::new());
+        let mut app = App::with_api(api.clone()).await.unwrap();
+
+        app.start_create_job();
+        app.job_wizard.job_name = "Pallets".to_string();
+        app.job_wizard.num_scenes = "10".to_string();
+        app.submit_job().await.unwrap();
+
+        assert_eq!(app.state, AppState::Jobs);
+        assert_eq!(app.jobs.len(), 1);
+        assert_eq!(app.queued_jobs_count(), 1);
+        assert!(api.calls().contains(&"create_job Pallets".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_failed_submit_stays_in_wizard() {
+        let api = Arc::new(FakeApi::new());
+        let mut app = App::with_api(api.clone()).await.unwrap();
+
+        app.start_create_job();
+        app.job_wizard.job_name = "Pallets".to_string();
+        app.job_wizard.num_scenes = "10".to_string();
+        api.fail_next(CliError::Other("quota exceeded".to_string()));
+        app.submit_job().await.unwrap();
+
+        assert_eq!(app.state, AppState::CreateJob);
+        assert!(app.status_message.unwrap().contains("quota exceeded"));
+    }
+
+    #[tokio::test]
+    async fn test_preview_loads_once_per_asset() {
+        let mut png = Vec::new();
+        image::RgbaImage::from_pixel(4, 4, image::Rgba([0, 128, 255, 255]))
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let with_thumbnail = Asset {
+            thumbnail_url: Some("/thumbs/asset-1.png".to_string()),
+            ..fake::asset("asset-1", "Crate")
+        };
+        let api = Arc::new(
+            FakeApi::new()
+                .with_assets(vec![with_thumbnail, fake::asset("asset-2", "Pallet")])
+                .with_thumbnail("/thumbs/asset-1.png", png),
+        );
+        let mut app = App::with_api(api.clone()).await.unwrap();
+
+        app.state = AppState::Assets;
+        app.load_preview_if_needed();
+        app.load_preview_if_needed();
+        wait_for_previews(&mut app).await;
+        app.load_preview_if_needed();
+        assert!(app.previews["asset-1"].is_some());
+
+        app.on_down();
+        app.load_preview_if_needed();
+        assert!(app.previews["asset-2"].is_none());
+
+        let fetches = api.calls().iter().filter(|call| call.starts_with("get_thumbnail")).count();
+        assert_eq!(fetches, 1);
+    }
+
+    #[tokio::test]
+    async fn test_preview_caches_missing_thumbnails_but_not_failures() {
+        let assets = ["asset-1", "asset-2"].map(|id| Asset {
+            thumbnail_url: Some(format!("/thumbs/{}.png", id)),
+            ..fake::asset(id, "Crate")
+        });
+        let api = Arc::new(FakeApi::new().with_assets(assets.to_vec()));
+        let mut app = App::with_api(api.clone()).await.unwrap();
+
+        app.state = AppState::Assets;
+        api.fail_next(CliError::Other("connection reset".to_string()));
+        app.load_preview_if_needed();
+        wait_for_previews(&mut app).await;
+        assert!(!app.previews.contains_key("asset-1"));
+
+        app.on_down();
+        app.load_preview_if_needed();
+        wait_for_previews(&mut app).await;
+        assert!(app.previews["asset-2"].is_none());
+    }
+}
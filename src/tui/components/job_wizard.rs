@@ -168,7 +168,7 @@ impl JobWizard {
         f.render_widget(help, chunks[3]);
     }
 
-    fn step_indicator(&self, step: u8, current: u8, label: &str) -> Span {
+    fn step_indicator(&self, step: u8, current: u8, label: &str) -> Span<'_> {
         let style = if step == current {
             Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
         } else if step < current {
@@ -177,7 +177,7 @@ impl JobWizard {
             Style::default().fg(Color::Gray)
         };
 
-        Span::styled(format!("{}", label), style)
+        Span::styled(label.to_string(), style)
     }
 
     fn render_job_name_step(&self, f: &mut Frame, area: Rect) {
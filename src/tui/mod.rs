@@ -5,6 +5,7 @@ pub mod components;
 
 pub use app::{App, AppState};
 
+use crate::api::ApiClient;
 use crate::error::Result;
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
@@ -17,8 +18,8 @@ use ratatui::{
 };
 use std::io;
 
-/// Run the TUI application
-pub async fn run() -> Result<()> {
+/// Run the TUI application on top of `client`
+pub async fn run(client: ApiClient) -> Result<()> {
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -27,7 +28,7 @@ pub async fn run() -> Result<()> {
     let mut terminal = Terminal::new(backend)?;
 
     // Create app state
-    let mut app = App::new().await?;
+    let mut app = App::new(client).await?;
 
     // Run app
     let res = run_app(&mut terminal, &mut app).await;
@@ -50,13 +51,15 @@ pub async fn run() -> Result<()> {
 
 async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
     loop {
+        app.receive_previews();
+        app.load_preview_if_needed();
         terminal.draw(|f| ui::draw(f, app))?;
 
         if event::poll(std::time::Duration::from_millis(100))? {
             if let Event::Key(key) = event::read()? {
                 if key.kind == KeyEventKind::Press {
                     // Handle wizard mode separately
-                    if app.state == app::AppState::CreateJob {
+                    if app.state == AppState::CreateJob {
                         match key.code {
                             KeyCode::Esc => {
                                 app.cancel_create_job();
@@ -127,6 +130,7 @@ async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Resul
                         }
                         KeyCode::Down | KeyCode::Char('j') => {
                             app.on_down();
+                            app.load_more_if_needed().await?;
                         }
                         KeyCode::Up | KeyCode::Char('k') => {
                             app.on_up();
@@ -1,12 +1,19 @@
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
-    widgets::{Block, Borders, Cell, Row, Table},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
     Frame,
 };
 
 use crate::tui::app::App;
 
+/// Width of the preview pane, border included
+const PREVIEW_WIDTH: u16 = 44;
+
+/// Narrower terminals only get the table
+const MIN_WIDTH_FOR_PREVIEW: u16 = 120;
+
 pub fn render(f: &mut Frame, app: &App, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -19,12 +26,72 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
     // Render search box
     app.search_box.render(f, chunks[0]);
 
-    // Render table in second chunk
-    render_table(f, app, chunks[1]);
+    if chunks[1].width < MIN_WIDTH_FOR_PREVIEW {
+        render_table(f, app, chunks[1]);
+        return;
+    }
+
+    let panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(PREVIEW_WIDTH)])
+        .split(chunks[1]);
+    render_table(f, app, panes[0]);
+    render_preview(f, app, panes[1]);
+}
+
+/// Thumbnail of the selected asset drawn with half blocks, then its labels
+fn render_preview(f: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default().borders(Borders::ALL).title("Preview");
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let Some(asset) = app.selected_asset() else {
+        return;
+    };
+
+    let label = |name: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{}: ", name), Style::default().fg(Color::DarkGray)),
+            Span::raw(value),
+        ])
+    };
+    let mut details = vec![Line::from(Span::styled(
+        asset.name.clone(),
+        Style::default().add_modifier(Modifier::BOLD),
+    ))];
+    if let Some(category) = &asset.category {
+        details.push(label("Category", category.clone()));
+    }
+    if !asset.tags.is_empty() {
+        details.push(label("Tags", asset.tags.join(", ")));
+    }
+
+    let image_rows = inner.height.saturating_sub(details.len() as u16 + 1);
+    let dim = Style::default().fg(Color::DarkGray);
+    let mut lines: Vec<Line> = match app.previews.get(&asset.asset_id) {
+        None => vec![Line::styled("Loading thumbnail...", dim)],
+        Some(None) => vec![Line::styled("No thumbnail", dim)],
+        Some(Some(thumbnail)) => thumbnail
+            .half_blocks(inner.width, image_rows)
+            .into_iter()
+            .map(|row| {
+                let cells: Vec<Span> = row
+                    .into_iter()
+                    .map(|([r, g, b], [r2, g2, b2])| {
+                        Span::styled("▀", Style::default().fg(Color::Rgb(r, g, b)).bg(Color::Rgb(r2, g2, b2)))
+                    })
+                    .collect();
+                Line::from(cells).alignment(Alignment::Center)
+            })
+            .collect(),
+    };
+    lines.push(Line::default());
+    lines.extend(details);
+
+    f.render_widget(Paragraph::new(lines), inner);
 }
 
 fn render_table(f: &mut Frame, app: &App, area: Rect) {
-    let header_cells = ["ID", "Asset ID", "Name", "Type", "Size"]
+    let header_cells = ["ID", "Asset ID", "Name", "Category", "Tags", "Type", "Size"]
         .iter()
         .map(|h| Cell::from(*h).style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)));
 
@@ -43,6 +110,8 @@ fn render_table(f: &mut Frame, app: &App, area: Rect) {
             Cell::from(asset.id.to_string()),
             Cell::from(asset.asset_id.clone()),
             Cell::from(asset.name.clone()),
+            Cell::from(asset.category.clone().unwrap_or_default()),
+            Cell::from(asset.tags.join(", ")),
             Cell::from(asset.file_type.clone()),
             Cell::from(size_display),
         ];
@@ -61,14 +130,22 @@ fn render_table(f: &mut Frame, app: &App, area: Rect) {
         Constraint::Length(6),
         Constraint::Length(15),
         Constraint::Min(20),
-        Constraint::Length(15),
+        Constraint::Length(14),
+        Constraint::Min(16),
+        Constraint::Length(8),
         Constraint::Length(12),
     ];
 
+    let loaded = if app.assets.len() < app.assets_total {
+        format!(", {} loaded", app.assets.len())
+    } else {
+        String::new()
+    };
+
     let title = if app.search_box.query.is_empty() {
-        format!("Assets ({} total)", app.assets.len())
+        format!("Assets ({} total{})", app.assets_total, loaded)
     } else {
-        format!("Assets ({} of {} total)", filtered_assets.len(), app.assets.len())
+        format!("Assets ({} of {} total{})", filtered_assets.len(), app.assets_total, loaded)
     };
 
     let table = Table::new(rows, widths)
@@ -31,13 +31,13 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
         Line::from(vec![
             Span::styled("Total Jobs: ", Style::default().fg(Color::White)),
             Span::styled(
-                app.jobs.len().to_string(),
+                app.jobs_total.to_string(),
                 Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
             ),
             Span::raw("  "),
             Span::styled("Total Assets: ", Style::default().fg(Color::White)),
             Span::styled(
-                app.assets.len().to_string(),
+                app.assets_total.to_string(),
                 Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
             ),
         ]),
@@ -97,13 +97,7 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
         vec![Line::from("No recent jobs")]
     } else {
         let recent_jobs: Vec<Line> = app.jobs.iter().take(5).map(|job| {
-            let status_color = match job.status.as_str() {
-                "running" => Color::Yellow,
-                "completed" => Color::Green,
-                "failed" => Color::Red,
-                "queued" => Color::Blue,
-                _ => Color::Gray,
-            };
+            let status_color = super::job_status_color(&job.status);
 
             Line::from(vec![
                 Span::styled(
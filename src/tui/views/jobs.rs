@@ -33,19 +33,13 @@ fn render_table(f: &mut Frame, app: &App, area: Rect) {
 
     let filtered_jobs = app.filtered_jobs();
     let rows = filtered_jobs.iter().enumerate().map(|(i, job)| {
-        let status_color = match job.status.as_str() {
-            "running" => Color::Yellow,
-            "completed" => Color::Green,
-            "failed" => Color::Red,
-            "queued" => Color::Blue,
-            _ => Color::Gray,
-        };
+        let status_color = super::job_status_color(&job.status);
 
         let cells = vec![
             Cell::from(job.id.to_string()),
             Cell::from(job.name.clone()),
             Cell::from(Span::styled(
-                job.status.clone(),
+                job.status.to_string(),
                 Style::default().fg(status_color).add_modifier(Modifier::BOLD),
             )),
             Cell::from(job.num_scenes.to_string()),
@@ -70,10 +64,16 @@ fn render_table(f: &mut Frame, app: &App, area: Rect) {
         Constraint::Length(10),
     ];
 
+    let loaded = if app.jobs.len() < app.jobs_total {
+        format!(", {} loaded", app.jobs.len())
+    } else {
+        String::new()
+    };
+
     let title = if app.search_box.query.is_empty() {
-        format!("Jobs ({} total)", app.jobs.len())
+        format!("Jobs ({} total{})", app.jobs_total, loaded)
     } else {
-        format!("Jobs ({} of {} total)", filtered_jobs.len(), app.jobs.len())
+        format!("Jobs ({} of {} total{})", filtered_jobs.len(), app.jobs_total, loaded)
     };
 
     let table = Table::new(rows, widths)
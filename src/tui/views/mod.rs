@@ -2,3 +2,18 @@ pub mod dashboard;
 pub mod assets;
 pub mod jobs;
 pub mod help;
+
+use crate::api::jobs::JobStatus;
+use ratatui::style::Color;
+
+/// Color used for a job status in every view
+pub fn job_status_color(status: &JobStatus) -> Color {
+    match status {
+        JobStatus::Queued => Color::Blue,
+        JobStatus::Running => Color::Yellow,
+        JobStatus::Paused => Color::Magenta,
+        JobStatus::Completed => Color::Green,
+        JobStatus::Failed => Color::Red,
+        JobStatus::Cancelled | JobStatus::Unknown(_) => Color::Gray,
+    }
+}
@@ -0,0 +1,311 @@
+use super::source::Source;
+use super::stats::{image_size, MeshStats, TextureStats, UpAxis};
+use super::{Bounds, Report};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::Value;
+use std::io;
+
+const CHUNK_JSON: &[u8; 4] = b"JSON";
+const CHUNK_BIN: &[u8; 4] = b"BIN\0";
+
+fn u32_at(bytes: &[u8], offset: usize) -> Option<u32> {
+    let field = bytes.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes(field.try_into().ok()?))
+}
+
+/// Binary glTF: 12-byte header, a JSON chunk and an optional BIN chunk
+pub(super) fn check_glb(report: &mut Report, source: &Source) -> io::Result<()> {
+    let header = source.head(12)?;
+    let (Some(version), Some(length)) = (u32_at(&header, 4), u32_at(&header, 8)) else {
+        report.error("GLB header is truncated");
+        return Ok(());
+    };
+    if version != 2 {
+        report.error(format!("Unsupported GLB version {}", version));
+        return Ok(());
+    }
+
+    let length = u64::from(length);
+    if length > source.len() {
+        report.error(format!("GLB is truncated ({} of {} bytes)", source.len(), length));
+        return Ok(());
+    }
+    if length < source.len() {
+        report.warn(format!("{} bytes of trailing data after the GLB", source.len() - length));
+    }
+
+    let json = match chunk(source, length, 12)? {
+        Some((kind, data_length)) if &kind == CHUNK_JSON => source.read_at(12 + 8, data_length)?,
+        Some(_) => {
+            report.error("GLB does not start with a JSON chunk");
+            return Ok(());
+        }
+        None => {
+            report.error("GLB JSON chunk is truncated");
+            return Ok(());
+        }
+    };
+
+    let bin_offset = 12 + 8 + json.len() as u64;
+    let bin = match chunk(source, length, bin_offset)? {
+        Some((kind, data_length)) if &kind == CHUNK_BIN => Some(BinChunk {
+            source,
+            offset: bin_offset + 8,
+            len: data_length,
+        }),
+        _ if bin_offset >= length => None,
+        _ => {
+            report.error("GLB BIN chunk is truncated or malformed");
+            return Ok(());
+        }
+    };
+
+    check_document(report, &json, bin.as_ref());
+    Ok(())
+}
+
+/// A chunk's type and data length at `offset`, if it fits before `end`
+fn chunk(source: &Source, end: u64, offset: u64) -> io::Result<Option<([u8; 4], u64)>> {
+    if offset + 8 > end {
+        return Ok(None);
+    }
+    let header = source.read_at(offset, 8)?;
+    let (Some(length), Some(kind)) = (u32_at(&header, 0), header.get(4..8)) else {
+        return Ok(None);
+    };
+    let length = u64::from(length);
+    if offset + 8 + length > end {
+        return Ok(None);
+    }
+    Ok(kind.try_into().ok().map(|kind| (kind, length)))
+}
+
+/// Where the GLB BIN chunk's data sits in the file; read only for embedded images
+struct BinChunk<'a> {
+    source: &'a Source,
+    offset: u64,
+    len: u64,
+}
+
+impl BinChunk<'_> {
+    /// `length` bytes at `offset` within the chunk, if they are inside it and readable
+    fn read(&self, offset: u64, length: u64) -> Option<Vec<u8>> {
+        if offset.checked_add(length)? > self.len {
+            return None;
+        }
+        self.source.read_at(self.offset + offset, length).ok()
+    }
+}
+
+/// JSON glTF, with buffers and images in external files or data URIs
+pub(super) fn check_gltf(report: &mut Report, source: &Source) -> io::Result<()> {
+    check_document(report, &source.read_all()?, None);
+    Ok(())
+}
+
+/// Check the glTF JSON; `bin` is the GLB BIN chunk, if any
+fn check_document(report: &mut Report, json: &[u8], bin: Option<&BinChunk>) {
+    let doc: Value = match serde_json::from_slice(json) {
+        Ok(doc) => doc,
+        Err(e) => {
+            report.error(format!("Invalid glTF JSON: {}", e));
+            return;
+        }
+    };
+
+    match doc.pointer("/asset/version").and_then(Value::as_str) {
+        Some(version) if version.starts_with("2.") => {}
+        Some(version) => {
+            report.error(format!("Unsupported glTF version {}", version));
+            return;
+        }
+        None => {
+            report.error("glTF is missing asset.version");
+            return;
+        }
+    }
+
+    let base = report.path.clone();
+    for (i, buffer) in array(&doc, "buffers").iter().enumerate() {
+        let expected = buffer.get("byteLength").and_then(Value::as_u64).unwrap_or(0);
+        match buffer.get("uri").and_then(Value::as_str) {
+            Some(uri) if uri.starts_with("data:") => {}
+            Some(uri) => {
+                let Some(path) = report.check_reference(&base, "buffer", &decode_uri(uri)) else {
+                    continue;
+                };
+                let actual = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                if actual < expected {
+                    report.error(format!("Buffer '{}' is truncated ({} of {} bytes)", uri, actual, expected));
+                }
+            }
+            // Only the first buffer of a GLB may omit its URI; it is the BIN chunk
+            None => match bin.map(|bin| bin.len) {
+                Some(actual) if i == 0 && actual < expected => {
+                    report.error(format!("GLB BIN chunk is truncated ({} of {} bytes)", actual, expected));
+                }
+                Some(_) if i == 0 => {}
+                _ => report.error(format!("Buffer {} has no data", i)),
+            },
+        }
+    }
+
+    let mut stats = MeshStats {
+        materials: array(&doc, "materials").len() as u64,
+        ..MeshStats::default()
+    };
+    for (i, image) in array(&doc, "images").iter().enumerate() {
+        let uri = image.get("uri").and_then(Value::as_str);
+        let label = uri
+            .filter(|uri| !uri.starts_with("data:"))
+            .map(decode_uri)
+            .or_else(|| image.get("name").and_then(Value::as_str).map(str::to_string))
+            .unwrap_or_else(|| format!("image {}", i));
+        match uri {
+            Some(uri) if uri.starts_with("data:") => {
+                let data = uri.split_once(";base64,").and_then(|(_, data)| STANDARD.decode(data).ok());
+                stats.add_texture(TextureStats::new(&label, data.and_then(|data| image_size(&data))));
+            }
+            Some(_) => {
+                let path = report.check_reference(&base, "texture", &label);
+                stats.add_texture(TextureStats::from_file(&label, path.as_deref()));
+            }
+            // Embedded in a buffer view, which in a GLB points into the BIN chunk
+            None => {
+                let data = image
+                    .get("bufferView")
+                    .and_then(Value::as_u64)
+                    .and_then(|view| array(&doc, "bufferViews").get(view as usize))
+                    .and_then(|view| {
+                        let offset = view.get("byteOffset").and_then(Value::as_u64).unwrap_or(0);
+                        let length = view.get("byteLength").and_then(Value::as_u64)?;
+                        bin?.read(offset, length)
+                    });
+                stats.add_texture(TextureStats::new(&label, data.and_then(|data| image_size(&data))));
+            }
+        }
+    }
+
+    // Positions are in metres by spec, and their accessors must carry min/max
+    let accessors = array(&doc, "accessors");
+    let views = array(&doc, "bufferViews");
+    let counts: Vec<Option<u64>> = accessors
+        .iter()
+        .enumerate()
+        .map(|(i, accessor)| {
+            let count = accessor.get("count").and_then(Value::as_u64)?;
+            match accessor_capacity(accessor, views) {
+                Some(capacity) if count > capacity => {
+                    report.error(format!(
+                        "Accessor {} has {} elements but its buffer view only holds {}",
+                        i, count, capacity
+                    ));
+                    Some(capacity)
+                }
+                _ => Some(count),
+            }
+        })
+        .collect();
+    let count = |index: Option<u64>| index.and_then(|index| counts.get(index as usize).copied().flatten());
+    let mut bounds = Bounds::default();
+    let (mut primitives, mut with_normals, mut with_uvs) = (0, 0, 0);
+    for mesh in array(&doc, "meshes") {
+        for primitive in mesh.get("primitives").and_then(Value::as_array).into_iter().flatten() {
+            let Some(index) = primitive.pointer("/attributes/POSITION").and_then(Value::as_u64) else {
+                continue;
+            };
+            primitives += 1;
+            with_normals += usize::from(primitive.pointer("/attributes/NORMAL").is_some());
+            with_uvs += usize::from(primitive.pointer("/attributes/TEXCOORD_0").is_some());
+
+            let vertices = count(Some(index)).unwrap_or(0);
+            let corners = count(primitive.get("indices").and_then(Value::as_u64)).unwrap_or(vertices);
+            stats.vertices += vertices;
+            // Modes: 4 triangles (the default), 5 strip, 6 fan; the rest are points and lines
+            stats.triangles += match primitive.get("mode").and_then(Value::as_u64).unwrap_or(4) {
+                4 => corners / 3,
+                5 | 6 => corners.saturating_sub(2),
+                _ => 0,
+            };
+
+            if let Some(accessor) = accessors.get(index as usize) {
+                for key in ["min", "max"] {
+                    if let Some(point) = vec3(accessor.get(key)) {
+                        bounds.add(point);
+                    }
+                }
+            }
+        }
+    }
+    report.check_size(&bounds);
+
+    stats.has_normals = primitives > 0 && with_normals == primitives;
+    stats.has_uvs = primitives > 0 && with_uvs == primitives;
+    stats.set_bounds(&bounds, UpAxis::Y, true);
+    report.mesh = Some(stats);
+}
+
+/// How many elements fit in an accessor's buffer view; `None` if it has none or is malformed
+fn accessor_capacity(accessor: &Value, views: &[Value]) -> Option<u64> {
+    let view = views.get(accessor.get("bufferView")?.as_u64()? as usize)?;
+    let view_length = view.get("byteLength").and_then(Value::as_u64)?;
+    let offset = accessor.get("byteOffset").and_then(Value::as_u64).unwrap_or(0);
+    let component: u64 = match accessor.get("componentType").and_then(Value::as_u64)? {
+        5120 | 5121 => 1,
+        5122 | 5123 => 2,
+        5125 | 5126 => 4,
+        _ => return None,
+    };
+    let components: u64 = match accessor.get("type").and_then(Value::as_str)? {
+        "SCALAR" => 1,
+        "VEC2" => 2,
+        "VEC3" => 3,
+        "VEC4" | "MAT2" => 4,
+        "MAT3" => 9,
+        "MAT4" => 16,
+        _ => return None,
+    };
+    let element = component * components;
+    let stride = view.get("byteStride").and_then(Value::as_u64).unwrap_or(element).max(element);
+    match view_length.saturating_sub(offset) {
+        available if available < element => Some(0),
+        available => Some((available - element) / stride + 1),
+    }
+}
+
+/// Relative URIs are percent-encoded, e.g. `oak%20planks.png`
+fn decode_uri(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn array<'a>(doc: &'a Value, key: &str) -> &'a [Value] {
+    doc.get(key).and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default()
+}
+
+fn vec3(value: Option<&Value>) -> Option<[f64; 3]> {
+    let values = value?.as_array()?;
+    match values.as_slice() {
+        [x, y, z] => Some([x.as_f64()?, y.as_f64()?, z.as_f64()?]),
+        _ => None,
+    }
+}
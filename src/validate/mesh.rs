@@ -0,0 +1,550 @@
+use super::source::{lines, Source, SCAN_LIMIT};
+use super::stats::{MeshStats, UpAxis};
+use super::{Bounds, Report};
+use std::io::{self, BufRead, Read};
+
+pub(super) const FBX_MAGIC: &[u8] = b"Kaydara FBX Binary  \0";
+
+/// Size of the binary FBX header: magic, two unknown bytes and the version
+const FBX_HEADER_LEN: u64 = 27;
+
+/// FBX `UnitScaleFactor` is centimetres per unit; outside this range it is almost certainly wrong
+const FBX_UNIT_SCALE_RANGE: (f64, f64) = (0.01, 10_000.0);
+
+/// USD `metersPerUnit` outside this range is almost certainly wrong
+const USD_METERS_PER_UNIT_RANGE: (f64, f64) = (0.0001, 1000.0);
+
+/// Binary STL: 80-byte header, triangle count, then 50 bytes per triangle
+const STL_HEADER_LEN: u64 = 84;
+const STL_TRIANGLE_LEN: u64 = 50;
+
+/// Zip local file header, up to the file name
+const ZIP_ENTRY_HEADER_LEN: u64 = 30;
+
+/// The zip end of central directory record and the longest comment it can carry
+const ZIP_EOCD_SEARCH_LEN: u64 = 22 + 65_535;
+
+fn f32_at(bytes: &[u8], offset: usize) -> f32 {
+    f32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap_or_default())
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+fn u64_at(bytes: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(bytes.get(offset..offset + 8)?.try_into().ok()?))
+}
+
+/// FBX, binary or ASCII.
+///
+/// The header extension and global settings, which hold the unit scale, come
+/// first in the file, so only its start is searched for them.
+pub(super) fn check_fbx(report: &mut Report, source: &Source) -> io::Result<()> {
+    let head = source.head(SCAN_LIMIT)?;
+    if !head.starts_with(FBX_MAGIC) {
+        let text = String::from_utf8_lossy(&head);
+        if !text.contains("FBXHeaderExtension") {
+            report.error("Not a valid FBX file");
+            return Ok(());
+        }
+        let scale = text
+            .lines()
+            .find(|line| line.contains("\"UnitScaleFactor\""))
+            .and_then(|line| line.rsplit(',').next())
+            .and_then(|value| value.trim().parse().ok());
+        check_fbx_scale(report, scale);
+        return Ok(());
+    }
+
+    let Some(version) = u32_at(&head, 23) else {
+        report.error("FBX header is truncated");
+        return Ok(());
+    };
+
+    // Walk the top-level node records; each starts with the offset where it ends,
+    // and the list is closed by an all-zero record
+    let wide = version >= 7500;
+    let mut offset = FBX_HEADER_LEN;
+    loop {
+        let end = if wide {
+            u64_at(&source.read_at(offset, 8)?, 0)
+        } else {
+            u32_at(&source.read_at(offset, 4)?, 0).map(u64::from)
+        };
+        match end {
+            Some(0) => break,
+            Some(end) if end > offset && end <= source.len() => offset = end,
+            _ => {
+                report.error(format!("FBX is truncated or corrupt at byte {}", offset));
+                return Ok(());
+            }
+        }
+    }
+
+    check_fbx_scale(report, binary_fbx_unit_scale(&head));
+    Ok(())
+}
+
+/// Value of the `UnitScaleFactor` property in a binary FBX.
+///
+/// Properties70 entries are `P: "UnitScaleFactor", "double", "Number", "", <D value>`,
+/// encoded as typed fields; skip the strings and read the double.
+fn binary_fbx_unit_scale(bytes: &[u8]) -> Option<f64> {
+    let name = b"UnitScaleFactor";
+    let mut offset = bytes.windows(name.len()).position(|w| w == name)? + name.len();
+    for _ in 0..4 {
+        match bytes.get(offset)? {
+            b'S' => offset += 5 + u32_at(bytes, offset + 1)? as usize,
+            b'D' => return Some(f64::from_le_bytes(bytes.get(offset + 1..offset + 9)?.try_into().ok()?)),
+            _ => return None,
+        }
+    }
+    None
+}
+
+fn check_fbx_scale(report: &mut Report, scale: Option<f64>) {
+    if let Some(scale) = scale {
+        if !(FBX_UNIT_SCALE_RANGE.0..=FBX_UNIT_SCALE_RANGE.1).contains(&scale) {
+            report.warn(format!("FBX unit scale of {} cm per unit is implausible", scale));
+        }
+    }
+}
+
+/// USD layer, crate (binary) or ASCII
+pub(super) fn check_usd(report: &mut Report, source: &Source) -> io::Result<()> {
+    // Layer metadata such as metersPerUnit opens an ASCII layer
+    let head = source.head(SCAN_LIMIT)?;
+    if head.starts_with(b"PXR-USDC") {
+        // Version at 8, table of contents offset at 16
+        match u64_at(&head, 16) {
+            Some(toc) if toc >= 24 && toc < source.len() => {}
+            _ => report.error("USD crate file is truncated (table of contents is missing)"),
+        }
+        return Ok(());
+    }
+
+    let text = String::from_utf8_lossy(&head);
+    let meters_per_unit = text
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("metersPerUnit"))
+        .and_then(|rest| rest.trim_start().strip_prefix('='))
+        .and_then(|value| value.trim().parse::<f64>().ok());
+    if let Some(meters) = meters_per_unit {
+        if !(USD_METERS_PER_UNIT_RANGE.0..=USD_METERS_PER_UNIT_RANGE.1).contains(&meters) {
+            report.warn(format!("USD metersPerUnit of {} is implausible", meters));
+        }
+    }
+    Ok(())
+}
+
+/// USDZ package: an uncompressed zip whose first entry is a USD layer
+pub(super) fn check_usdz(report: &mut Report, source: &Source) -> io::Result<()> {
+    // The end of central directory record sits in the last 22 + 65535 bytes
+    let tail = source.tail(ZIP_EOCD_SEARCH_LEN)?;
+    if !tail.windows(4).any(|w| w == b"PK\x05\x06") {
+        report.error("USDZ archive is truncated (no central directory)");
+        return Ok(());
+    }
+
+    let header = source.head(ZIP_ENTRY_HEADER_LEN)?;
+    if header.len() < ZIP_ENTRY_HEADER_LEN as usize {
+        report.error("USDZ archive is truncated");
+        return Ok(());
+    }
+    let method = u16::from_le_bytes([header[8], header[9]]);
+    let name_len = u16::from_le_bytes([header[26], header[27]]);
+    let name = source.read_at(ZIP_ENTRY_HEADER_LEN, u64::from(name_len))?;
+    let name = if name.len() == usize::from(name_len) {
+        String::from_utf8_lossy(&name).into_owned()
+    } else {
+        String::new()
+    };
+
+    if method != 0 {
+        report.error("USDZ entries must be stored uncompressed");
+    }
+    if ![".usd", ".usda", ".usdc"].iter().any(|ext| name.ends_with(ext)) {
+        report.error(format!("USDZ must start with a USD layer, found '{}'", name));
+    }
+    Ok(())
+}
+
+/// Scalar type of a PLY property
+#[derive(Clone, Copy)]
+enum PlyType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl PlyType {
+    fn parse(name: &str) -> Option<PlyType> {
+        match name {
+            "char" | "int8" => Some(PlyType::I8),
+            "uchar" | "uint8" => Some(PlyType::U8),
+            "short" | "int16" => Some(PlyType::I16),
+            "ushort" | "uint16" => Some(PlyType::U16),
+            "int" | "int32" => Some(PlyType::I32),
+            "uint" | "uint32" => Some(PlyType::U32),
+            "float" | "float32" => Some(PlyType::F32),
+            "double" | "float64" => Some(PlyType::F64),
+            _ => None,
+        }
+    }
+
+    fn size(self) -> usize {
+        match self {
+            PlyType::I8 | PlyType::U8 => 1,
+            PlyType::I16 | PlyType::U16 => 2,
+            PlyType::I32 | PlyType::U32 | PlyType::F32 => 4,
+            PlyType::F64 => 8,
+        }
+    }
+
+    /// Decode a value at the start of `bytes`
+    fn read(self, bytes: &[u8], big_endian: bool) -> Option<f64> {
+        let mut raw = bytes.get(..self.size())?.to_vec();
+        if big_endian {
+            raw.reverse();
+        }
+        let value = match self {
+            PlyType::I8 => f64::from(raw[0] as i8),
+            PlyType::U8 => f64::from(raw[0]),
+            PlyType::I16 => f64::from(i16::from_le_bytes(raw.try_into().ok()?)),
+            PlyType::U16 => f64::from(u16::from_le_bytes(raw.try_into().ok()?)),
+            PlyType::I32 => f64::from(i32::from_le_bytes(raw.try_into().ok()?)),
+            PlyType::U32 => f64::from(u32::from_le_bytes(raw.try_into().ok()?)),
+            PlyType::F32 => f64::from(f32::from_le_bytes(raw.try_into().ok()?)),
+            PlyType::F64 => f64::from_le_bytes(raw.try_into().ok()?),
+        };
+        Some(value)
+    }
+
+    /// Read and decode the next value from `reader`
+    fn read_from(self, mut reader: impl Read, big_endian: bool) -> io::Result<f64> {
+        let mut raw = [0; 8];
+        let raw = &mut raw[..self.size()];
+        reader.read_exact(raw)?;
+        self.read(raw, big_endian).ok_or_else(|| io::ErrorKind::InvalidData.into())
+    }
+}
+
+enum PlyProperty {
+    Scalar { name: String, kind: PlyType },
+    /// A count followed by that many items, e.g. the vertex indices of a face
+    List { count: PlyType, item: PlyType },
+}
+
+struct PlyElement {
+    name: String,
+    count: u64,
+    properties: Vec<PlyProperty>,
+}
+
+impl PlyElement {
+    fn has_property(&self, names: &[&str]) -> bool {
+        self.properties
+            .iter()
+            .any(|p| matches!(p, PlyProperty::Scalar { name, .. } if names.contains(&name.as_str())))
+    }
+}
+
+/// Stanford PLY: header declares elements, then ASCII lines or binary records
+pub(super) fn check_ply(report: &mut Report, source: &Source) -> io::Result<()> {
+    let marker = b"end_header";
+    let mut reader = source.reader_at(0)?.take(SCAN_LIMIT);
+    let mut header = Vec::new();
+    let mut line = Vec::new();
+    let body_start = loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            report.error("PLY header is incomplete (no end_header)");
+            return Ok(());
+        }
+        if let Some(marker_at) = line.windows(marker.len()).position(|w| w == marker) {
+            let body_start = (header.len() + line.len()) as u64;
+            header.extend_from_slice(&line[..marker_at]);
+            break body_start;
+        }
+        header.extend_from_slice(&line);
+    };
+    let header = String::from_utf8_lossy(&header);
+
+    let mut format = None;
+    let mut elements: Vec<PlyElement> = Vec::new();
+    for line in header.lines() {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        match tokens.as_slice() {
+            ["format", kind, ..] => format = Some(kind.to_string()),
+            ["element", name, count] => elements.push(PlyElement {
+                name: name.to_string(),
+                count: count.parse().unwrap_or(0),
+                properties: Vec::new(),
+            }),
+            ["property", "list", count_type, item_type, _] => {
+                let (Some(element), Some(count), Some(item)) =
+                    (elements.last_mut(), PlyType::parse(count_type), PlyType::parse(item_type))
+                else {
+                    report.error(format!("Malformed PLY header line '{}'", line));
+                    return Ok(());
+                };
+                element.properties.push(PlyProperty::List { count, item });
+            }
+            ["property", kind, name] => {
+                let (Some(element), Some(kind)) = (elements.last_mut(), PlyType::parse(kind)) else {
+                    report.error(format!("Malformed PLY header line '{}'", line));
+                    return Ok(());
+                };
+                element.properties.push(PlyProperty::Scalar {
+                    name: name.to_string(),
+                    kind,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    let body = source.reader_at(body_start)?;
+    let body_len = source.len().saturating_sub(body_start);
+    let mut bounds = Bounds::default();
+    let mut stats = MeshStats::default();
+    match format.as_deref() {
+        Some("ascii") => {
+            let mut lines = lines(body).filter(|line| !line.as_ref().is_ok_and(|line| line.trim().is_empty()));
+            let expected: u64 = elements.iter().map(|e| e.count).sum();
+            let mut seen = 0u64;
+            for element in &elements {
+                for _ in 0..element.count {
+                    let Some(line) = lines.next().transpose()? else {
+                        report.error(format!("PLY is truncated ({} of {} records)", seen, expected));
+                        return Ok(());
+                    };
+                    seen += 1;
+                    let mut tokens = line.split_whitespace().map(|v| v.parse::<f64>().ok());
+                    let mut values = Vec::with_capacity(element.properties.len());
+                    for property in &element.properties {
+                        match property {
+                            PlyProperty::Scalar { .. } => values.push(tokens.next().flatten()),
+                            PlyProperty::List { .. } => {
+                                let items = tokens.next().flatten().unwrap_or(0.0) as u64;
+                                tokens.by_ref().take(items as usize).for_each(drop);
+                                add_ply_list(&mut stats, element, items);
+                                values.push(None);
+                            }
+                        }
+                    }
+                    add_ply_record(&mut bounds, element, &values);
+                }
+            }
+        }
+        Some(kind @ ("binary_little_endian" | "binary_big_endian")) => {
+            // Lists make records variable-length; count each at its minimum (an empty list)
+            let minimum: u64 = elements
+                .iter()
+                .map(|e| {
+                    let record: usize = e
+                        .properties
+                        .iter()
+                        .map(|p| match p {
+                            PlyProperty::Scalar { kind, .. } => kind.size(),
+                            PlyProperty::List { count, .. } => count.size(),
+                        })
+                        .sum();
+                    e.count * record as u64
+                })
+                .sum();
+            if body_len < minimum {
+                report.error(format!("PLY is truncated ({} of at least {} bytes of data)", body_len, minimum));
+                return Ok(());
+            }
+            match read_binary_ply(body, &elements, kind == "binary_big_endian", &mut bounds, &mut stats) {
+                Ok(()) => {}
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                    report.error("PLY is truncated (data ends inside a record)");
+                    return Ok(());
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Some(other) => {
+            report.error(format!("Unknown PLY format '{}'", other));
+            return Ok(());
+        }
+        None => {
+            report.error("PLY header has no format line");
+            return Ok(());
+        }
+    }
+    report.check_size(&bounds);
+
+    if let Some(vertex) = elements.iter().find(|e| e.name == "vertex") {
+        stats.vertices = vertex.count;
+        stats.has_normals = vertex.has_property(&["nx"]);
+        stats.has_uvs = vertex.has_property(&["u", "s", "texture_u"]);
+    }
+    stats.set_bounds(&bounds, UpAxis::Y, false);
+    report.mesh = Some(stats);
+    Ok(())
+}
+
+/// Walk every binary record; fails with `UnexpectedEof` if the data ends inside one
+fn read_binary_ply(
+    mut body: impl Read,
+    elements: &[PlyElement],
+    big_endian: bool,
+    bounds: &mut Bounds,
+    stats: &mut MeshStats,
+) -> io::Result<()> {
+    for element in elements {
+        let mut values = Vec::with_capacity(element.properties.len());
+        for _ in 0..element.count {
+            values.clear();
+            for property in &element.properties {
+                match property {
+                    PlyProperty::Scalar { kind, .. } => {
+                        values.push(Some(kind.read_from(&mut body, big_endian)?));
+                    }
+                    PlyProperty::List { count, item } => {
+                        let items = count.read_from(&mut body, big_endian)? as u64;
+                        let skip = items * item.size() as u64;
+                        if io::copy(&mut (&mut body).take(skip), &mut io::sink())? < skip {
+                            return Err(io::ErrorKind::UnexpectedEof.into());
+                        }
+                        add_ply_list(stats, element, items);
+                        values.push(None);
+                    }
+                }
+            }
+            add_ply_record(bounds, element, &values);
+        }
+    }
+    Ok(())
+}
+
+/// A face with `items` vertices is a fan of triangles
+fn add_ply_list(stats: &mut MeshStats, element: &PlyElement, items: u64) {
+    if element.name == "face" {
+        stats.triangles += items.saturating_sub(2);
+    }
+}
+
+/// Grow the bounds by a vertex record's position
+fn add_ply_record(bounds: &mut Bounds, element: &PlyElement, values: &[Option<f64>]) {
+    if element.name == "vertex" {
+        if let Some(point) = ply_position(element, |i| values.get(i).copied().flatten()) {
+            bounds.add(point);
+        }
+    }
+}
+
+/// x, y, z of a vertex, given a lookup from property index to value
+fn ply_position(element: &PlyElement, value: impl Fn(usize) -> Option<f64>) -> Option<[f64; 3]> {
+    let mut point = [None; 3];
+    for (i, property) in element.properties.iter().enumerate() {
+        if let PlyProperty::Scalar { name, .. } = property {
+            let axis = match name.as_str() {
+                "x" => 0,
+                "y" => 1,
+                "z" => 2,
+                _ => continue,
+            };
+            point[axis] = value(i);
+        }
+    }
+    Some([point[0]?, point[1]?, point[2]?])
+}
+
+/// STL, binary or ASCII
+pub(super) fn check_stl(report: &mut Report, source: &Source) -> io::Result<()> {
+    let head = source.head(STL_HEADER_LEN)?;
+    let binary_count = u32_at(&head, 80).map(u64::from);
+    let is_binary = binary_count.is_some_and(|n| STL_HEADER_LEN + n * STL_TRIANGLE_LEN == source.len());
+    let looks_ascii = head.trim_ascii_start().starts_with(b"solid") && !is_binary;
+
+    let mut bounds = Bounds::default();
+    if looks_ascii {
+        let (mut facets, mut last) = (0u64, None);
+        for line in lines(source.reader_at(0)?) {
+            let line = line?;
+            let trimmed = line.trim_start();
+            if let Some(rest) = trimmed.strip_prefix("vertex") {
+                let coords: Vec<f64> = rest.split_whitespace().filter_map(|t| t.parse().ok()).collect();
+                if let [x, y, z] = coords[..] {
+                    bounds.add([x, y, z]);
+                }
+            } else if trimmed.starts_with("facet") {
+                facets += 1;
+            }
+            if !trimmed.is_empty() {
+                last = Some(line);
+            }
+        }
+        if !last.is_some_and(|line| line.trim_start().starts_with("endsolid")) {
+            report.error("ASCII STL is truncated (no endsolid)");
+        }
+        if bounds.size().is_none() {
+            report.error("STL has no triangles");
+        }
+        report.check_size(&bounds);
+        set_stl_stats(report, &bounds, facets);
+        return Ok(());
+    }
+
+    let Some(count) = binary_count else {
+        report.error("STL header is truncated");
+        return Ok(());
+    };
+    if count == 0 {
+        report.error("STL has no triangles");
+        return Ok(());
+    }
+
+    let present = (source.len() - STL_HEADER_LEN) / STL_TRIANGLE_LEN;
+    if present < count {
+        report.error(format!("STL is truncated ({} of {} triangles)", present, count));
+    } else if !is_binary {
+        report.warn(format!(
+            "{} bytes of trailing data after {} triangles",
+            source.len() - STL_HEADER_LEN - count * STL_TRIANGLE_LEN,
+            count
+        ));
+    }
+
+    let mut triangles = source.reader_at(STL_HEADER_LEN)?;
+    let mut triangle = [0u8; STL_TRIANGLE_LEN as usize];
+    for _ in 0..present.min(count) {
+        triangles.read_exact(&mut triangle)?;
+        // Normal first, then three vertices
+        for vertex in 0..3 {
+            let at = 12 + vertex * 12;
+            bounds.add([
+                f64::from(f32_at(&triangle, at)),
+                f64::from(f32_at(&triangle, at + 4)),
+                f64::from(f32_at(&triangle, at + 8)),
+            ]);
+        }
+    }
+    report.check_size(&bounds);
+    set_stl_stats(report, &bounds, present.min(count));
+    Ok(())
+}
+
+/// STL stores unshared vertices and a normal per facet, but no UVs or materials.
+///
+/// CAD tools that export STL are mostly Z-up.
+fn set_stl_stats(report: &mut Report, bounds: &Bounds, triangles: u64) {
+    let mut stats = MeshStats {
+        vertices: 3 * triangles,
+        triangles,
+        has_normals: true,
+        ..MeshStats::default()
+    };
+    stats.set_bounds(bounds, UpAxis::Z, false);
+    report.mesh = Some(stats);
+}
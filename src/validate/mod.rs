@@ -0,0 +1,500 @@
+//! Local checks run on asset files before they are uploaded.
+//!
+//! Formats are detected from magic bytes, falling back to the file extension.
+//! Each format's headers are parsed far enough to catch truncated files,
+//! external buffers, materials and textures that are missing from disk, and
+//! models whose size suggests they were exported in the wrong units. Meshes
+//! that can be read (glTF/GLB, OBJ, PLY and STL) also get their geometry
+//! measured along the way.
+//!
+//! Files are read piecemeal: headers and chunk tables by offset, text and
+//! vertex data as a stream, so validating a large asset does not load it
+//! into memory.
+
+mod gltf;
+mod mesh;
+mod obj;
+mod source;
+mod stats;
+mod texture;
+
+pub use stats::{LengthUnit, MeshBounds, MeshStats, TextureStats, UpAxis};
+
+use crate::error::{CliError, Result};
+use serde::Serialize;
+use source::Source;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Enough leading bytes to recognise every format's signature
+const MAGIC_LEN: u64 = 16;
+
+/// Smallest and largest plausible model size in metres; outside this range the
+/// model was most likely exported in the wrong units
+const PLAUSIBLE_SIZE_METRES: (f64, f64) = (0.001, 1000.0);
+
+/// Asset file formats the validator recognises
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AssetFormat {
+    Gltf,
+    Glb,
+    Obj,
+    Fbx,
+    Usd,
+    Usdz,
+    Ply,
+    Stl,
+    Png,
+    Jpeg,
+    Ktx2,
+    Webp,
+    Tga,
+    Exr,
+    Hdr,
+    /// External `.bin` buffer of a `.gltf`
+    GltfBuffer,
+    /// `.mtl` material library of an `.obj`
+    ObjMaterial,
+}
+
+impl AssetFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AssetFormat::Gltf => "glTF",
+            AssetFormat::Glb => "GLB",
+            AssetFormat::Obj => "OBJ",
+            AssetFormat::Fbx => "FBX",
+            AssetFormat::Usd => "USD",
+            AssetFormat::Usdz => "USDZ",
+            AssetFormat::Ply => "PLY",
+            AssetFormat::Stl => "STL",
+            AssetFormat::Png => "PNG",
+            AssetFormat::Jpeg => "JPEG",
+            AssetFormat::Ktx2 => "KTX2",
+            AssetFormat::Webp => "WebP",
+            AssetFormat::Tga => "TGA",
+            AssetFormat::Exr => "OpenEXR",
+            AssetFormat::Hdr => "Radiance HDR",
+            AssetFormat::GltfBuffer => "glTF buffer",
+            AssetFormat::ObjMaterial => "MTL",
+        }
+    }
+
+    /// Format implied by a file extension
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        let format = match ext.to_ascii_lowercase().as_str() {
+            "gltf" => AssetFormat::Gltf,
+            "glb" => AssetFormat::Glb,
+            "obj" => AssetFormat::Obj,
+            "fbx" => AssetFormat::Fbx,
+            "usd" | "usda" | "usdc" => AssetFormat::Usd,
+            "usdz" => AssetFormat::Usdz,
+            "ply" => AssetFormat::Ply,
+            "stl" => AssetFormat::Stl,
+            "png" => AssetFormat::Png,
+            "jpg" | "jpeg" => AssetFormat::Jpeg,
+            "ktx2" => AssetFormat::Ktx2,
+            "webp" => AssetFormat::Webp,
+            "tga" => AssetFormat::Tga,
+            "exr" => AssetFormat::Exr,
+            "hdr" => AssetFormat::Hdr,
+            "bin" => AssetFormat::GltfBuffer,
+            "mtl" => AssetFormat::ObjMaterial,
+            _ => return None,
+        };
+        Some(format)
+    }
+
+    /// Format identified by a file's leading bytes, for formats that have a signature
+    pub fn from_magic(bytes: &[u8]) -> Option<Self> {
+        let format = if bytes.starts_with(b"glTF") {
+            AssetFormat::Glb
+        } else if bytes.starts_with(mesh::FBX_MAGIC) {
+            AssetFormat::Fbx
+        } else if bytes.starts_with(b"PXR-USDC") || bytes.starts_with(b"#usda") {
+            AssetFormat::Usd
+        } else if bytes.starts_with(b"ply\n") || bytes.starts_with(b"ply\r\n") {
+            AssetFormat::Ply
+        } else if bytes.starts_with(texture::PNG_MAGIC) {
+            AssetFormat::Png
+        } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            AssetFormat::Jpeg
+        } else if bytes.starts_with(texture::KTX2_MAGIC) {
+            AssetFormat::Ktx2
+        } else if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+            AssetFormat::Webp
+        } else if bytes.starts_with(&[0x76, 0x2F, 0x31, 0x01]) {
+            AssetFormat::Exr
+        } else if bytes.starts_with(b"#?RADIANCE") || bytes.starts_with(b"#?RGBE") {
+            AssetFormat::Hdr
+        } else {
+            return None;
+        };
+        Some(format)
+    }
+
+    /// Whether files of this format always start with a signature
+    fn has_magic(&self) -> bool {
+        matches!(
+            self,
+            AssetFormat::Glb
+                | AssetFormat::Usd
+                | AssetFormat::Usdz
+                | AssetFormat::Ply
+                | AssetFormat::Png
+                | AssetFormat::Jpeg
+                | AssetFormat::Ktx2
+                | AssetFormat::Webp
+                | AssetFormat::Exr
+                | AssetFormat::Hdr
+        )
+    }
+
+    pub fn is_texture(&self) -> bool {
+        matches!(
+            self,
+            AssetFormat::Png
+                | AssetFormat::Jpeg
+                | AssetFormat::Ktx2
+                | AssetFormat::Webp
+                | AssetFormat::Tga
+                | AssetFormat::Exr
+                | AssetFormat::Hdr
+        )
+    }
+}
+
+impl fmt::Display for AssetFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Worth a look, but the file can still be uploaded
+    Warning,
+    /// The file is broken or unsupported and should not be uploaded
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Issue {
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Result of validating one file
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub path: PathBuf,
+    pub format: Option<AssetFormat>,
+    pub issues: Vec<Issue>,
+    /// Geometry metrics, for mesh formats whose geometry could be read
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mesh: Option<MeshStats>,
+}
+
+impl Report {
+    /// Whether the file has no errors (warnings are allowed)
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &Issue> {
+        self.issues.iter().filter(|i| i.severity == Severity::Error)
+    }
+
+    fn error(&mut self, message: impl Into<String>) {
+        self.issues.push(Issue {
+            severity: Severity::Error,
+            message: message.into(),
+        });
+    }
+
+    fn warn(&mut self, message: impl Into<String>) {
+        self.issues.push(Issue {
+            severity: Severity::Warning,
+            message: message.into(),
+        });
+    }
+
+    /// Flag a referenced file (buffer, material, texture) that is not on disk
+    fn check_reference(&mut self, base: &Path, kind: &str, reference: &str) -> Option<PathBuf> {
+        let path = base.parent().unwrap_or(Path::new("")).join(reference);
+        if path.is_file() {
+            Some(path)
+        } else {
+            self.error(format!("Missing {} '{}'", kind, reference));
+            None
+        }
+    }
+
+    /// Warn when a model's largest dimension, taken as metres, suggests it is in the wrong units
+    fn check_size(&mut self, bounds: &Bounds) {
+        let Some(metres) = bounds.size() else {
+            return;
+        };
+        if metres > PLAUSIBLE_SIZE_METRES.1 {
+            self.warn(format!(
+                "Model is {:.0} m across; was it exported in millimetres or centimetres?",
+                metres
+            ));
+        } else if metres > 0.0 && metres < PLAUSIBLE_SIZE_METRES.0 {
+            self.warn(format!("Model is only {:.5} m across; check its units", metres));
+        }
+    }
+}
+
+/// Axis-aligned bounds of the vertices seen so far
+#[derive(Debug, Clone, Copy)]
+struct Bounds {
+    min: [f64; 3],
+    max: [f64; 3],
+}
+
+impl Default for Bounds {
+    fn default() -> Self {
+        Bounds {
+            min: [f64::INFINITY; 3],
+            max: [f64::NEG_INFINITY; 3],
+        }
+    }
+}
+
+impl Bounds {
+    fn add(&mut self, point: [f64; 3]) {
+        for (axis, value) in point.into_iter().enumerate() {
+            if value.is_finite() {
+                self.min[axis] = self.min[axis].min(value);
+                self.max[axis] = self.max[axis].max(value);
+            }
+        }
+    }
+
+    /// Largest dimension, or `None` if no vertices were added
+    fn size(&self) -> Option<f64> {
+        (0..3)
+            .map(|axis| self.max[axis] - self.min[axis])
+            .filter(|extent| extent.is_finite())
+            .reduce(f64::max)
+    }
+}
+
+/// Detect a file's format and check it.
+///
+/// Problems with the file itself are reported in the [`Report`]; only failing
+/// to read it returns an error. This does blocking file I/O; async callers
+/// should use [`validate_file_async`].
+pub fn validate_file(path: &Path) -> Result<Report> {
+    let source = Source::open(path)?;
+    let mut report = Report {
+        path: path.to_path_buf(),
+        format: None,
+        issues: Vec::new(),
+        mesh: None,
+    };
+
+    if source.len() == 0 {
+        report.error("File is empty");
+        return Ok(report);
+    }
+
+    let ext = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+    let by_extension = AssetFormat::from_extension(ext);
+    let head = source.head(MAGIC_LEN)?;
+    let by_magic = match AssetFormat::from_magic(&head) {
+        // Zip archives are only assets when they are USDZ packages
+        None if head.starts_with(b"PK\x03\x04") && by_extension == Some(AssetFormat::Usdz) => {
+            Some(AssetFormat::Usdz)
+        }
+        format => format,
+    };
+
+    report.format = match (by_magic, by_extension) {
+        (Some(magic), Some(extension)) if magic != extension => {
+            report.error(format!(
+                "File extension is .{} but the contents are {}",
+                ext, magic
+            ));
+            Some(magic)
+        }
+        (Some(magic), _) => Some(magic),
+        (None, Some(extension)) if extension.has_magic() => {
+            report.error(format!("Not a valid {} file (missing signature)", extension));
+            return Ok(report);
+        }
+        (None, Some(extension)) => Some(extension),
+        (None, None) => {
+            report.error("Unsupported file format");
+            return Ok(report);
+        }
+    };
+
+    match report.format {
+        Some(AssetFormat::Glb) => gltf::check_glb(&mut report, &source)?,
+        Some(AssetFormat::Gltf) => gltf::check_gltf(&mut report, &source)?,
+        Some(AssetFormat::Obj) => obj::check_obj(&mut report, &source)?,
+        Some(AssetFormat::ObjMaterial) => {
+            obj::check_mtl(&mut report, path, &source)?;
+        }
+        Some(AssetFormat::Fbx) => mesh::check_fbx(&mut report, &source)?,
+        Some(AssetFormat::Usd) => mesh::check_usd(&mut report, &source)?,
+        Some(AssetFormat::Usdz) => mesh::check_usdz(&mut report, &source)?,
+        Some(AssetFormat::Ply) => mesh::check_ply(&mut report, &source)?,
+        Some(AssetFormat::Stl) => mesh::check_stl(&mut report, &source)?,
+        Some(format) if format.is_texture() => texture::check(&mut report, format, &source)?,
+        _ => {}
+    }
+
+    Ok(report)
+}
+
+/// [`validate_file`] on the blocking thread pool, so it does not stall the async runtime
+pub async fn validate_file_async(path: PathBuf) -> Result<Report> {
+    tokio::task::spawn_blocking(move || validate_file(&path))
+        .await
+        .map_err(|e| CliError::Other(format!("Validation did not finish: {}", e)))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(report: &Report) -> Vec<&str> {
+        report.issues.iter().map(|i| i.message.as_str()).collect()
+    }
+
+    #[test]
+    fn test_extension_mismatch_and_unknown_format() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("photo.png"), [0xFF, 0xD8, 0xFF, 0xE0, 0xFF, 0xD9]).unwrap();
+        std::fs::write(dir.path().join("notes.docx"), b"hello").unwrap();
+
+        let report = validate_file(&dir.path().join("photo.png")).unwrap();
+        assert_eq!(report.format, Some(AssetFormat::Jpeg));
+        assert_eq!(messages(&report), ["File extension is .png but the contents are JPEG"]);
+
+        let report = validate_file(&dir.path().join("notes.docx")).unwrap();
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn test_gltf_accessor_count_is_bounded_by_its_buffer_view() {
+        let dir = tempfile::tempdir().unwrap();
+        let gltf = r#"{
+            "asset": {"version": "2.0"},
+            "buffers": [{"uri": "data:application/octet-stream;base64,", "byteLength": 36}],
+            "bufferViews": [{"buffer": 0, "byteLength": 36}],
+            "accessors": [{"bufferView": 0, "componentType": 5126, "type": "VEC3", "count": 99999999999,
+                           "min": [0, 0, 0], "max": [1, 1, 0]}],
+            "meshes": [{"primitives": [{"attributes": {"POSITION": 0}}]}]
+        }"#;
+        std::fs::write(dir.path().join("tri.gltf"), gltf).unwrap();
+
+        let report = validate_file(&dir.path().join("tri.gltf")).unwrap();
+        assert_eq!(
+            messages(&report),
+            ["Accessor 0 has 99999999999 elements but its buffer view only holds 3"]
+        );
+        let mesh = report.mesh.unwrap();
+        assert_eq!((mesh.vertices, mesh.triangles), (3, 1));
+    }
+
+    #[test]
+    fn test_obj_missing_material_and_truncated_faces() {
+        let dir = tempfile::tempdir().unwrap();
+        let obj = "mtllib chair.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\nf 1 2 4\n";
+        std::fs::write(dir.path().join("chair.obj"), obj).unwrap();
+
+        let report = validate_file(&dir.path().join("chair.obj")).unwrap();
+        assert_eq!(
+            messages(&report),
+            [
+                "Missing material library 'chair.mtl'",
+                "Face references vertex 4 but only 3 are defined; the file may be truncated",
+            ]
+        );
+
+        std::fs::write(dir.path().join("chair.mtl"), "newmtl oak\nmap_Kd -s 1 1 1 textures/oak.png\n").unwrap();
+        let report = validate_file(&dir.path().join("chair.obj")).unwrap();
+        assert_eq!(messages(&report)[0], "Missing texture 'textures/oak.png'");
+    }
+
+    #[test]
+    fn test_truncated_glb_and_binary_stl() {
+        let dir = tempfile::tempdir().unwrap();
+        let json = br#"{"asset":{"version":"2.0"}} "#;
+        let mut glb = Vec::new();
+        glb.extend_from_slice(b"glTF");
+        glb.extend_from_slice(&2u32.to_le_bytes());
+        glb.extend_from_slice(&(12 + 8 + json.len() as u32 + 100).to_le_bytes());
+        glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+        glb.extend_from_slice(b"JSON");
+        glb.extend_from_slice(json);
+        std::fs::write(dir.path().join("box.glb"), &glb).unwrap();
+
+        let report = validate_file(&dir.path().join("box.glb")).unwrap();
+        assert_eq!(messages(&report), ["GLB is truncated (48 of 148 bytes)"]);
+
+        // Header claims two triangles but only one is present, and it is 5 km across
+        let mut stl = vec![0u8; 80];
+        stl.extend_from_slice(&2u32.to_le_bytes());
+        stl.extend_from_slice(&[0u8; 12]);
+        for vertex in [[0.0f32, 0.0, 0.0], [5000.0, 0.0, 0.0], [0.0, 1.0, 0.0]] {
+            for coord in vertex {
+                stl.extend_from_slice(&coord.to_le_bytes());
+            }
+        }
+        stl.extend_from_slice(&[0u8; 2]);
+        std::fs::write(dir.path().join("rack.stl"), &stl).unwrap();
+
+        let report = validate_file(&dir.path().join("rack.stl")).unwrap();
+        assert_eq!(
+            messages(&report),
+            [
+                "STL is truncated (1 of 2 triangles)",
+                "Model is 5000 m across; was it exported in millimetres or centimetres?",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mesh_stats_for_obj_and_binary_ply() {
+        let dir = tempfile::tempdir().unwrap();
+        // A chair modelled in millimetres, standing on the ground along Y
+        let obj = "mtllib chair.mtl\nv 0 0 -225\nv 500 0 -225\nv 500 1200 225\nv 0 1200 225\n\
+                   vt 0 0\nvn 0 0 1\nusemtl oak\nf 1/1/1 2/1/1 3/1/1 4/1/1\n";
+        std::fs::write(dir.path().join("chair.obj"), obj).unwrap();
+        std::fs::write(dir.path().join("chair.mtl"), "newmtl oak\nmap_Kd oak.png\nnewmtl steel\n").unwrap();
+        image::RgbaImage::new(4, 2).save(dir.path().join("oak.png")).unwrap();
+
+        let mesh = validate_file(&dir.path().join("chair.obj")).unwrap().mesh.unwrap();
+        assert_eq!((mesh.vertices, mesh.triangles, mesh.materials), (4, 2, 2));
+        assert!(mesh.has_normals && mesh.has_uvs && !mesh.from_spec);
+        assert_eq!(mesh.textures, [TextureStats::new("oak.png", Some((4, 2)))]);
+        assert_eq!((mesh.units, mesh.up_axis), (LengthUnit::Millimetres, UpAxis::Y));
+        assert_eq!(mesh.bounds.unwrap().size(), [0.5, 1.2, 0.45]);
+
+        // One quad face, resting on the ground along Z
+        let mut ply = b"ply\nformat binary_little_endian 1.0\nelement vertex 4\n\
+                        property float x\nproperty float y\nproperty float z\n\
+                        element face 1\nproperty list uchar int vertex_indices\nend_header\n"
+            .to_vec();
+        for coord in [0.0f32, -1.0, 0.0, 1.0, -1.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 2.0] {
+            ply.extend_from_slice(&coord.to_le_bytes());
+        }
+        ply.push(4);
+        for index in 0..4i32 {
+            ply.extend_from_slice(&index.to_le_bytes());
+        }
+        std::fs::write(dir.path().join("crate.ply"), &ply).unwrap();
+
+        let mesh = validate_file(&dir.path().join("crate.ply")).unwrap().mesh.unwrap();
+        assert_eq!((mesh.vertices, mesh.triangles), (4, 2));
+        assert!(!mesh.has_normals && !mesh.has_uvs);
+        assert_eq!((mesh.units, mesh.up_axis), (LengthUnit::Metres, UpAxis::Z));
+    }
+}
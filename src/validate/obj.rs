@@ -0,0 +1,116 @@
+use super::source::{lines, Source};
+use super::stats::{MeshStats, TextureStats, UpAxis};
+use super::{Bounds, Report};
+use std::collections::HashSet;
+use std::io;
+use std::path::Path;
+
+/// Material statements whose last argument is a texture file
+const TEXTURE_STATEMENTS: &[&str] = &[
+    "map_Ka", "map_Kd", "map_Ks", "map_Ke", "map_Ns", "map_d", "map_Bump", "map_bump", "bump", "disp", "decal",
+    "refl", "norm", "map_Pr", "map_Pm",
+];
+
+/// Wavefront OBJ: vertices, faces and the material libraries they use
+pub(super) fn check_obj(report: &mut Report, source: &Source) -> io::Result<()> {
+    let base = report.path.clone();
+
+    let mut bounds = Bounds::default();
+    let mut stats = MeshStats::default();
+    let mut max_index = 0u64;
+    let mut used_materials = HashSet::new();
+    let mut library_materials = 0;
+    for line in lines(source.reader_at(0)?) {
+        let line = line?;
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("v") => {
+                stats.vertices += 1;
+                let coords: Vec<f64> = tokens.take(3).filter_map(|t| t.parse().ok()).collect();
+                if let [x, y, z] = coords[..] {
+                    bounds.add([x, y, z]);
+                }
+            }
+            Some("vn") => stats.has_normals = true,
+            Some("vt") => stats.has_uvs = true,
+            Some("f") => {
+                // `f 1/2/3 4//6 -1`: the first number is the vertex; negative ones are relative
+                let mut corners = 0u64;
+                for vertex in tokens {
+                    corners += 1;
+                    let index = vertex.split('/').next().and_then(|i| i.parse::<i64>().ok());
+                    if let Some(index) = index.filter(|i| *i > 0) {
+                        max_index = max_index.max(index as u64);
+                    }
+                }
+                // Polygons are fans of triangles
+                stats.triangles += corners.saturating_sub(2);
+            }
+            Some("usemtl") => {
+                used_materials.extend(tokens.next().map(str::to_string));
+            }
+            Some("mtllib") => {
+                // File names may not contain spaces, so each token is one library
+                for library in tokens {
+                    if let Some(path) = report.check_reference(&base, "material library", library) {
+                        let mtl = Source::open(&path).and_then(|mtl| check_mtl(report, &path, &mtl));
+                        if let Ok((materials, textures)) = mtl {
+                            library_materials += materials;
+                            for texture in textures {
+                                stats.add_texture(texture);
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    let vertices = stats.vertices;
+
+    if vertices == 0 {
+        report.error("OBJ has no vertices");
+        return Ok(());
+    }
+    if max_index > vertices {
+        report.error(format!(
+            "Face references vertex {} but only {} are defined; the file may be truncated",
+            max_index, vertices
+        ));
+    }
+    report.check_size(&bounds);
+
+    // Without its libraries, the materials the faces use are the best count there is
+    stats.materials = if library_materials > 0 { library_materials } else { used_materials.len() as u64 };
+    stats.set_bounds(&bounds, UpAxis::Y, false);
+    report.mesh = Some(stats);
+    Ok(())
+}
+
+/// Material library: every referenced texture must exist next to it.
+///
+/// Returns the number of materials defined and the textures they use.
+pub(super) fn check_mtl(report: &mut Report, path: &Path, source: &Source) -> io::Result<(u64, Vec<TextureStats>)> {
+    let mut materials = 0;
+    let mut textures: Vec<TextureStats> = Vec::new();
+    for line in lines(source.reader_at(0)?) {
+        let line = line?;
+        let mut tokens = line.split_whitespace();
+        let Some(statement) = tokens.next() else {
+            continue;
+        };
+        if statement == "newmtl" {
+            materials += 1;
+        }
+        // Options such as `-s 1 1 1` come first; the file name is last
+        if TEXTURE_STATEMENTS.contains(&statement) {
+            if let Some(texture) = tokens.last() {
+                let found = report.check_reference(path, "texture", texture);
+                if !textures.iter().any(|t| t.path == texture) {
+                    textures.push(TextureStats::from_file(texture, found.as_deref()));
+                }
+            }
+        }
+    }
+    Ok((materials, textures))
+}
@@ -0,0 +1,67 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// How far into a file to look for settings stored near its start, such as FBX and USD units
+pub(super) const SCAN_LIMIT: u64 = 1024 * 1024;
+
+/// A file being validated. Checks read the parts they need instead of the whole file,
+/// since assets can be gigabytes.
+pub(super) struct Source {
+    file: File,
+    len: u64,
+}
+
+impl Source {
+    pub(super) fn open(path: &Path) -> io::Result<Source> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len();
+        Ok(Source { file, len })
+    }
+
+    pub(super) fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Up to `len` bytes starting at `offset`; fewer if the file ends first
+    pub(super) fn read_at(&self, offset: u64, len: u64) -> io::Result<Vec<u8>> {
+        let mut file = &self.file;
+        file.seek(SeekFrom::Start(offset))?;
+        let mut bytes = Vec::with_capacity(len.min(self.len.saturating_sub(offset)) as usize);
+        file.take(len).read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Up to the first `len` bytes
+    pub(super) fn head(&self, len: u64) -> io::Result<Vec<u8>> {
+        self.read_at(0, len)
+    }
+
+    /// Up to the last `len` bytes
+    pub(super) fn tail(&self, len: u64) -> io::Result<Vec<u8>> {
+        self.read_at(self.len.saturating_sub(len), len)
+    }
+
+    /// The whole file, for formats that can only be checked as a whole (glTF JSON)
+    pub(super) fn read_all(&self) -> io::Result<Vec<u8>> {
+        self.read_at(0, self.len)
+    }
+
+    /// Buffered reader from `offset` to the end of the file
+    pub(super) fn reader_at(&self, offset: u64) -> io::Result<BufReader<&File>> {
+        let mut file = &self.file;
+        file.seek(SeekFrom::Start(offset))?;
+        Ok(BufReader::new(file))
+    }
+}
+
+/// Lines of a text file, decoded as leniently as `String::from_utf8_lossy`
+pub(super) fn lines(reader: impl BufRead) -> impl Iterator<Item = io::Result<String>> {
+    reader.split(b'\n').map(|line| {
+        let mut line = line?;
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        Ok(String::from_utf8_lossy(&line).into_owned())
+    })
+}
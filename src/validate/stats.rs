@@ -0,0 +1,191 @@
+use super::{Bounds, PLAUSIBLE_SIZE_METRES};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+/// KTX2 header up to the pixel width and height
+const KTX2_HEADER_LEN: u64 = 28;
+
+/// A model resting on the ground has its lowest point on the up axis within this
+/// fraction of its size from zero
+const GROUND_TOLERANCE: f64 = 0.01;
+
+/// Axis a model treats as up
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpAxis {
+    #[default]
+    Y,
+    Z,
+}
+
+impl UpAxis {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UpAxis::Y => "Y",
+            UpAxis::Z => "Z",
+        }
+    }
+}
+
+/// Length unit of a model's coordinates
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LengthUnit {
+    #[default]
+    Metres,
+    Centimetres,
+    Millimetres,
+}
+
+impl LengthUnit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LengthUnit::Metres => "metres",
+            LengthUnit::Centimetres => "centimetres",
+            LengthUnit::Millimetres => "millimetres",
+        }
+    }
+
+    pub fn metres_per_unit(&self) -> f64 {
+        match self {
+            LengthUnit::Metres => 1.0,
+            LengthUnit::Centimetres => 0.01,
+            LengthUnit::Millimetres => 0.001,
+        }
+    }
+
+    /// Units that make a model `size` file units across a plausible size, preferring metres.
+    ///
+    /// Of the smaller units, the one that brings the model closest to a metre wins.
+    fn guess(size: f64) -> LengthUnit {
+        if size <= PLAUSIBLE_SIZE_METRES.1 {
+            return LengthUnit::Metres;
+        }
+        [LengthUnit::Millimetres, LengthUnit::Centimetres]
+            .into_iter()
+            .map(|unit| (unit, size * unit.metres_per_unit()))
+            .filter(|(_, metres)| (PLAUSIBLE_SIZE_METRES.0..=PLAUSIBLE_SIZE_METRES.1).contains(metres))
+            .min_by(|(_, a), (_, b)| a.ln().abs().total_cmp(&b.ln().abs()))
+            .map_or(LengthUnit::Metres, |(unit, _)| unit)
+    }
+}
+
+/// Axis-aligned bounding box in metres
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MeshBounds {
+    pub min: [f64; 3],
+    pub max: [f64; 3],
+}
+
+impl MeshBounds {
+    pub fn size(&self) -> [f64; 3] {
+        [0, 1, 2].map(|axis| self.max[axis] - self.min[axis])
+    }
+}
+
+/// A texture a mesh uses, with its resolution when the image header could be read
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextureStats {
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+}
+
+impl TextureStats {
+    /// Read the resolution of a texture file on disk, if it exists and is an image we know
+    pub(super) fn from_file(label: &str, path: Option<&Path>) -> TextureStats {
+        TextureStats::new(label, path.and_then(file_image_size))
+    }
+
+    pub(super) fn new(label: &str, size: Option<(u32, u32)>) -> TextureStats {
+        TextureStats {
+            path: label.to_string(),
+            width: size.map(|(width, _)| width),
+            height: size.map(|(_, height)| height),
+        }
+    }
+}
+
+/// Width and height of an encoded image, read from its header
+pub(super) fn image_size(bytes: &[u8]) -> Option<(u32, u32)> {
+    if bytes.starts_with(super::texture::KTX2_MAGIC) {
+        let u32_at = |offset: usize| Some(u32::from_le_bytes(bytes.get(offset..offset + 4)?.try_into().ok()?));
+        return Some((u32_at(20)?, u32_at(24)?));
+    }
+    image::ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()
+}
+
+/// [`image_size`] of a file, reading no more of it than the header
+fn file_image_size(path: &Path) -> Option<(u32, u32)> {
+    let mut head = Vec::new();
+    File::open(path).ok()?.take(KTX2_HEADER_LEN).read_to_end(&mut head).ok()?;
+    if head.starts_with(super::texture::KTX2_MAGIC) {
+        return image_size(&head);
+    }
+    image::ImageReader::open(path).ok()?.with_guessed_format().ok()?.into_dimensions().ok()
+}
+
+/// Geometry metrics of a mesh file, gathered while validating it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MeshStats {
+    pub vertices: u64,
+    pub triangles: u64,
+    pub materials: u64,
+    pub textures: Vec<TextureStats>,
+    pub has_normals: bool,
+    pub has_uvs: bool,
+    /// Bounds converted to metres from `units`; `None` for a mesh without vertices
+    pub bounds: Option<MeshBounds>,
+    pub up_axis: UpAxis,
+    /// Units of the file's coordinates
+    pub units: LengthUnit,
+    /// Whether the format defines the up axis and units (glTF); otherwise both are guessed
+    pub from_spec: bool,
+}
+
+impl MeshStats {
+    /// Fill in bounds, units and up axis from the vertex bounds in file units.
+    ///
+    /// Formats without a specification get `convention` as their up axis unless the model
+    /// clearly rests on the ground along the other candidate axis.
+    pub(super) fn set_bounds(&mut self, bounds: &Bounds, convention: UpAxis, from_spec: bool) {
+        self.from_spec = from_spec;
+        let Some(size) = bounds.size() else {
+            self.up_axis = convention;
+            return;
+        };
+
+        self.units = if from_spec { LengthUnit::Metres } else { LengthUnit::guess(size) };
+        self.up_axis = if from_spec {
+            convention
+        } else {
+            let grounded = |axis: usize| bounds.min[axis].abs() <= size * GROUND_TOLERANCE;
+            match (grounded(1), grounded(2)) {
+                (true, false) => UpAxis::Y,
+                (false, true) => UpAxis::Z,
+                _ => convention,
+            }
+        };
+
+        let scale = self.units.metres_per_unit();
+        self.bounds = Some(MeshBounds {
+            min: bounds.min.map(|value| value * scale),
+            max: bounds.max.map(|value| value * scale),
+        });
+    }
+
+    /// Add a texture unless one with the same path is already listed
+    pub(super) fn add_texture(&mut self, texture: TextureStats) {
+        if !self.textures.iter().any(|t| t.path == texture.path) {
+            self.textures.push(texture);
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use super::source::Source;
+use super::{AssetFormat, Report};
+use std::io;
+
+pub(super) const PNG_MAGIC: &[u8] = b"\x89PNG\r\n\x1a\n";
+pub(super) const KTX2_MAGIC: &[u8] = b"\xABKTX 20\xBB\r\n\x1a\n";
+
+/// KTX2 header up to and including the level index of the first mip level
+const KTX2_MIN_LEN: u64 = 80 + 24;
+
+/// How much of a JPEG's end to search for the end-of-image marker past any padding
+const JPEG_TAIL_LEN: u64 = 64 * 1024;
+
+/// Texture images: the signature has been checked, so look for truncation
+pub(super) fn check(report: &mut Report, format: AssetFormat, source: &Source) -> io::Result<()> {
+    match format {
+        AssetFormat::Png => {
+            // IHDR is always first, IEND always last (length, "IEND", CRC)
+            let header = source.head(24)?;
+            let (Some(width), Some(height)) = (be_u32(&header, 16), be_u32(&header, 20)) else {
+                report.error("PNG header is truncated");
+                return Ok(());
+            };
+            if width == 0 || height == 0 {
+                report.error(format!("PNG has invalid dimensions {}x{}", width, height));
+            }
+            if source.tail(8)?.get(..4) != Some(b"IEND") {
+                report.error("PNG is truncated (no IEND chunk)");
+            }
+        }
+        AssetFormat::Jpeg => {
+            // Some encoders pad the file after the end-of-image marker
+            let tail = source.tail(JPEG_TAIL_LEN)?;
+            let trimmed = tail.iter().rposition(|b| *b != 0).map_or(&tail[..], |last| &tail[..=last]);
+            if !trimmed.ends_with(&[0xFF, 0xD9]) {
+                report.error("JPEG is truncated (no end-of-image marker)");
+            }
+        }
+        AssetFormat::Webp => {
+            let riff_len = source
+                .head(8)?
+                .get(4..8)
+                .and_then(|b| b.try_into().ok())
+                .map(u32::from_le_bytes)
+                .unwrap_or(0);
+            let expected = u64::from(riff_len) + 8;
+            if expected > source.len() {
+                report.error(format!("WebP is truncated ({} of {} bytes)", source.len(), expected));
+            }
+        }
+        AssetFormat::Ktx2 if source.len() < KTX2_MIN_LEN => report.error("KTX2 header is truncated"),
+        _ => {}
+    }
+    Ok(())
+}
+
+fn be_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(offset..offset + 4)?.try_into().ok()?))
+}